@@ -6,11 +6,20 @@ pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
     pub jwt_expiration: i64,
+    pub download_url_ttl: i64,
     pub server_host: String,
     pub server_port: u16,
     pub cors_origin: String,
     pub rom_storage_path: String,
     pub save_storage_path: String,
+    pub media_storage_path: String,
+    pub patch_storage_path: String,
+    pub bios_storage_path: String,
+    pub document_storage_path: String,
+    pub shader_storage_path: String,
+    pub emulatorjs_path: String,
+    pub emulatorjs_version: Option<String>,
+    pub retroarch_rom_root: String,
     pub max_rom_size_mb: usize,
     pub rate_limit_per_minute: usize,
 }
@@ -26,6 +35,10 @@ impl Config {
                 .unwrap_or_else(|_| "86400".to_string())
                 .parse()
                 .unwrap_or(86400),
+            download_url_ttl: env::var("DOWNLOAD_URL_TTL")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
             server_host: env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
             server_port: env::var("SERVER_PORT")
                 .unwrap_or_else(|_| "8080".to_string())
@@ -34,6 +47,14 @@ impl Config {
             cors_origin: env::var("CORS_ORIGIN").unwrap_or_else(|_| "http://localhost:5173".to_string()),
             rom_storage_path: env::var("ROM_STORAGE_PATH").unwrap_or_else(|_| "../storage/roms".to_string()),
             save_storage_path: env::var("SAVE_STORAGE_PATH").unwrap_or_else(|_| "../storage/saves".to_string()),
+            media_storage_path: env::var("MEDIA_STORAGE_PATH").unwrap_or_else(|_| "../storage/media".to_string()),
+            patch_storage_path: env::var("PATCH_STORAGE_PATH").unwrap_or_else(|_| "../storage/patches".to_string()),
+            bios_storage_path: env::var("BIOS_STORAGE_PATH").unwrap_or_else(|_| "../storage/bios".to_string()),
+            document_storage_path: env::var("DOCUMENT_STORAGE_PATH").unwrap_or_else(|_| "../storage/documents".to_string()),
+            shader_storage_path: env::var("SHADER_STORAGE_PATH").unwrap_or_else(|_| "../storage/shaders".to_string()),
+            emulatorjs_path: env::var("EMULATORJS_PATH").unwrap_or_else(|_| "../storage/emulatorjs".to_string()),
+            emulatorjs_version: env::var("EMULATORJS_VERSION").ok().filter(|v| !v.is_empty()),
+            retroarch_rom_root: env::var("RETROARCH_ROM_ROOT").unwrap_or_else(|_| "/storage/roms".to_string()),
             max_rom_size_mb: env::var("MAX_ROM_SIZE_MB")
                 .unwrap_or_else(|_| "100".to_string())
                 .parse()
@@ -18,10 +18,51 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
         ("002_consoles_games.sql", include_str!("migrations/002_consoles_games.sql")),
         ("003_save_states.sql", include_str!("migrations/003_save_states.sql")),
         ("004_achievements.sql", include_str!("migrations/004_achievements.sql")),
+        ("005_game_search.sql", include_str!("migrations/005_game_search.sql")),
+        ("006_game_metadata.sql", include_str!("migrations/006_game_metadata.sql")),
+        ("007_game_media.sql", include_str!("migrations/007_game_media.sql")),
+        ("008_gamelist.sql", include_str!("migrations/008_gamelist.sql")),
+        ("009_rom_checksums.sql", include_str!("migrations/009_rom_checksums.sql")),
+        ("010_metadata_db.sql", include_str!("migrations/010_metadata_db.sql")),
+        ("011_collections.sql", include_str!("migrations/011_collections.sql")),
+        ("012_smart_collections.sql", include_str!("migrations/012_smart_collections.sql")),
+        ("013_reviews.sql", include_str!("migrations/013_reviews.sql")),
+        ("014_tags.sql", include_str!("migrations/014_tags.sql")),
+        ("015_rom_patches.sql", include_str!("migrations/015_rom_patches.sql")),
+        ("016_bios.sql", include_str!("migrations/016_bios.sql")),
+        ("017_console_config.sql", include_str!("migrations/017_console_config.sql")),
+        ("018_game_visibility.sql", include_str!("migrations/018_game_visibility.sql")),
+        ("019_game_titles.sql", include_str!("migrations/019_game_titles.sql")),
+        ("020_game_cheats.sql", include_str!("migrations/020_game_cheats.sql")),
+        ("021_game_documents.sql", include_str!("migrations/021_game_documents.sql")),
+        ("022_input_profiles.sql", include_str!("migrations/022_input_profiles.sql")),
+        ("023_settings_overrides.sql", include_str!("migrations/023_settings_overrides.sql")),
+        ("024_shader_presets.sql", include_str!("migrations/024_shader_presets.sql")),
+        ("025_rom_modified_at.sql", include_str!("migrations/025_rom_modified_at.sql")),
     ];
 
+    // Applied migrations are recorded so seed data only goes in once, otherwise seeded rows an
+    // admin deleted would come back on every start. Databases created before this table existed
+    // run everything once more, which the migrations tolerate, and are tracked from then on.
+    sqlx::raw_sql(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            name VARCHAR(255) PRIMARY KEY,
+            applied_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    let applied: Vec<String> = sqlx::query_scalar("SELECT name FROM schema_migrations")
+        .fetch_all(pool)
+        .await?;
+
     for (name, migration) in migrations.iter() {
+        if applied.iter().any(|a| a == name) {
+            continue;
+        }
         log::info!("Executing migration {}...", name);
+        let mut tx = pool.begin().await?;
 
         // Execute the entire migration file using raw SQL
         // This handles multiple statements properly
@@ -43,11 +84,17 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
             // Log the statement being executed
             log::debug!("Executing SQL: {}", &cleaned[..cleaned.len().min(100)]);
             // Execute raw SQL
-            sqlx::raw_sql(cleaned).execute(pool).await.map_err(|e| {
+            sqlx::raw_sql(cleaned).execute(&mut *tx).await.map_err(|e| {
                 log::error!("Failed to execute SQL: {}\nError: {:?}", cleaned, e);
                 e
             })?;
         }
+
+        sqlx::query("INSERT INTO schema_migrations (name) VALUES ($1)")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
     }
 
     log::info!("Migrations completed successfully");
@@ -0,0 +1,23 @@
+-- Migration: Game Search
+-- Full-text and trigram search over the game library
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS region VARCHAR(100);
+
+-- Backfill region from No-Intro style filenames, e.g. "Sonic (USA, Europe).md"
+UPDATE games
+SET region = substring(rom_filename from '\(([^)]*)\)')
+WHERE region IS NULL;
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(developer, '')), 'B') ||
+        setweight(to_tsvector('simple', coalesce(description, '')), 'C')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_games_search_vector ON games USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_games_title_trgm ON games USING GIN (title gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_games_release_year ON games(release_year);
+CREATE INDEX IF NOT EXISTS idx_games_created ON games(created_at DESC);
@@ -0,0 +1,25 @@
+-- Migration: Game Metadata Editing
+-- Admin role, field locks and edit history for game metadata
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT false;
+
+-- The first account on an instance administers it
+UPDATE users SET is_admin = true
+WHERE id = (SELECT id FROM users ORDER BY created_at LIMIT 1)
+  AND NOT EXISTS (SELECT 1 FROM users WHERE is_admin);
+
+-- Fields listed here are never overwritten by rescans or metadata imports
+ALTER TABLE games ADD COLUMN IF NOT EXISTS locked_fields TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE IF NOT EXISTS game_edits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    source VARCHAR(30) NOT NULL DEFAULT 'manual',
+    field VARCHAR(50) NOT NULL,
+    old_value JSONB,
+    new_value JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_edits_game ON game_edits(game_id, created_at DESC);
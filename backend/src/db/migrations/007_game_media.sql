@@ -0,0 +1,20 @@
+-- Migration: Game Media
+-- Box art, title screens, screenshots and cartridge/disc scans per game
+
+CREATE TABLE IF NOT EXISTS game_media (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('box_art', 'title_screen', 'screenshot', 'cartridge')),
+    content_hash VARCHAR(64) NOT NULL,
+    file_extension VARCHAR(10) NOT NULL,
+    mime_type VARCHAR(50) NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (game_id, kind, content_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_media_game ON game_media(game_id, kind);
+CREATE INDEX IF NOT EXISTS idx_game_media_hash ON game_media(content_hash);
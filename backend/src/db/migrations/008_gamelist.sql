@@ -0,0 +1,6 @@
+-- Migration: EmulationStation Gamelists
+-- Extra metadata carried by gamelist.xml files
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS publisher VARCHAR(100);
+ALTER TABLE games ADD COLUMN IF NOT EXISTS players VARCHAR(20);
+ALTER TABLE games ADD COLUMN IF NOT EXISTS rating REAL CHECK (rating >= 0 AND rating <= 1);
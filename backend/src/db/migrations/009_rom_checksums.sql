@@ -0,0 +1,6 @@
+-- Migration: ROM Checksums
+-- CRC32 of the ROM image, computed lazily and used to match external databases
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS rom_crc32 VARCHAR(8);
+
+CREATE INDEX IF NOT EXISTS idx_games_rom_crc32 ON games(rom_crc32);
@@ -0,0 +1,26 @@
+-- Migration: Offline Metadata Database
+-- Locally imported metadata (libretro-database .dat files or JSON bundles)
+
+CREATE TABLE IF NOT EXISTS metadata_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    console_id VARCHAR(20) NOT NULL REFERENCES consoles(id) ON DELETE CASCADE,
+    match_key VARCHAR(300) NOT NULL,
+    crc32 VARCHAR(8),
+    serial VARCHAR(50),
+    title VARCHAR(255),
+    normalized_title VARCHAR(255),
+    description TEXT,
+    developer VARCHAR(100),
+    publisher VARCHAR(100),
+    genre VARCHAR(50),
+    release_year INT,
+    players VARCHAR(20),
+    region VARCHAR(100),
+    source VARCHAR(100) NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (console_id, match_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_metadata_entries_crc ON metadata_entries(console_id, crc32);
+CREATE INDEX IF NOT EXISTS idx_metadata_entries_serial ON metadata_entries(console_id, serial);
+CREATE INDEX IF NOT EXISTS idx_metadata_entries_title ON metadata_entries(console_id, normalized_title);
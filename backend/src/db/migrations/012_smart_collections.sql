@@ -0,0 +1,17 @@
+-- Migration: Smart Collections
+-- Saved rule-based queries over games, play statistics and save states
+
+CREATE TABLE IF NOT EXISTS smart_collections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    rules JSONB NOT NULL,
+    sort JSONB,
+    is_public BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_smart_collections_user ON smart_collections(user_id);
@@ -0,0 +1,44 @@
+-- Migration: Ratings, Reviews and Play Status
+-- Per-user ratings and reviews, backlog tracking and an activity feed for profiles
+
+-- Aggregate of user ratings on a 1-10 scale, kept up to date by the review service
+ALTER TABLE games ADD COLUMN IF NOT EXISTS user_rating REAL;
+ALTER TABLE games ADD COLUMN IF NOT EXISTS user_rating_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_games_user_rating ON games(user_rating DESC NULLS LAST);
+
+CREATE TABLE IF NOT EXISTS game_reviews (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    rating SMALLINT CHECK (rating BETWEEN 1 AND 10),
+    review TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, game_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_reviews_game ON game_reviews(game_id, updated_at DESC);
+
+CREATE TABLE IF NOT EXISTS user_game_status (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('backlog', 'playing', 'beaten', 'completed', 'abandoned')),
+    started_on DATE,
+    finished_on DATE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, game_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_game_status_user ON user_game_status(user_id, status);
+
+CREATE TABLE IF NOT EXISTS user_activity (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    old_status VARCHAR(20),
+    new_status VARCHAR(20),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_activity_user ON user_activity(user_id, created_at DESC);
@@ -0,0 +1,52 @@
+-- Migration: Tags
+-- Replaces games.genre with genre, theme, feature and user-defined tags
+
+CREATE TABLE IF NOT EXISTS tags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('genre', 'theme', 'feature', 'user')),
+    name VARCHAR(50) NOT NULL,
+    slug VARCHAR(60) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, slug)
+);
+
+CREATE TABLE IF NOT EXISTS game_tags (
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    added_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (game_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_tags_tag ON game_tags(tag_id);
+
+-- Move existing genres, e.g. "Action, Platform" or "Shooter / Run and Gun", into genre tags.
+-- The column is read through to_jsonb so these statements stay valid once it is dropped.
+INSERT INTO tags (kind, name, slug)
+SELECT DISTINCT ON (slug) 'genre', name, slug
+FROM (
+    SELECT left(trim(part), 50) AS name,
+           trim(both '-' from regexp_replace(lower(trim(part)), '[^a-z0-9]+', '-', 'g')) AS slug
+    FROM games g
+    CROSS JOIN LATERAL regexp_split_to_table(to_jsonb(g) ->> 'genre', '[,/]') AS part
+) genres
+WHERE slug <> ''
+ORDER BY slug, name
+ON CONFLICT (kind, slug) DO NOTHING;
+
+INSERT INTO game_tags (game_id, tag_id)
+SELECT DISTINCT g.id, t.id
+FROM games g
+CROSS JOIN LATERAL regexp_split_to_table(to_jsonb(g) ->> 'genre', '[,/]') AS part
+JOIN tags t
+    ON t.kind = 'genre'
+    AND t.slug = trim(both '-' from regexp_replace(lower(trim(part)), '[^a-z0-9]+', '-', 'g'))
+ON CONFLICT (game_id, tag_id) DO NOTHING;
+
+UPDATE games
+SET locked_fields = array_replace(locked_fields, 'genre', 'genres')
+WHERE 'genre' = ANY(locked_fields);
+
+DROP INDEX IF EXISTS idx_games_genre;
+ALTER TABLE games DROP COLUMN IF EXISTS genre;
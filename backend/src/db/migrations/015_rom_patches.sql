@@ -0,0 +1,26 @@
+-- Migration: ROM patches
+-- IPS/BPS/UPS patches stored next to a base game and applied when the ROM is downloaded
+
+CREATE TABLE IF NOT EXISTS rom_patches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    format VARCHAR(10) NOT NULL CHECK (format IN ('ips', 'bps', 'ups')),
+    filename VARCHAR(255) NOT NULL,
+    file_size BIGINT NOT NULL,
+    patch_crc32 VARCHAR(8) NOT NULL,
+    source_crc32 VARCHAR(8),
+    target_crc32 VARCHAR(8),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (game_id, name)
+);
+
+-- Save states belong to either the base game or one of its patched variants
+ALTER TABLE save_states ADD COLUMN IF NOT EXISTS patch_id UUID REFERENCES rom_patches(id) ON DELETE CASCADE;
+
+ALTER TABLE save_states DROP CONSTRAINT IF EXISTS save_states_user_id_game_id_slot_key;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_save_states_slot
+    ON save_states(user_id, game_id, COALESCE(patch_id, '00000000-0000-0000-0000-000000000000'), slot);
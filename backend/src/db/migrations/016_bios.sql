@@ -0,0 +1,42 @@
+-- Migration: BIOS registry
+-- Known BIOS/firmware files per console and the copies admins have uploaded
+
+CREATE TABLE IF NOT EXISTS console_bios (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    console_id VARCHAR(20) NOT NULL REFERENCES consoles(id) ON DELETE CASCADE,
+    filename VARCHAR(255) NOT NULL,
+    description VARCHAR(255),
+    required BOOLEAN NOT NULL DEFAULT false,
+    -- Files sharing a group are alternatives (e.g. regional revisions), one of them is enough
+    choice_group VARCHAR(50),
+    md5_hashes TEXT[] NOT NULL,
+    UNIQUE (console_id, filename)
+);
+
+CREATE TABLE IF NOT EXISTS bios_files (
+    bios_id UUID PRIMARY KEY REFERENCES console_bios(id) ON DELETE CASCADE,
+    md5 VARCHAR(32) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    uploaded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Known-good dumps, as listed in libretro's System.dat
+INSERT INTO console_bios (console_id, filename, description, required, choice_group, md5_hashes) VALUES
+    ('nes', 'disksys.rom', 'Famicom Disk System BIOS', false, NULL, ARRAY['ca30b50f880eb660a320674ed365ef7a']),
+    ('gb', 'gb_bios.bin', 'Game Boy boot ROM', false, NULL, ARRAY['32fbbd84168d3482956eb3c5051637f5']),
+    ('gbc', 'gbc_bios.bin', 'Game Boy Color boot ROM', false, NULL, ARRAY['dbfce9db9deaa2567f6a84fde55f9680']),
+    ('gba', 'gba_bios.bin', 'Game Boy Advance BIOS, improves compatibility over the built-in HLE BIOS', false, NULL, ARRAY['a860e8c0b6d573d191e4ec7db1b1e4f6']),
+    ('psx', 'scph5500.bin', 'PlayStation BIOS v3.0 (Japan)', false, 'region', ARRAY['8dd7d5296a650fac7319bce665a6a53c']),
+    ('psx', 'scph5501.bin', 'PlayStation BIOS v3.0 (USA)', false, 'region', ARRAY['490f666e1afb15b7362b406ed1cea246']),
+    ('psx', 'scph5502.bin', 'PlayStation BIOS v3.0 (Europe)', false, 'region', ARRAY['32736f17079d0b2b7024407c39bd3050']),
+    ('dreamcast', 'dc_boot.bin', 'Dreamcast boot ROM', true, NULL, ARRAY['e10c53c2f8b90bab96ead2d368858623']),
+    ('dreamcast', 'dc_flash.bin', 'Dreamcast flash memory (region and settings)', false, NULL, ARRAY['0a93f7940c455905bea6e392dfde92a4']),
+    ('ps2', 'ps2-0230a-20080220.bin', 'PlayStation 2 BIOS v2.30 (USA)', true, 'region', ARRAY['21038400dc633070a78ad53090c53017']),
+    ('ps2', 'ps2-0230e-20080220.bin', 'PlayStation 2 BIOS v2.30 (Europe)', true, 'region', ARRAY['dc69f0643a3030aaa4797501b483d6c4']),
+    ('ps2', 'ps2-0230j-20080220.bin', 'PlayStation 2 BIOS v2.30 (Japan)', true, 'region', ARRAY['30d56e79d89fbddf10938fa67fe3f34e'])
+ON CONFLICT (console_id, filename) DO UPDATE SET
+    description = EXCLUDED.description,
+    required = EXCLUDED.required,
+    choice_group = EXCLUDED.choice_group,
+    md5_hashes = EXCLUDED.md5_hashes;
@@ -0,0 +1,18 @@
+-- Migration: console management
+-- Per-console emulator settings and per-game overrides on top of them
+
+ALTER TABLE consoles ADD COLUMN IF NOT EXISTS emulator_config JSONB NOT NULL DEFAULT '{}';
+
+CREATE TABLE IF NOT EXISTS game_emulator_overrides (
+    game_id UUID PRIMARY KEY REFERENCES games(id) ON DELETE CASCADE,
+    config JSONB NOT NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Defaults for the built-in consoles, left alone once an admin has configured them
+UPDATE consoles SET emulator_config = '{"required_bios": ["dc_boot.bin"]}'
+    WHERE id = 'dreamcast' AND emulator_config = '{}';
+
+UPDATE consoles SET emulator_config = '{"threads": true}'
+    WHERE id IN ('n64', 'ps2') AND emulator_config = '{}';
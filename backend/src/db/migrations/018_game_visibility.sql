@@ -0,0 +1,37 @@
+-- Migration: game visibility
+-- Games are visible instance-wide, only to their uploader, or shared with users and groups
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS visibility VARCHAR(20) NOT NULL DEFAULT 'instance'
+    CHECK (visibility IN ('instance', 'private', 'shared'));
+
+CREATE TABLE IF NOT EXISTS user_groups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS user_group_members (
+    group_id UUID NOT NULL REFERENCES user_groups(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS game_user_shares (
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY (game_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS game_group_shares (
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    group_id UUID NOT NULL REFERENCES user_groups(id) ON DELETE CASCADE,
+    PRIMARY KEY (game_id, group_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_games_restricted ON games(visibility) WHERE visibility <> 'instance';
+CREATE INDEX IF NOT EXISTS idx_games_uploaded_by ON games(uploaded_by);
+CREATE INDEX IF NOT EXISTS idx_user_group_members_user ON user_group_members(user_id);
+CREATE INDEX IF NOT EXISTS idx_game_user_shares_user ON game_user_shares(user_id);
+CREATE INDEX IF NOT EXISTS idx_game_group_shares_group ON game_group_shares(group_id);
@@ -0,0 +1,19 @@
+-- Migration: game titles
+-- Groups the regional releases and revisions of a game under one title
+
+CREATE TABLE IF NOT EXISTS game_titles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    console_id VARCHAR(20) NOT NULL REFERENCES consoles(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    -- Grouping key of automatically created titles, NULL for titles split off by an admin
+    normalized_name VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (console_id, normalized_name)
+);
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS title_id UUID REFERENCES game_titles(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_games_title ON games(title_id);
+
+-- Regions in order of preference, used to pick the release shown for a title
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS preferred_regions TEXT[] NOT NULL DEFAULT '{}';
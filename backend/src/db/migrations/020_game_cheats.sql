@@ -0,0 +1,20 @@
+-- Migration: cheat codes
+-- Per-game cheats, private to the user who added them unless shared
+
+CREATE TABLE IF NOT EXISTS game_cheats (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    description VARCHAR(255) NOT NULL,
+    format VARCHAR(20) NOT NULL CHECK (format IN ('raw', 'game_genie', 'action_replay', 'gameshark')),
+    -- Normalised code as passed to the emulator, several codes joined with '+'
+    code TEXT NOT NULL,
+    -- The code decoded into address/value writes
+    decoded JSONB NOT NULL,
+    is_shared BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_cheats_game ON game_cheats(game_id);
+CREATE INDEX IF NOT EXISTS idx_game_cheats_user ON game_cheats(user_id);
@@ -0,0 +1,23 @@
+-- Migration: game documents
+-- Scanned manuals, maps and FAQs attached to a game, stored under DOCUMENT_STORAGE_PATH
+
+CREATE TABLE IF NOT EXISTS game_documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('manual', 'map', 'faq', 'other')),
+    title VARCHAR(255) NOT NULL,
+    -- Name of the uploaded file, offered again on download
+    original_filename VARCHAR(255) NOT NULL,
+    -- Stored file, relative to the document storage path
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    file_size BIGINT NOT NULL,
+    sha256 VARCHAR(64) NOT NULL,
+    -- PDFs only
+    page_count INTEGER,
+    has_thumbnail BOOLEAN NOT NULL DEFAULT false,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_documents_game ON game_documents(game_id);
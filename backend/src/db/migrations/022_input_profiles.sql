@@ -0,0 +1,58 @@
+-- Migration: input profiles
+-- Named button mappings per controller layout, per-game overrides and SDL controller mappings
+
+CREATE TABLE IF NOT EXISTS input_profiles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- Controller layout the buttons belong to, e.g. 'snes' or 'n64'
+    layout VARCHAR(20) NOT NULL,
+    -- Button id -> {"keyboard": KeyboardEvent.code, "gamepad": SDL button or axis}
+    bindings JSONB NOT NULL DEFAULT '{}',
+    -- Used for games of the layout that have no override
+    is_default BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, layout, name)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_input_profiles_default
+    ON input_profiles(user_id, layout) WHERE is_default;
+
+-- A user's profile choice and binding changes for one game
+CREATE TABLE IF NOT EXISTS game_input_overrides (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    profile_id UUID REFERENCES input_profiles(id) ON DELETE SET NULL,
+    bindings JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, game_id)
+);
+
+-- Entries of SDL's gamecontrollerdb.txt, recognising pads by GUID
+CREATE TABLE IF NOT EXISTS controller_mappings (
+    guid VARCHAR(32) NOT NULL,
+    platform VARCHAR(50) NOT NULL DEFAULT '',
+    name VARCHAR(255) NOT NULL,
+    -- SDL element -> physical input, e.g. {"a": "b0", "leftx": "a0", "dpup": "h0.1"}
+    mapping JSONB NOT NULL,
+    -- USB ids decoded from the GUID, how browsers identify pads
+    vendor_id VARCHAR(4),
+    product_id VARCHAR(4),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (guid, platform)
+);
+
+CREATE INDEX IF NOT EXISTS idx_controller_mappings_usb ON controller_mappings(vendor_id, product_id);
+
+-- Move the old free-form keyboard mappings into a default profile, then clear them so
+-- deleting that profile sticks
+INSERT INTO input_profiles (user_id, name, layout, bindings, is_default)
+SELECT user_id, 'Keyboard', 'retropad', jsonb_object_agg(key, jsonb_build_object('keyboard', value)), true
+FROM user_settings, jsonb_each_text(key_mappings)
+WHERE jsonb_typeof(key_mappings) = 'object'
+    AND key IN ('up', 'down', 'left', 'right', 'a', 'b', 'x', 'y', 'l', 'r', 'start', 'select')
+GROUP BY user_id
+ON CONFLICT DO NOTHING;
+
+UPDATE user_settings SET key_mappings = '{}' WHERE key_mappings IS NOT NULL AND key_mappings <> '{}';
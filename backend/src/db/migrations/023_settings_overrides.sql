@@ -0,0 +1,42 @@
+-- Migration: Settings Overrides
+-- Shader and core options join the global settings, and any of them can be overridden
+-- per console and per game
+
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS shader VARCHAR(255);
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS core_options JSONB NOT NULL DEFAULT '{}';
+
+-- Rows created outside registration got 003's defaults, use the ones registration writes
+ALTER TABLE user_settings ALTER COLUMN theme SET DEFAULT 'classic-xp';
+ALTER TABLE user_settings ALTER COLUMN audio_volume SET DEFAULT 0.8;
+
+UPDATE user_settings SET
+    theme = COALESCE(theme, 'classic-xp'),
+    crt_effect = COALESCE(crt_effect, true),
+    scanline_intensity = COALESCE(scanline_intensity, 0.5),
+    audio_volume = COALESCE(audio_volume, 0.8),
+    updated_at = COALESCE(updated_at, NOW())
+WHERE theme IS NULL OR crt_effect IS NULL OR scanline_intensity IS NULL
+    OR audio_volume IS NULL OR updated_at IS NULL;
+
+ALTER TABLE user_settings ALTER COLUMN theme SET NOT NULL;
+ALTER TABLE user_settings ALTER COLUMN crt_effect SET NOT NULL;
+ALTER TABLE user_settings ALTER COLUMN scanline_intensity SET NOT NULL;
+ALTER TABLE user_settings ALTER COLUMN audio_volume SET NOT NULL;
+ALTER TABLE user_settings ALTER COLUMN updated_at SET NOT NULL;
+
+-- Overrides hold only the fields they change, see models::SettingsOverride
+CREATE TABLE IF NOT EXISTS user_console_settings (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    console_id VARCHAR(20) NOT NULL REFERENCES consoles(id) ON DELETE CASCADE,
+    settings JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, console_id)
+);
+
+CREATE TABLE IF NOT EXISTS user_game_settings (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    settings JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, game_id)
+);
@@ -0,0 +1,41 @@
+-- Migration: Shader Presets
+-- RetroArch .glslp/.slangp presets, stored with their passes and textures under SHADER_STORAGE_PATH
+
+CREATE TABLE IF NOT EXISTS shader_presets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- What settings name in their shader field
+    name VARCHAR(100) NOT NULL UNIQUE,
+    description TEXT,
+    format VARCHAR(10) NOT NULL CHECK (format IN ('glslp', 'slangp')),
+    -- Path of the preset file inside the preset's folder
+    preset_path VARCHAR(255) NOT NULL,
+    passes JSONB NOT NULL DEFAULT '[]',
+    textures JSONB NOT NULL DEFAULT '{}',
+    -- Parameters the passes declare with #pragma parameter, and the values the preset sets
+    parameters JSONB NOT NULL DEFAULT '[]',
+    preset_values JSONB NOT NULL DEFAULT '{}',
+    -- Every stored file, as paths relative to the preset's folder
+    files TEXT[] NOT NULL DEFAULT '{}',
+    total_size BIGINT NOT NULL DEFAULT 0,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Consoles a preset is meant for, presets without any suit every console
+CREATE TABLE IF NOT EXISTS shader_preset_consoles (
+    preset_id UUID NOT NULL REFERENCES shader_presets(id) ON DELETE CASCADE,
+    console_id VARCHAR(20) NOT NULL REFERENCES consoles(id) ON DELETE CASCADE,
+    PRIMARY KEY (preset_id, console_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_shader_preset_consoles_console ON shader_preset_consoles(console_id);
+
+-- A user's values for a preset's parameters, over the preset's own
+CREATE TABLE IF NOT EXISTS user_shader_parameters (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    preset_id UUID NOT NULL REFERENCES shader_presets(id) ON DELETE CASCADE,
+    parameters JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, preset_id)
+);
@@ -0,0 +1,5 @@
+-- Migration: ROM Modification Times
+-- Modification time of the ROM file when rom_crc32 was computed, so a same-size replacement
+-- invalidates the checksum
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS rom_modified_at TIMESTAMPTZ;
@@ -0,0 +1,114 @@
+use crate::models::{BiosQuery, BiosStatusResponse};
+use crate::services::{bios_status, AuthService, BiosService, MAX_BIOS_SIZE_BYTES};
+use crate::utils::{ApiError, FileDelivery};
+use actix_multipart::Multipart;
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures_util::stream::StreamExt;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Which BIOS files each console expects and which of them are present
+pub async fn get_bios_status(
+    pool: web::Data<PgPool>,
+    bios_service: web::Data<BiosService>,
+    query: web::Query<BiosQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let files = bios_service
+        .get_bios_files(&pool, query.console.as_deref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(BiosStatusResponse {
+        consoles: bios_status(files),
+    }))
+}
+
+pub async fn upload_bios(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    bios_service: web::Data<BiosService>,
+    user_id: web::ReqData<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let mut console_id: Option<String> = None;
+    let mut data: Option<Vec<u8>> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let field_name = field.name().to_string();
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            if bytes.len() + chunk.len() > MAX_BIOS_SIZE_BYTES {
+                return Err(ApiError::BadRequest("BIOS file exceeds maximum size".to_string()));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        match field_name.as_str() {
+            "console_id" => {
+                let value = String::from_utf8(bytes)
+                    .map_err(|_| ApiError::BadRequest("Invalid console_id format".to_string()))?;
+                console_id = Some(value.trim().to_string()).filter(|c| !c.is_empty());
+            }
+            "file" => data = Some(bytes),
+            _ => {}
+        }
+    }
+
+    let data = data.ok_or_else(|| ApiError::BadRequest("Missing file".to_string()))?;
+
+    let bios = bios_service
+        .upload_bios(&pool, console_id.as_deref(), data, &user_id)
+        .await?;
+
+    Ok(HttpResponse::Created().json(bios))
+}
+
+pub async fn get_bios_file(
+    pool: web::Data<PgPool>,
+    bios_service: web::Data<BiosService>,
+    bios_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    send_bios(&pool, &bios_service, &bios_id, &req).await
+}
+
+pub async fn delete_bios(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    bios_service: web::Data<BiosService>,
+    bios_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let bios = bios_service.get_bios_file(&pool, &bios_id).await?;
+    bios_service.delete_bios(&pool, &bios).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "BIOS file deleted" })))
+}
+
+pub(crate) async fn send_bios(
+    pool: &PgPool,
+    bios_service: &BiosService,
+    bios_id: &Uuid,
+    req: &HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let bios = bios_service.get_bios_file(pool, bios_id).await?;
+    let md5 = bios
+        .md5
+        .as_deref()
+        .ok_or_else(|| ApiError::NotFound(format!("{} has not been uploaded", bios.filename)))?;
+
+    FileDelivery {
+        path: &bios_service.file_path(&bios.console_id, &bios.filename),
+        checksum: md5,
+        cache_control: "private, no-cache",
+        compressible: false,
+    }
+    .respond(req)
+    .await
+}
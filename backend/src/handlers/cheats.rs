@@ -0,0 +1,115 @@
+use crate::models::{CheatImportParams, CreateCheatRequest, GameCheat, UpdateCheatRequest};
+use crate::services::{AuthService, CheatService, GameService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Returns the cheat if the user may change it: their own, or any visible one for admins
+async fn get_owned_cheat(
+    pool: &PgPool,
+    auth_service: &AuthService,
+    cheat_service: &CheatService,
+    game_id: &Uuid,
+    cheat_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<GameCheat, ApiError> {
+    let cheat = cheat_service.get_visible_cheat(pool, game_id, cheat_id, user_id).await?;
+    if cheat.user_id != *user_id && !auth_service.is_admin(pool, user_id).await? {
+        return Err(ApiError::Forbidden("Only the cheat's owner can change it".to_string()));
+    }
+
+    Ok(cheat)
+}
+
+pub async fn get_game_cheats(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    cheat_service: web::Data<CheatService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let cheats = cheat_service.get_cheats(&pool, &game.id, &user_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "cheats": cheats })))
+}
+
+pub async fn create_game_cheat(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    cheat_service: web::Data<CheatService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateCheatRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let console = game_service.get_console(&pool, &game.console_id).await?;
+    let cheat = cheat_service
+        .create_cheat(&pool, &console, &game.id, &user_id, &req)
+        .await?;
+
+    Ok(HttpResponse::Created().json(cheat))
+}
+
+pub async fn update_game_cheat(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    cheat_service: web::Data<CheatService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateCheatRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+    let (game_id, cheat_id) = path.into_inner();
+
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let cheat = get_owned_cheat(&pool, &auth_service, &cheat_service, &game.id, &cheat_id, &user_id).await?;
+    let console = game_service.get_console(&pool, &game.console_id).await?;
+    let cheat = cheat_service.update_cheat(&pool, &console, &cheat, &req).await?;
+
+    Ok(HttpResponse::Ok().json(cheat))
+}
+
+pub async fn delete_game_cheat(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    cheat_service: web::Data<CheatService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let (game_id, cheat_id) = path.into_inner();
+
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let cheat = get_owned_cheat(&pool, &auth_service, &cheat_service, &game.id, &cheat_id, &user_id).await?;
+    cheat_service.delete_cheat(&pool, &cheat.id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Cheat deleted" })))
+}
+
+/// Imports a RetroArch `.cht` file sent as the request body
+pub async fn import_game_cheats(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    cheat_service: web::Data<CheatService>,
+    game_id: web::Path<Uuid>,
+    query: web::Query<CheatImportParams>,
+    user_id: web::ReqData<Uuid>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let contents = std::str::from_utf8(&body)
+        .map_err(|_| ApiError::BadRequest("Cheat file must be UTF-8 text".to_string()))?;
+
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let console = game_service.get_console(&pool, &game.console_id).await?;
+    let summary = cheat_service
+        .import_cht(&pool, &console, &game.id, &user_id, contents, query.shared)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
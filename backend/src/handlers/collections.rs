@@ -0,0 +1,189 @@
+use crate::models::{
+    AddCollectionGameRequest, CollectionQueryParams, CreateCollectionRequest, ReorderCollectionRequest,
+    UpdateCollectionRequest,
+};
+use crate::services::{CollectionService, GameService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_favorites(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let games = collection_service.get_favorites(&pool, &user_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "games": games })))
+}
+
+pub async fn add_favorite(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    collection_service: web::Data<CollectionService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    collection_service.add_favorite(&pool, &user_id, &game.id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Added to favorites" })))
+}
+
+pub async fn remove_favorite(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    collection_service.remove_favorite(&pool, &user_id, &game_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Removed from favorites" })))
+}
+
+pub async fn get_collections(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    query: web::Query<CollectionQueryParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let public = match query.scope.as_deref() {
+        None | Some("mine") => false,
+        Some("public") => true,
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid scope '{}', expected mine or public",
+                other
+            )))
+        }
+    };
+
+    let collections = collection_service
+        .get_collections(&pool, &user_id, public)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "collections": collections })))
+}
+
+pub async fn create_collection(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateCollectionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let collection = collection_service
+        .create_collection(&pool, &user_id, &req)
+        .await?;
+
+    Ok(HttpResponse::Created().json(collection))
+}
+
+pub async fn get_collection(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    collection_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let collection = collection_service
+        .get_visible_collection(&pool, &collection_id, &user_id)
+        .await?;
+    let collection = collection_service
+        .get_collection_with_games(&pool, collection, &user_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(collection))
+}
+
+pub async fn update_collection(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    collection_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateCollectionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let collection = collection_service
+        .get_owned_collection(&pool, &collection_id, &user_id)
+        .await?;
+    let collection = collection_service
+        .update_collection(&pool, &collection, &req)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(collection))
+}
+
+pub async fn delete_collection(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    collection_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let collection = collection_service
+        .get_owned_collection(&pool, &collection_id, &user_id)
+        .await?;
+    collection_service
+        .delete_collection(&pool, &collection.id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Collection deleted" })))
+}
+
+pub async fn add_collection_game(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    collection_service: web::Data<CollectionService>,
+    collection_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<AddCollectionGameRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let collection = collection_service
+        .get_owned_collection(&pool, &collection_id, &user_id)
+        .await?;
+    let game = game_service.get_visible_game(&pool, &req.game_id, &user_id).await?;
+
+    collection_service
+        .add_game(&pool, &collection.id, &game.id, req.position)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Game added to collection" })))
+}
+
+pub async fn remove_collection_game(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let (collection_id, game_id) = path.into_inner();
+    let collection = collection_service
+        .get_owned_collection(&pool, &collection_id, &user_id)
+        .await?;
+
+    collection_service
+        .remove_game(&pool, &collection.id, &game_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Game removed from collection" })))
+}
+
+pub async fn reorder_collection(
+    pool: web::Data<PgPool>,
+    collection_service: web::Data<CollectionService>,
+    collection_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<ReorderCollectionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let collection = collection_service
+        .get_owned_collection(&pool, &collection_id, &user_id)
+        .await?;
+
+    collection_service
+        .reorder(&pool, &collection.id, &req.game_ids)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Collection reordered" })))
+}
@@ -0,0 +1,120 @@
+use crate::models::{CreateConsoleRequest, EmulatorOverride, UpdateConsoleRequest};
+use crate::services::{resolve_emulator_config, AuthService, BiosService, ConsoleService, GameService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_console(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    console_id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let console = game_service.get_console(&pool, &console_id).await?;
+    Ok(HttpResponse::Ok().json(console))
+}
+
+pub async fn create_console(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    console_service: web::Data<ConsoleService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateConsoleRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+
+    let console = console_service.create_console(pool.get_ref(), &req).await?;
+    Ok(HttpResponse::Created().json(console))
+}
+
+pub async fn update_console(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    console_service: web::Data<ConsoleService>,
+    console_id: web::Path<String>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateConsoleRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+
+    let console = console_service
+        .update_console(&pool, &console_id, &req)
+        .await?;
+    Ok(HttpResponse::Ok().json(console))
+}
+
+pub async fn delete_console(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    console_service: web::Data<ConsoleService>,
+    console_id: web::Path<String>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    console_service.delete_console(&pool, &console_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Console deleted" })))
+}
+
+/// The emulator settings a game starts with: console config, game overrides and BIOS
+pub async fn get_game_emulator_config(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    console_service: web::Data<ConsoleService>,
+    bios_service: web::Data<BiosService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let console = game_service.get_console(&pool, &game.console_id).await?;
+    let overrides = console_service.get_game_override(&pool, &game.id).await?;
+    let bios_files = bios_service
+        .get_bios_files(&pool, Some(&game.console_id))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(resolve_emulator_config(
+        &game,
+        &console,
+        overrides.map(|o| o.config.0),
+        &bios_files,
+    )))
+}
+
+pub async fn update_game_emulator_config(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    console_service: web::Data<ConsoleService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<EmulatorOverride>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+
+    let game = game_service.get_game(&pool, &game_id).await?;
+    let overrides = console_service
+        .set_game_override(&pool, &game.id, &req, &user_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(overrides))
+}
+
+pub async fn delete_game_emulator_config(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    console_service: web::Data<ConsoleService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    console_service.delete_game_override(&pool, &game_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Emulator overrides removed" })))
+}
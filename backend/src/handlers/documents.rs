@@ -0,0 +1,213 @@
+use crate::models::{CreateDocumentRequest, DocumentFileParams, UpdateDocumentRequest};
+use crate::services::{AuthService, DocumentService, GameService, MAX_DOCUMENT_SIZE_BYTES};
+use crate::utils::{ApiError, FileDelivery};
+use actix_files::NamedFile;
+use actix_multipart::Multipart;
+use actix_web::http::header::{
+    self, Charset, ContentDisposition, DispositionParam, DispositionType, ExtendedValue, HeaderValue,
+};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures_util::stream::StreamExt;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_game_documents(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    document_service: web::Data<DocumentService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let documents = document_service.get_documents(&pool, &game.id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "documents": documents })))
+}
+
+pub async fn upload_game_document(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    document_service: web::Data<DocumentService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    let game = game_service.get_game(&pool, &game_id).await?;
+
+    let mut kind: Option<String> = None;
+    let mut title: Option<String> = None;
+    let mut file: Option<(String, Vec<u8>)> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let field_name = field.name().to_string();
+        let filename = field
+            .content_disposition()
+            .get_filename()
+            .unwrap_or_default()
+            .to_string();
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            if bytes.len() + chunk.len() > MAX_DOCUMENT_SIZE_BYTES {
+                return Err(ApiError::BadRequest("Document exceeds maximum size".to_string()));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        match field_name.as_str() {
+            "kind" => {
+                kind = Some(
+                    String::from_utf8(bytes)
+                        .map_err(|_| ApiError::BadRequest("Invalid kind format".to_string()))?,
+                );
+            }
+            "title" => {
+                title = Some(
+                    String::from_utf8(bytes)
+                        .map_err(|_| ApiError::BadRequest("Invalid title format".to_string()))?,
+                );
+            }
+            "file" => file = Some((filename, bytes)),
+            _ => {}
+        }
+    }
+
+    let request = CreateDocumentRequest {
+        kind: kind.ok_or_else(|| ApiError::BadRequest("Missing kind".to_string()))?,
+        title,
+    };
+    request
+        .validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+    let (filename, data) = file.ok_or_else(|| ApiError::BadRequest("Missing file".to_string()))?;
+
+    let document = document_service
+        .create_document(&pool, &game, &request, &filename, data, &user_id)
+        .await?;
+
+    Ok(HttpResponse::Created().json(document))
+}
+
+pub async fn update_game_document(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    document_service: web::Data<DocumentService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateDocumentRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+    let (game_id, document_id) = path.into_inner();
+
+    let document = document_service.get_document(&pool, &game_id, &document_id).await?;
+    let document = document_service.update_document(&pool, &document, &req).await?;
+
+    Ok(HttpResponse::Ok().json(document))
+}
+
+pub async fn delete_game_document(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    document_service: web::Data<DocumentService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    let (game_id, document_id) = path.into_inner();
+
+    let document = document_service.get_document(&pool, &game_id, &document_id).await?;
+    document_service.delete_document(&pool, &document).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Document deleted" })))
+}
+
+/// Serves the document inline for the browser's viewer, or as an attachment with
+/// `?download=true`. Ranges are supported so PDF viewers can load large scans progressively.
+pub async fn get_game_document_file(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    document_service: web::Data<DocumentService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+    params: web::Query<DocumentFileParams>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let (game_id, document_id) = path.into_inner();
+    game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let document = document_service.get_document(&pool, &game_id, &document_id).await?;
+
+    let mut response = FileDelivery {
+        path: &document_service.file_path(&document),
+        checksum: &document.sha256,
+        cache_control: "private, no-cache",
+        compressible: document.content_type.starts_with("text/"),
+    }
+    .respond(&req)
+    .await?;
+
+    if response.status() != StatusCode::NOT_MODIFIED {
+        let disposition = ContentDisposition {
+            disposition: if params.download {
+                DispositionType::Attachment
+            } else {
+                DispositionType::Inline
+            },
+            parameters: vec![filename_param(&document.original_filename)],
+        };
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(&document.content_type)
+                .map_err(|e| ApiError::InternalServerError(e.to_string()))?,
+        );
+        headers.insert(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_str(&disposition.to_string())
+                .map_err(|e| ApiError::InternalServerError(e.to_string()))?,
+        );
+    }
+
+    Ok(response)
+}
+
+pub async fn get_game_document_thumbnail(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    document_service: web::Data<DocumentService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let (game_id, document_id) = path.into_inner();
+    game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let document = document_service.get_document(&pool, &game_id, &document_id).await?;
+    if !document.has_thumbnail {
+        return Err(ApiError::NotFound("Document has no thumbnail".to_string()));
+    }
+
+    let file = NamedFile::open(document_service.thumbnail_path(&document))
+        .map_err(|_| ApiError::NotFound("Thumbnail not found".to_string()))?;
+
+    Ok(file.into_response(&req))
+}
+
+/// Non-ASCII names need the RFC 5987 `filename*` form to survive the header
+fn filename_param(filename: &str) -> DispositionParam {
+    if filename.is_ascii() {
+        DispositionParam::Filename(filename.to_string())
+    } else {
+        DispositionParam::FilenameExt(ExtendedValue {
+            charset: Charset::Ext("UTF-8".to_string()),
+            language_tag: None,
+            value: filename.as_bytes().to_vec(),
+        })
+    }
+}
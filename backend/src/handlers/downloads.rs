@@ -0,0 +1,104 @@
+use crate::handlers::{open_save_file, send_bios, send_rom};
+use crate::models::{CreateDownloadUrlRequest, DownloadKind, DownloadUrl, SignedDownloadQuery};
+use crate::services::{AuthService, BiosService, GameService, PatchService, SaveService};
+use crate::utils::ApiError;
+use actix_files::NamedFile;
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::DateTime;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Issues a short-lived URL that downloads one ROM or save without an `Authorization` header
+#[allow(clippy::too_many_arguments)]
+pub async fn create_download_url(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    patch_service: web::Data<PatchService>,
+    save_service: web::Data<SaveService>,
+    bios_service: web::Data<BiosService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateDownloadUrlRequest>,
+) -> Result<HttpResponse, ApiError> {
+    // Check access up front so the client gets a real error rather than a dead link
+    match req.kind {
+        DownloadKind::Rom => {
+            game_service.get_visible_game(&pool, &req.id, &user_id).await?;
+            if let Some(patch_id) = &req.patch {
+                patch_service.get_patch(&pool, &req.id, patch_id).await?;
+            }
+        }
+        DownloadKind::Save | DownloadKind::Bios if req.patch.is_some() => {
+            return Err(ApiError::BadRequest("Patches only apply to ROM downloads".to_string()));
+        }
+        DownloadKind::Save => {
+            save_service.get_owned_save_state(&pool, &req.id, &user_id).await?;
+        }
+        DownloadKind::Bios => {
+            let bios = bios_service.get_bios_file(&pool, &req.id).await?;
+            if !bios.is_present() {
+                return Err(ApiError::NotFound(format!("{} has not been uploaded", bios.filename)));
+            }
+        }
+    }
+
+    let signed = auth_service.sign_download(req.kind, &req.id, req.patch.as_ref(), &user_id)?;
+    let patch = signed
+        .patch
+        .map(|patch_id| format!("&patch={}", patch_id))
+        .unwrap_or_default();
+    let expires_at = DateTime::from_timestamp(signed.expires, 0)
+        .ok_or_else(|| ApiError::InternalServerError("Invalid download expiry".to_string()))?;
+    Ok(HttpResponse::Ok().json(DownloadUrl {
+        url: format!(
+            "/api/v1/downloads/{}/{}?user={}&expires={}&signature={}{}",
+            req.kind.path_segment(),
+            req.id,
+            signed.user,
+            signed.expires,
+            signed.signature,
+            patch
+        ),
+        expires_at,
+    }))
+}
+
+pub async fn download_signed_rom(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    patch_service: web::Data<PatchService>,
+    game_id: web::Path<Uuid>,
+    query: web::Query<SignedDownloadQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.verify_download(DownloadKind::Rom, &game_id, &query)?;
+
+    // Visibility can change while the URL is valid, so check it again for the signing user
+    send_rom(&pool, &game_service, &patch_service, &game_id, query.patch.as_ref(), &query.user, &req).await
+}
+
+pub async fn download_signed_save(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    save_service: web::Data<SaveService>,
+    save_id: web::Path<Uuid>,
+    query: web::Query<SignedDownloadQuery>,
+) -> Result<NamedFile, ApiError> {
+    auth_service.verify_download(DownloadKind::Save, &save_id, &query)?;
+
+    open_save_file(&pool, &save_service, &save_id, &query.user).await
+}
+
+pub async fn download_signed_bios(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    bios_service: web::Data<BiosService>,
+    bios_id: web::Path<Uuid>,
+    query: web::Query<SignedDownloadQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.verify_download(DownloadKind::Bios, &bios_id, &query)?;
+
+    send_bios(&pool, &bios_service, &bios_id, &req).await
+}
@@ -0,0 +1,39 @@
+use crate::models::MergeGamesRequest;
+use crate::services::{AuthService, DuplicateService, GameService, SaveService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn find_duplicates(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    duplicate_service: web::Data<DuplicateService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let report = duplicate_service.find_duplicates(&pool, &game_service).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub async fn merge_games(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    save_service: web::Data<SaveService>,
+    duplicate_service: web::Data<DuplicateService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<MergeGamesRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()?;
+
+    let summary = duplicate_service
+        .merge_games(&pool, &game_service, &save_service, &req.primary_id, &req.duplicate_ids)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
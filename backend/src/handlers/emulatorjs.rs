@@ -0,0 +1,26 @@
+use crate::services::{AuthService, EmulatorJsService, GameService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// The EmulatorJS release the frontend should load and where its data directory is served
+pub async fn get_emulatorjs_info(
+    emulatorjs_service: web::Data<EmulatorJsService>,
+) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(emulatorjs_service.info()?))
+}
+
+/// Installed cores of the current release and whether each console has one
+pub async fn get_emulatorjs_cores(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    emulatorjs_service: web::Data<EmulatorJsService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let consoles = game_service.get_all_consoles(&pool).await?;
+    Ok(HttpResponse::Ok().json(emulatorjs_service.core_report(&consoles)?))
+}
@@ -0,0 +1,99 @@
+use crate::models::Console;
+use crate::services::{AuthService, GameService, GamelistService, MediaService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct GamelistQueryParams {
+    console: Option<String>,
+}
+
+async fn selected_consoles(
+    pool: &PgPool,
+    game_service: &GameService,
+    console: Option<&str>,
+) -> Result<Vec<Console>, ApiError> {
+    match console {
+        Some(console_id) => Ok(vec![game_service.get_console(pool, console_id).await?]),
+        None => game_service.get_all_consoles(pool).await,
+    }
+}
+
+pub async fn import_gamelists(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    gamelist_service: web::Data<GamelistService>,
+    query: web::Query<GamelistQueryParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let mut imports = Vec::new();
+    for console in selected_consoles(&pool, &game_service, query.console.as_deref()).await? {
+        if let Some(summary) = gamelist_service
+            .import_console(&pool, &console, &game_service, &media_service)
+            .await?
+        {
+            imports.push(summary);
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "imports": imports })))
+}
+
+pub async fn export_gamelists(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    gamelist_service: web::Data<GamelistService>,
+    query: web::Query<GamelistQueryParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let mut exports = Vec::new();
+    for console in selected_consoles(&pool, &game_service, query.console.as_deref()).await? {
+        match gamelist_service
+            .export_console(&pool, &console, &game_service, &media_service)
+            .await
+        {
+            Ok(summary) => exports.push(summary),
+            // Consoles without a ROM folder are skipped when exporting everything
+            Err(ApiError::NotFound(msg)) if query.console.is_none() => log::warn!("{}", msg),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "exports": exports })))
+}
+
+pub async fn download_gamelist(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    gamelist_service: web::Data<GamelistService>,
+    console_id: web::Path<String>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let console = game_service.get_console(&pool, &console_id).await?;
+    let rendered = gamelist_service
+        .render_gamelist(&pool, &console, &game_service, &media_service)
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/xml; charset=utf-8")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}-gamelist.xml\"", console.id),
+        ))
+        .body(rendered.xml))
+}
@@ -1,53 +1,141 @@
-use crate::models::{Game, GameListResponse};
-use crate::services::GameService;
-use crate::utils::ApiError;
-use actix_files::NamedFile;
+use crate::models::{GameListResponse, GameQuery, RomQuery, UpdateGameRequest};
+use crate::services::{
+    is_compressible_rom, load_game_cheats, load_game_documents, load_game_variants, AuthService, CollectionService, GameService, GamelistService,
+    MediaService, PatchService, SmartCollectionService, TitleService,
+};
+use crate::utils::{ApiError, FileDelivery};
 use actix_web::{web, HttpRequest, HttpResponse};
 use sqlx::PgPool;
+use std::path::PathBuf;
 use uuid::Uuid;
+use validator::Validate;
 
 pub async fn get_games(
     pool: web::Data<PgPool>,
     game_service: web::Data<GameService>,
-    query: web::Query<std::collections::HashMap<String, String>>,
+    collection_service: web::Data<CollectionService>,
+    smart_collection_service: web::Data<SmartCollectionService>,
+    query: web::Query<GameQuery>,
+    user_id: web::ReqData<Uuid>,
 ) -> Result<HttpResponse, ApiError> {
-    let console_id = query.get("console").map(|s| s.as_str());
-    let limit = query
-        .get("limit")
-        .and_then(|s| s.parse::<i64>().ok())
-        .unwrap_or(50);
-    let offset = query
-        .get("offset")
-        .and_then(|s| s.parse::<i64>().ok())
-        .unwrap_or(0);
-
-    let (games, total) = game_service
-        .get_games(&pool, console_id, limit, offset)
-        .await?;
+    if let Some(collection_id) = &query.collection {
+        collection_service
+            .get_visible_collection(&pool, collection_id, &user_id)
+            .await?;
+    }
+
+    let smart_collection = match &query.smart_collection {
+        Some(id) => Some(
+            smart_collection_service
+                .get_visible_smart_collection(&pool, id, &user_id)
+                .await?,
+        ),
+        None => None,
+    };
+    let rule = smart_collection.as_ref().map(|c| &c.rules.0);
 
-    Ok(HttpResponse::Ok().json(GameListResponse { games, total }))
+    let (games, total, facets) = game_service.get_games(&pool, &query, &user_id, rule).await?;
+
+    Ok(HttpResponse::Ok().json(GameListResponse {
+        games,
+        total,
+        facets,
+    }))
 }
 
 pub async fn get_game(
     pool: web::Data<PgPool>,
     game_service: web::Data<GameService>,
     game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    load_game_variants(&pool, std::slice::from_mut(&mut game), &user_id).await?;
+    load_game_cheats(&pool, std::slice::from_mut(&mut game), &user_id).await?;
+    load_game_documents(&pool, std::slice::from_mut(&mut game)).await?;
+    Ok(HttpResponse::Ok().json(game))
+}
+
+pub async fn update_game(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateGameRequest>,
 ) -> Result<HttpResponse, ApiError> {
-    let game = game_service.get_game(&pool, &game_id).await?;
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()?;
+
+    let game = game_service
+        .update_game(&pool, &game_id, &req, &user_id)
+        .await?;
+
     Ok(HttpResponse::Ok().json(game))
 }
 
+pub async fn get_game_history(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let edits = game_service.get_game_history(&pool, &game_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "edits": edits })))
+}
+
+/// Streams a ROM with a checksum ETag so emulators revalidate instead of re-downloading,
+/// byte ranges for CD images and compression for cartridge ROMs (see `FileDelivery`).
+/// With `?patch=<id>` the ROM is served with that soft patch applied.
 pub async fn get_rom(
     pool: web::Data<PgPool>,
     game_service: web::Data<GameService>,
+    patch_service: web::Data<PatchService>,
     game_id: web::Path<Uuid>,
-    _req: HttpRequest,
-) -> Result<NamedFile, ApiError> {
-    let game = game_service.get_game(&pool, &game_id).await?;
+    query: web::Query<RomQuery>,
+    user_id: web::ReqData<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    send_rom(&pool, &game_service, &patch_service, &game_id, query.patch.as_ref(), &user_id, &req).await
+}
+
+pub(crate) async fn send_rom(
+    pool: &PgPool,
+    game_service: &GameService,
+    patch_service: &PatchService,
+    game_id: &Uuid,
+    patch_id: Option<&Uuid>,
+    user_id: &Uuid,
+    req: &HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(pool, game_id, user_id).await?;
     let rom_path = game_service.get_rom_path(&game.console_id, &game.rom_filename);
+    let crc32 = game_service.ensure_rom_crc32(pool, &game).await?;
+
+    let (path, checksum) = match patch_id {
+        Some(patch_id) => {
+            let patch = patch_service.get_patch(pool, &game.id, patch_id).await?;
+            let path = patch_service
+                .patched_rom_path(&game, &patch, &rom_path, &crc32)
+                .await?;
+            // The patched image changes whenever either input does
+            (path, format!("{}+{}", crc32, patch.patch_crc32))
+        }
+        None => (PathBuf::from(rom_path), crc32),
+    };
 
-    NamedFile::open(&rom_path)
-        .map_err(|e| ApiError::NotFound(format!("ROM file not found: {}", e)))
+    FileDelivery {
+        path: &path,
+        checksum: &checksum,
+        // The URL survives the file being replaced, so clients must revalidate before reuse
+        cache_control: "private, no-cache",
+        compressible: is_compressible_rom(&game.rom_filename, game.rom_size_bytes as u64),
+    }
+    .respond(req)
+    .await
 }
 
 pub async fn get_consoles(
@@ -61,10 +149,32 @@ pub async fn get_consoles(
 pub async fn scan_roms(
     pool: web::Data<PgPool>,
     game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    gamelist_service: web::Data<GamelistService>,
+    title_service: web::Data<TitleService>,
 ) -> Result<HttpResponse, ApiError> {
     let count = game_service.scan_roms(&pool).await?;
+
+    // Pick up curated metadata from any gamelist.xml next to the ROMs
+    let mut gamelists = Vec::new();
+    for console in game_service.get_all_consoles(&pool).await? {
+        match gamelist_service
+            .import_console(&pool, &console, &game_service, &media_service)
+            .await
+        {
+            Ok(Some(summary)) => gamelists.push(summary),
+            Ok(None) => {}
+            Err(e) => log::warn!("Gamelist import failed for {}: {}", console.id, e),
+        }
+    }
+
+    // After the gamelists, which may have corrected titles
+    let grouped = title_service.assign_titles(&pool).await?;
+
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": format!("Scanned and added {} new games", count),
-        "count": count
+        "count": count,
+        "gamelists": gamelists,
+        "grouped": grouped
     })))
 }
@@ -0,0 +1,135 @@
+use crate::models::{
+    ControllerQuery, CreateInputProfileRequest, InputProfileQuery, UpdateGameInputRequest, UpdateInputProfileRequest,
+};
+use crate::services::{AuthService, GameService, InputService, CONTROLLER_LAYOUTS};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_controller_layouts() -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "layouts": &CONTROLLER_LAYOUTS })))
+}
+
+pub async fn get_input_profiles(
+    pool: web::Data<PgPool>,
+    input_service: web::Data<InputService>,
+    user_id: web::ReqData<Uuid>,
+    query: web::Query<InputProfileQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let profiles = input_service
+        .get_profiles(&pool, &user_id, query.layout.as_deref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "profiles": profiles })))
+}
+
+pub async fn create_input_profile(
+    pool: web::Data<PgPool>,
+    input_service: web::Data<InputService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateInputProfileRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let profile = input_service.create_profile(&pool, &user_id, &req).await?;
+    Ok(HttpResponse::Created().json(profile))
+}
+
+pub async fn update_input_profile(
+    pool: web::Data<PgPool>,
+    input_service: web::Data<InputService>,
+    profile_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateInputProfileRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let profile = input_service.get_profile(&pool, &user_id, &profile_id).await?;
+    let profile = input_service.update_profile(&pool, &profile, &req).await?;
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+pub async fn delete_input_profile(
+    pool: web::Data<PgPool>,
+    input_service: web::Data<InputService>,
+    profile_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let profile = input_service.get_profile(&pool, &user_id, &profile_id).await?;
+    input_service.delete_profile(&pool, &profile).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Input profile deleted" })))
+}
+
+/// The layout and bindings the user plays the game with
+pub async fn get_game_input(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    input_service: web::Data<InputService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let console = game_service.get_console(&pool, &game.console_id).await?;
+
+    let config = input_service.resolve_game_input(&pool, &console, &game, &user_id).await?;
+    Ok(HttpResponse::Ok().json(config))
+}
+
+pub async fn update_game_input(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    input_service: web::Data<InputService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateGameInputRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let console = game_service.get_console(&pool, &game.console_id).await?;
+
+    input_service
+        .set_game_override(&pool, &console, &game, &user_id, &req)
+        .await?;
+    let config = input_service.resolve_game_input(&pool, &console, &game, &user_id).await?;
+    Ok(HttpResponse::Ok().json(config))
+}
+
+pub async fn delete_game_input(
+    pool: web::Data<PgPool>,
+    input_service: web::Data<InputService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    input_service.delete_game_override(&pool, &user_id, &game_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Input override removed" })))
+}
+
+/// Finds the SDL mapping of a pad by GUID or by USB vendor and product id
+pub async fn find_controllers(
+    pool: web::Data<PgPool>,
+    input_service: web::Data<InputService>,
+    query: web::Query<ControllerQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let controllers = input_service.find_controllers(&pool, &query).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "controllers": controllers })))
+}
+
+/// Imports SDL's `gamecontrollerdb.txt`, sent as the raw request body
+pub async fn import_controller_db(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    input_service: web::Data<InputService>,
+    user_id: web::ReqData<Uuid>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    let contents = std::str::from_utf8(&body)
+        .map_err(|_| ApiError::BadRequest("Controller database must be UTF-8 text".to_string()))?;
+
+    let summary = input_service.import_controller_db(&pool, contents).await?;
+    Ok(HttpResponse::Ok().json(summary))
+}
@@ -0,0 +1,81 @@
+use crate::models::RetroArchExportParams;
+use crate::services::{AuthService, GameService, LaunchBoxService, RetroArchService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct LaunchBoxImportParams {
+    console: Option<String>,
+}
+
+pub async fn get_retroarch_playlists(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    retroarch_service: web::Data<RetroArchService>,
+    query: web::Query<RetroArchExportParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let mut playlists = Vec::new();
+    for console in game_service.get_all_consoles(&pool).await? {
+        let export = retroarch_service
+            .build_playlist(&pool, &console, &game_service, &query)
+            .await?;
+
+        if !export.playlist.items.is_empty() {
+            playlists.push(export);
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "playlists": playlists })))
+}
+
+pub async fn download_retroarch_playlist(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    retroarch_service: web::Data<RetroArchService>,
+    console_id: web::Path<String>,
+    query: web::Query<RetroArchExportParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let console = game_service.get_console(&pool, &console_id).await?;
+    let export = retroarch_service
+        .build_playlist(&pool, &console, &game_service, &query)
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", export.filename),
+        ))
+        .json(export.playlist))
+}
+
+pub async fn import_launchbox(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    launchbox_service: web::Data<LaunchBoxService>,
+    query: web::Query<LaunchBoxImportParams>,
+    user_id: web::ReqData<Uuid>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let xml = std::str::from_utf8(&body)
+        .map_err(|_| ApiError::BadRequest("Platform file must be UTF-8 XML".to_string()))?;
+
+    let summary = launchbox_service
+        .import_platform(&pool, xml, query.console.as_deref(), &game_service)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
@@ -0,0 +1,63 @@
+use crate::models::LibraryExportParams;
+use crate::services::{AuthService, GameService, LibraryService, MediaService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn export_library(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    library_service: web::Data<LibraryService>,
+    query: web::Query<LibraryExportParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let format = query.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "zip" {
+        return Err(ApiError::ValidationError(format!(
+            "Invalid format '{}', expected json or zip",
+            format
+        )));
+    }
+
+    let bundle = library_service
+        .export_library(&pool, &game_service, &media_service)
+        .await?;
+    let filename = format!("library-{}.{}", bundle.exported_at.format("%Y%m%d"), format);
+
+    let mut response = HttpResponse::Ok();
+    response.insert_header((
+        "Content-Disposition",
+        format!("attachment; filename=\"{}\"", filename),
+    ));
+
+    if format == "zip" {
+        let archive = library_service.write_archive(&bundle, &media_service)?;
+        Ok(response.content_type("application/zip").body(archive))
+    } else {
+        Ok(response.json(bundle))
+    }
+}
+
+pub async fn import_library(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    library_service: web::Data<LibraryService>,
+    user_id: web::ReqData<Uuid>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let archive = library_service.read_archive(&body)?;
+    let summary = library_service
+        .import_library(&pool, &game_service, &media_service, &archive)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
@@ -0,0 +1,145 @@
+use crate::models::{GameMediaListResponse, GameMetadata};
+use crate::services::{AuthService, GameService, MediaService, MAX_MEDIA_SIZE_BYTES};
+use crate::utils::ApiError;
+use actix_files::NamedFile;
+use actix_multipart::Multipart;
+use actix_web::http::header::{HeaderValue, CACHE_CONTROL};
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures_util::stream::StreamExt;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn get_game_media(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let media = media_service.get_game_media(&pool, &game.id).await?;
+
+    Ok(HttpResponse::Ok().json(GameMediaListResponse {
+        media: media
+            .into_iter()
+            .map(|m| media_service.to_response(m))
+            .collect(),
+    }))
+}
+
+pub async fn upload_game_media(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    media_service: web::Data<MediaService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    let game = game_service.get_game(&pool, &game_id).await?;
+
+    let mut kind: Option<String> = None;
+    let mut data: Option<Vec<u8>> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let field_name = field.name().to_string();
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            if bytes.len() + chunk.len() > MAX_MEDIA_SIZE_BYTES {
+                return Err(ApiError::BadRequest("Image exceeds maximum size".to_string()));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        match field_name.as_str() {
+            "kind" => {
+                kind = Some(
+                    String::from_utf8(bytes)
+                        .map_err(|_| ApiError::BadRequest("Invalid kind format".to_string()))?,
+                );
+            }
+            "file" => data = Some(bytes),
+            _ => {}
+        }
+    }
+
+    let kind = kind.ok_or_else(|| ApiError::BadRequest("Missing kind".to_string()))?;
+    let data = data.ok_or_else(|| ApiError::BadRequest("Missing file".to_string()))?;
+
+    let media = media_service
+        .create_media(pool.get_ref(), &game.id, kind.trim(), data, Some(&user_id))
+        .await?;
+
+    // New box art becomes the cover, unless an admin locked the cover URL
+    if media.kind == "box_art" {
+        let metadata = GameMetadata {
+            cover_url: Some(media_service.cover_url(&media.content_hash)),
+            ..Default::default()
+        };
+        game_service
+            .apply_metadata(pool.get_ref(), &game, &metadata, "media")
+            .await?;
+    }
+
+    Ok(HttpResponse::Created().json(media_service.to_response(media)))
+}
+
+pub async fn delete_game_media(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    media_service: web::Data<MediaService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    let (game_id, media_id) = path.into_inner();
+
+    let media = media_service.get_media(&pool, &media_id).await?;
+    if media.game_id != game_id {
+        return Err(ApiError::NotFound("Media not found".to_string()));
+    }
+
+    media_service.delete_media(&pool, &media).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Media deleted" })))
+}
+
+pub async fn get_media_file(
+    media_service: web::Data<MediaService>,
+    filename: web::Path<String>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    serve_media(&media_service, false, &filename, &req)
+}
+
+pub async fn get_media_thumbnail(
+    media_service: web::Data<MediaService>,
+    filename: web::Path<String>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    serve_media(&media_service, true, &filename, &req)
+}
+
+fn serve_media(
+    media_service: &MediaService,
+    thumbnail: bool,
+    filename: &str,
+    req: &HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let path = media_service.get_media_file_path(thumbnail, filename)?;
+    let file = NamedFile::open(&path)
+        .map_err(|_| ApiError::NotFound("Media not found".to_string()))?;
+
+    // Filenames are content hashes, so a given URL never changes
+    let mut response = file.into_response(req);
+    response.headers_mut().insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=31536000, immutable"),
+    );
+
+    Ok(response)
+}
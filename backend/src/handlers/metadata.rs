@@ -0,0 +1,96 @@
+use crate::models::{MetadataEnrichParams, MetadataImportParams, MetadataRecord};
+use crate::services::{AuthService, GameService, LocalMetadataProvider};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn import_metadata(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    metadata_provider: web::Data<LocalMetadataProvider>,
+    query: web::Query<MetadataImportParams>,
+    user_id: web::ReqData<Uuid>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    if let Some(console_id) = &query.console {
+        game_service.get_console(&pool, console_id).await?;
+    }
+
+    let source = query.source.as_deref().unwrap_or("local");
+    let body = std::str::from_utf8(&body)
+        .map_err(|_| ApiError::BadRequest("Dataset must be UTF-8 text".to_string()))?;
+
+    let imported = match query.format.as_deref().unwrap_or("dat") {
+        "dat" => {
+            let console_id = query.console.as_deref().ok_or_else(|| {
+                ApiError::BadRequest("A .dat import needs ?console=<id>".to_string())
+            })?;
+            metadata_provider
+                .import_dat(&pool, console_id, body, source)
+                .await?
+        }
+        "json" => {
+            let records: Vec<MetadataRecord> = serde_json::from_str(body)
+                .map_err(|e| ApiError::BadRequest(format!("Invalid metadata bundle: {}", e)))?;
+            metadata_provider
+                .import_records(&pool, query.console.as_deref(), &records, source)
+                .await?
+        }
+        other => {
+            return Err(ApiError::BadRequest(format!(
+                "Unsupported dataset format '{}', expected dat or json",
+                other
+            )))
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": format!("Imported {} metadata entries", imported),
+        "count": imported
+    })))
+}
+
+/// Starts the enrichment job in the background; hashing large disc images can take a while.
+/// Every applied change is recorded in the game edit history.
+pub async fn enrich_metadata(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    metadata_provider: web::Data<LocalMetadataProvider>,
+    query: web::Query<MetadataEnrichParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    if let Some(console_id) = &query.console {
+        game_service.get_console(&pool, console_id).await?;
+    }
+
+    let pool = pool.get_ref().clone();
+    let game_service = game_service.get_ref().clone();
+    let provider = metadata_provider.get_ref().clone();
+    let console_id = query.into_inner().console;
+
+    actix_web::rt::spawn(async move {
+        match game_service
+            .enrich_metadata(&pool, &provider, console_id.as_deref())
+            .await
+        {
+            Ok(summary) => log::info!(
+                "Metadata enrichment finished: {} scanned, {} matched, {} updated",
+                summary.scanned,
+                summary.matched,
+                summary.updated
+            ),
+            Err(e) => log::error!("Metadata enrichment failed: {}", e),
+        }
+    });
+
+    Ok(HttpResponse::Accepted().json(serde_json::json!({
+        "message": "Metadata enrichment started"
+    })))
+}
@@ -1,7 +1,51 @@
 pub mod auth;
+pub mod bios;
+pub mod cheats;
+pub mod collections;
+pub mod consoles;
+pub mod documents;
+pub mod downloads;
+pub mod duplicates;
+pub mod emulatorjs;
+pub mod gamelist;
 pub mod games;
+pub mod input;
+pub mod interop;
+pub mod library;
+pub mod media;
+pub mod metadata;
+pub mod patches;
+pub mod reviews;
 pub mod saves;
+pub mod settings;
+pub mod shaders;
+pub mod smart_collections;
+pub mod tags;
+pub mod titles;
+pub mod visibility;
 
 pub use auth::*;
+pub use bios::*;
+pub use cheats::*;
+pub use collections::*;
+pub use consoles::*;
+pub use documents::*;
+pub use downloads::*;
+pub use duplicates::*;
+pub use emulatorjs::*;
+pub use gamelist::*;
 pub use games::*;
+pub use input::*;
+pub use interop::*;
+pub use library::*;
+pub use media::*;
+pub use metadata::*;
+pub use patches::*;
+pub use reviews::*;
 pub use saves::*;
+pub use settings::*;
+pub use shaders::*;
+pub use smart_collections::*;
+pub use tags::*;
+pub use titles::*;
+pub use visibility::*;
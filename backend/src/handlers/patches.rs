@@ -0,0 +1,126 @@
+use crate::models::{CreateRomPatchRequest, RomPatchUpload, UpdateRomPatchRequest};
+use crate::services::{AuthService, GameService, PatchService, SaveService, MAX_PATCH_SIZE_BYTES};
+use crate::utils::ApiError;
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use futures_util::stream::StreamExt;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_game_patches(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    patch_service: web::Data<PatchService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let patches = patch_service.get_patches(&pool, &game.id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "patches": patches })))
+}
+
+pub async fn upload_game_patch(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    patch_service: web::Data<PatchService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    let game = game_service.get_game(&pool, &game_id).await?;
+
+    let mut name: Option<String> = None;
+    let mut description: Option<String> = None;
+    let mut data: Option<Vec<u8>> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let field_name = field.name().to_string();
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            if bytes.len() + chunk.len() > MAX_PATCH_SIZE_BYTES {
+                return Err(ApiError::BadRequest("Patch exceeds maximum size".to_string()));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        match field_name.as_str() {
+            "name" => {
+                name = Some(
+                    String::from_utf8(bytes)
+                        .map_err(|_| ApiError::BadRequest("Invalid name format".to_string()))?,
+                );
+            }
+            "description" => {
+                description = Some(
+                    String::from_utf8(bytes)
+                        .map_err(|_| ApiError::BadRequest("Invalid description format".to_string()))?,
+                );
+            }
+            "file" => data = Some(bytes),
+            _ => {}
+        }
+    }
+
+    let request = CreateRomPatchRequest {
+        name: name.ok_or_else(|| ApiError::BadRequest("Missing name".to_string()))?,
+        description,
+    };
+    request
+        .validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+    let data = data.ok_or_else(|| ApiError::BadRequest("Missing file".to_string()))?;
+
+    let rom_path = game_service.get_rom_path(&game.console_id, &game.rom_filename);
+    let base_crc32 = game_service.ensure_rom_crc32(&pool, &game).await?;
+
+    let patch = patch_service
+        .create_patch(&pool, &game, &rom_path, &base_crc32, RomPatchUpload { request, data }, &user_id)
+        .await?;
+
+    Ok(HttpResponse::Created().json(patch))
+}
+
+pub async fn update_game_patch(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    patch_service: web::Data<PatchService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateRomPatchRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+    let (game_id, patch_id) = path.into_inner();
+
+    let patch = patch_service.get_patch(&pool, &game_id, &patch_id).await?;
+    let patch = patch_service.update_patch(&pool, &patch, &req).await?;
+
+    Ok(HttpResponse::Ok().json(patch))
+}
+
+/// Deletes a patch along with every save state made while playing it
+pub async fn delete_game_patch(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    patch_service: web::Data<PatchService>,
+    save_service: web::Data<SaveService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    let (game_id, patch_id) = path.into_inner();
+
+    let patch = patch_service.get_patch(&pool, &game_id, &patch_id).await?;
+    save_service.delete_patch_save_files(&pool, &patch.id).await?;
+    patch_service.delete_patch(&pool, &patch).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Patch deleted" })))
+}
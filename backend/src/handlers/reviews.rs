@@ -0,0 +1,100 @@
+use crate::models::{UpdatePlayStatusRequest, UpsertReviewRequest};
+use crate::services::{GameService, ReviewService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_game_reviews(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    review_service: web::Data<ReviewService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let reviews = review_service.get_game_reviews(&pool, &game.id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "user_rating": game.user_rating,
+        "user_rating_count": game.user_rating_count,
+        "reviews": reviews,
+    })))
+}
+
+pub async fn upsert_review(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    review_service: web::Data<ReviewService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpsertReviewRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let review = review_service
+        .upsert_review(&pool, &user_id, &game.id, &req)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(review))
+}
+
+pub async fn delete_review(
+    pool: web::Data<PgPool>,
+    review_service: web::Data<ReviewService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    review_service.delete_review(&pool, &user_id, &game_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Review deleted" })))
+}
+
+pub async fn get_play_status(
+    pool: web::Data<PgPool>,
+    review_service: web::Data<ReviewService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let status = review_service.get_status(&pool, &user_id, &game_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "status": status })))
+}
+
+pub async fn update_play_status(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    review_service: web::Data<ReviewService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdatePlayStatusRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let status = review_service
+        .set_status(&pool, &user_id, &game.id, &req)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+pub async fn clear_play_status(
+    pool: web::Data<PgPool>,
+    review_service: web::Data<ReviewService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    review_service.clear_status(&pool, &user_id, &game_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Play status cleared" })))
+}
+
+pub async fn get_user_profile(
+    pool: web::Data<PgPool>,
+    review_service: web::Data<ReviewService>,
+    user_id: web::Path<Uuid>,
+    viewer_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let profile = review_service.get_profile(&pool, &user_id, &viewer_id).await?;
+    Ok(HttpResponse::Ok().json(profile))
+}
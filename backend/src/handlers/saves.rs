@@ -1,5 +1,5 @@
-use crate::models::{SaveState, SaveStateListResponse};
-use crate::services::SaveService;
+use crate::models::{NewSaveState, SaveState, SaveStateListResponse};
+use crate::services::{PatchService, SaveService};
 use crate::utils::ApiError;
 use actix_files::NamedFile;
 use actix_multipart::Multipart;
@@ -12,6 +12,8 @@ use uuid::Uuid;
 #[derive(Deserialize)]
 pub struct SaveQueryParams {
     game_id: Uuid,
+    /// Saves of a patched variant instead of the base game
+    patch_id: Option<Uuid>,
 }
 
 pub async fn get_user_saves(
@@ -28,7 +30,7 @@ pub async fn get_user_saves(
         .ok_or_else(|| ApiError::Unauthorized("User not authenticated".to_string()))?;
 
     let saves = save_service
-        .get_user_saves(&pool, &user_id, &query.game_id)
+        .get_user_saves(&pool, &user_id, &query.game_id, query.patch_id.as_ref())
         .await?;
 
     Ok(HttpResponse::Ok().json(SaveStateListResponse { saves }))
@@ -37,6 +39,7 @@ pub async fn get_user_saves(
 pub async fn upload_save_state(
     pool: web::Data<PgPool>,
     save_service: web::Data<SaveService>,
+    patch_service: web::Data<PatchService>,
     mut payload: Multipart,
     req: HttpRequest,
 ) -> Result<HttpResponse, ApiError> {
@@ -47,6 +50,7 @@ pub async fn upload_save_state(
         .ok_or_else(|| ApiError::Unauthorized("User not authenticated".to_string()))?;
 
     let mut game_id: Option<Uuid> = None;
+    let mut patch_id: Option<Uuid> = None;
     let mut slot: Option<i32> = None;
     let mut save_data: Option<Vec<u8>> = None;
     let mut screenshot: Option<Vec<u8>> = None;
@@ -70,6 +74,21 @@ pub async fn upload_save_state(
                         .map_err(|_| ApiError::BadRequest("Invalid game_id UUID".to_string()))?,
                 );
             }
+            "patch_id" => {
+                let mut bytes = Vec::new();
+                while let Some(chunk) = field.next().await {
+                    let chunk = chunk.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                    bytes.extend_from_slice(&chunk);
+                }
+                let patch_id_str = String::from_utf8(bytes)
+                    .map_err(|_| ApiError::BadRequest("Invalid patch_id format".to_string()))?;
+                if !patch_id_str.is_empty() {
+                    patch_id = Some(
+                        Uuid::parse_str(&patch_id_str)
+                            .map_err(|_| ApiError::BadRequest("Invalid patch_id UUID".to_string()))?,
+                    );
+                }
+            }
             "slot" => {
                 let mut bytes = Vec::new();
                 while let Some(chunk) = field.next().await {
@@ -123,8 +142,23 @@ pub async fn upload_save_state(
     let save_data =
         save_data.ok_or_else(|| ApiError::BadRequest("Missing save_data".to_string()))?;
 
+    if let Some(patch_id) = &patch_id {
+        patch_service.get_patch(&pool, &game_id, patch_id).await?;
+    }
+
     let save = save_service
-        .create_save_state(&pool, &user_id, &game_id, slot, save_data, screenshot, description)
+        .create_save_state(
+            &pool,
+            &user_id,
+            NewSaveState {
+                game_id,
+                patch_id,
+                slot,
+                save_data,
+                screenshot,
+                description,
+            },
+        )
         .await?;
 
     Ok(HttpResponse::Ok().json(save))
@@ -142,15 +176,17 @@ pub async fn download_save_state(
         .cloned()
         .ok_or_else(|| ApiError::Unauthorized("User not authenticated".to_string()))?;
 
-    let save = save_service.get_save_state(&pool, &save_id).await?;
-
-    // Verify ownership
-    if save.user_id != user_id {
-        return Err(ApiError::Forbidden(
-            "Not authorized to access this save state".to_string(),
-        ));
-    }
+    open_save_file(&pool, &save_service, &save_id, &user_id).await
+}
 
+/// Opens the save file after checking that `user_id` owns it
+pub(crate) async fn open_save_file(
+    pool: &PgPool,
+    save_service: &SaveService,
+    save_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<NamedFile, ApiError> {
+    let save = save_service.get_owned_save_state(pool, save_id, user_id).await?;
     let save_path = save_service.get_save_file_path(&save.save_data_filename);
 
     NamedFile::open(&save_path)
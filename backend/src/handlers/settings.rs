@@ -0,0 +1,156 @@
+use crate::models::{SettingsOverride, UpdateSettingsRequest};
+use crate::services::{resolve_emulator_config, ConsoleService, GameService, SettingsService, ShaderService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_settings(
+    pool: web::Data<PgPool>,
+    settings_service: web::Data<SettingsService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let settings = settings_service.get_settings(&pool, &user_id).await?;
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+pub async fn update_settings(
+    pool: web::Data<PgPool>,
+    settings_service: web::Data<SettingsService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateSettingsRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+
+    let settings = settings_service.update_settings(&pool, &user_id, &req).await?;
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+pub async fn get_console_settings_overrides(
+    pool: web::Data<PgPool>,
+    settings_service: web::Data<SettingsService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let overrides = settings_service.get_console_overrides(&pool, &user_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "overrides": overrides })))
+}
+
+pub async fn get_console_settings(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    settings_service: web::Data<SettingsService>,
+    console_id: web::Path<String>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let console = game_service.get_console(&pool, &console_id).await?;
+
+    let settings_override = settings_service
+        .get_console_override(&pool, &user_id, &console.id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("No settings for this console".to_string()))?;
+    Ok(HttpResponse::Ok().json(settings_override))
+}
+
+pub async fn update_console_settings(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    settings_service: web::Data<SettingsService>,
+    shader_service: web::Data<ShaderService>,
+    console_id: web::Path<String>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<SettingsOverride>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+    let console = game_service.get_console(&pool, &console_id).await?;
+    if let Some(shader) = &req.shader {
+        shader_service.check_shader_setting(&pool, shader, &console.id).await?;
+    }
+
+    let settings_override = settings_service
+        .set_console_override(&pool, &user_id, &console.id, &req)
+        .await?;
+    Ok(HttpResponse::Ok().json(settings_override))
+}
+
+pub async fn delete_console_settings(
+    pool: web::Data<PgPool>,
+    settings_service: web::Data<SettingsService>,
+    console_id: web::Path<String>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    settings_service.delete_console_override(&pool, &user_id, &console_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Console settings reset" })))
+}
+
+pub async fn get_game_settings_override(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    settings_service: web::Data<SettingsService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+
+    let settings_override = settings_service
+        .get_game_override(&pool, &user_id, &game.id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("No settings for this game".to_string()))?;
+    Ok(HttpResponse::Ok().json(settings_override))
+}
+
+pub async fn update_game_settings(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    settings_service: web::Data<SettingsService>,
+    shader_service: web::Data<ShaderService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<SettingsOverride>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    if let Some(shader) = &req.shader {
+        shader_service.check_shader_setting(&pool, shader, &game.console_id).await?;
+    }
+
+    let settings_override = settings_service
+        .set_game_override(&pool, &user_id, &game.id, &req)
+        .await?;
+    Ok(HttpResponse::Ok().json(settings_override))
+}
+
+pub async fn delete_game_settings(
+    pool: web::Data<PgPool>,
+    settings_service: web::Data<SettingsService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    settings_service.delete_game_override(&pool, &user_id, &game_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Game settings reset" })))
+}
+
+/// The settings the player starts the game with, every layer resolved
+pub async fn get_effective_game_settings(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    console_service: web::Data<ConsoleService>,
+    settings_service: web::Data<SettingsService>,
+    shader_service: web::Data<ShaderService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let console = game_service.get_console(&pool, &game.console_id).await?;
+    let overrides = console_service.get_game_override(&pool, &game.id).await?;
+    // Only the shader and core options are used, BIOS state doesn't matter here
+    let emulator = resolve_emulator_config(&game, &console, overrides.map(|o| o.config.0), &[]);
+
+    let settings = settings_service
+        .resolve_game_settings(&pool, &shader_service, &game, &emulator, &user_id)
+        .await?;
+    Ok(HttpResponse::Ok().json(settings))
+}
@@ -0,0 +1,184 @@
+use crate::models::{
+    CreateShaderPresetRequest, ShaderPresetQuery, UpdateShaderParametersRequest, UpdateShaderPresetRequest,
+};
+use crate::services::{AuthService, ShaderService, MAX_SHADER_UPLOAD_BYTES};
+use crate::utils::ApiError;
+use actix_files::NamedFile;
+use actix_multipart::Multipart;
+use actix_web::{mime, web, HttpRequest, HttpResponse};
+use futures_util::stream::StreamExt;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_shader_presets(
+    pool: web::Data<PgPool>,
+    shader_service: web::Data<ShaderService>,
+    query: web::Query<ShaderPresetQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let presets = shader_service.get_presets(&pool, query.console.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "presets": presets })))
+}
+
+/// The preset with the values its parameters take for the user
+pub async fn get_shader_preset(
+    pool: web::Data<PgPool>,
+    shader_service: web::Data<ShaderService>,
+    preset_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let preset = shader_service.get_preset(&pool, &preset_id).await?;
+    let resolved = shader_service.resolve_preset(&pool, preset, &user_id).await?;
+
+    Ok(HttpResponse::Ok().json(resolved))
+}
+
+/// Takes a preset with its passes and textures as `file` fields, or a ZIP of a shader folder,
+/// plus optional `name`, `description`, `consoles` (comma separated) and `preset` fields
+pub async fn upload_shader_preset(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    shader_service: web::Data<ShaderService>,
+    user_id: web::ReqData<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let mut request = CreateShaderPresetRequest::default();
+    let mut uploads: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut total = 0;
+
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let field_name = field.name().to_string();
+        let filename = field
+            .content_disposition()
+            .get_filename()
+            .unwrap_or_default()
+            .to_string();
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            total += chunk.len();
+            if total > MAX_SHADER_UPLOAD_BYTES {
+                return Err(ApiError::BadRequest("Shader upload exceeds maximum size".to_string()));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        if field_name == "file" {
+            uploads.push((filename, bytes));
+            continue;
+        }
+        let text = String::from_utf8(bytes)
+            .map_err(|_| ApiError::BadRequest(format!("Invalid {} format", field_name)))?;
+        match field_name.as_str() {
+            "name" => request.name = Some(text),
+            "description" => request.description = Some(text),
+            "preset" => request.preset = Some(text),
+            "consoles" => request.consoles.extend(
+                text.split(',')
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .map(str::to_string),
+            ),
+            _ => {}
+        }
+    }
+
+    request
+        .validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+    if uploads.is_empty() {
+        return Err(ApiError::BadRequest("Missing file".to_string()));
+    }
+
+    let preset = shader_service.create_preset(&pool, &request, uploads, &user_id).await?;
+    Ok(HttpResponse::Created().json(preset))
+}
+
+pub async fn update_shader_preset(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    shader_service: web::Data<ShaderService>,
+    preset_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateShaderPresetRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()
+        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+
+    let preset = shader_service.get_preset(&pool, &preset_id).await?;
+    let preset = shader_service.update_preset(&pool, &preset, &req).await?;
+
+    Ok(HttpResponse::Ok().json(preset))
+}
+
+pub async fn delete_shader_preset(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    shader_service: web::Data<ShaderService>,
+    preset_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let preset = shader_service.get_preset(&pool, &preset_id).await?;
+    shader_service.delete_preset(&pool, &preset).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Shader preset deleted" })))
+}
+
+pub async fn update_shader_parameters(
+    pool: web::Data<PgPool>,
+    shader_service: web::Data<ShaderService>,
+    preset_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateShaderParametersRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let preset = shader_service.get_preset(&pool, &preset_id).await?;
+    shader_service
+        .set_user_parameters(&pool, &user_id, &preset, &req.parameters)
+        .await?;
+
+    let resolved = shader_service.resolve_preset(&pool, preset, &user_id).await?;
+    Ok(HttpResponse::Ok().json(resolved))
+}
+
+pub async fn delete_shader_parameters(
+    pool: web::Data<PgPool>,
+    shader_service: web::Data<ShaderService>,
+    preset_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let preset = shader_service.get_preset(&pool, &preset_id).await?;
+    shader_service.delete_user_parameters(&pool, &user_id, &preset.id).await?;
+
+    let resolved = shader_service.resolve_preset(&pool, preset, &user_id).await?;
+    Ok(HttpResponse::Ok().json(resolved))
+}
+
+/// Serves the preset file, a pass source or a texture by its path in the preset's folder
+pub async fn get_shader_file(
+    pool: web::Data<PgPool>,
+    shader_service: web::Data<ShaderService>,
+    path: web::Path<(Uuid, String)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let (preset_id, file) = path.into_inner();
+    let preset = shader_service.get_preset(&pool, &preset_id).await?;
+
+    let file_path = shader_service
+        .file_path(&preset, &file)
+        .ok_or_else(|| ApiError::NotFound("Shader file not found".to_string()))?;
+    let mut file = NamedFile::open(file_path)
+        .map_err(|_| ApiError::NotFound("Shader file not found".to_string()))?;
+    // Everything but textures was checked to be UTF-8 source on upload
+    if file.content_type().type_() != mime::IMAGE {
+        file = file.set_content_type(mime::TEXT_PLAIN_UTF_8);
+    }
+
+    Ok(file.into_response(&req))
+}
@@ -0,0 +1,114 @@
+use crate::models::{
+    CollectionQueryParams, CreateSmartCollectionRequest, GameQuery, SmartCollectionWithGames,
+    UpdateSmartCollectionRequest,
+};
+use crate::services::{GameService, SmartCollectionService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_smart_collections(
+    pool: web::Data<PgPool>,
+    smart_collection_service: web::Data<SmartCollectionService>,
+    query: web::Query<CollectionQueryParams>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let public = match query.scope.as_deref() {
+        None | Some("mine") => false,
+        Some("public") => true,
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid scope '{}', expected mine or public",
+                other
+            )))
+        }
+    };
+
+    let collections = smart_collection_service
+        .get_smart_collections(&pool, &user_id, public)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "smart_collections": collections })))
+}
+
+pub async fn create_smart_collection(
+    pool: web::Data<PgPool>,
+    smart_collection_service: web::Data<SmartCollectionService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateSmartCollectionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let collection = smart_collection_service
+        .create_smart_collection(&pool, &user_id, &req)
+        .await?;
+
+    Ok(HttpResponse::Created().json(collection))
+}
+
+/// Evaluates the rules for the requesting user. Accepts the same paging, sorting
+/// and filter parameters as `GET /games`.
+pub async fn get_smart_collection(
+    pool: web::Data<PgPool>,
+    game_service: web::Data<GameService>,
+    smart_collection_service: web::Data<SmartCollectionService>,
+    collection_id: web::Path<Uuid>,
+    query: web::Query<GameQuery>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let smart_collection = smart_collection_service
+        .get_visible_smart_collection(&pool, &collection_id, &user_id)
+        .await?;
+
+    let mut query = query.into_inner();
+    query.collection = None;
+    query.smart_collection = Some(smart_collection.id);
+    query.sort = query.sort.or(smart_collection.sort.map(|s| s.0));
+
+    let (games, total, _) = game_service
+        .get_games(&pool, &query, &user_id, Some(&smart_collection.rules))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(SmartCollectionWithGames {
+        smart_collection,
+        games,
+        total,
+    }))
+}
+
+pub async fn update_smart_collection(
+    pool: web::Data<PgPool>,
+    smart_collection_service: web::Data<SmartCollectionService>,
+    collection_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateSmartCollectionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let collection = smart_collection_service
+        .get_owned_smart_collection(&pool, &collection_id, &user_id)
+        .await?;
+    let collection = smart_collection_service
+        .update_smart_collection(&pool, &collection, &req)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(collection))
+}
+
+pub async fn delete_smart_collection(
+    pool: web::Data<PgPool>,
+    smart_collection_service: web::Data<SmartCollectionService>,
+    collection_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let collection = smart_collection_service
+        .get_owned_smart_collection(&pool, &collection_id, &user_id)
+        .await?;
+    smart_collection_service
+        .delete_smart_collection(&pool, &collection.id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Smart collection deleted" })))
+}
@@ -0,0 +1,175 @@
+use crate::models::{
+    CreateTagRequest, GameMetadata, MergeTagRequest, TagQueryParams, UpdateGameRequest, UpdateTagRequest,
+};
+use crate::services::{AuthService, GameService, TagService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_tags(
+    pool: web::Data<PgPool>,
+    tag_service: web::Data<TagService>,
+    query: web::Query<TagQueryParams>,
+) -> Result<HttpResponse, ApiError> {
+    let tags = tag_service.get_tags(&pool, query.kind.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "tags": tags })))
+}
+
+/// Anyone can create `user` tags, other kinds need an admin
+pub async fn create_tag(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    tag_service: web::Data<TagService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateTagRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    if req.kind != "user" {
+        auth_service.require_admin(&pool, &user_id).await?;
+    }
+
+    let tag = tag_service.create_tag(&pool, &req, &user_id).await?;
+    Ok(HttpResponse::Created().json(tag))
+}
+
+pub async fn update_tag(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    tag_service: web::Data<TagService>,
+    tag_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateTagRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()?;
+
+    let tag = tag_service.get_tag(&pool, &tag_id).await?;
+    let tag = tag_service.update_tag(&pool, &tag, &req).await?;
+
+    Ok(HttpResponse::Ok().json(tag))
+}
+
+pub async fn delete_tag(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    tag_service: web::Data<TagService>,
+    tag_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let tag = tag_service.get_tag(&pool, &tag_id).await?;
+    tag_service.delete_tag(&pool, &tag.id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Tag deleted" })))
+}
+
+pub async fn merge_tag(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    tag_service: web::Data<TagService>,
+    tag_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<MergeTagRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let source = tag_service.get_tag(&pool, &tag_id).await?;
+    let target = tag_service.get_tag(&pool, &req.into).await?;
+    let tag = tag_service.merge_tags(&pool, &source, &target).await?;
+
+    Ok(HttpResponse::Ok().json(tag))
+}
+
+pub async fn add_game_tag(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    tag_service: web::Data<TagService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let (game_id, tag_id) = path.into_inner();
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let tag = tag_service.get_tag(&pool, &tag_id).await?;
+
+    if tag.kind != "user" {
+        auth_service.require_admin(&pool, &user_id).await?;
+    }
+
+    if tag.kind == "genre" {
+        let mut genres = game.genres();
+        genres.push(tag.name.clone());
+        edit_genres(&pool, &game_service, &game_id, genres, &user_id).await?;
+    } else {
+        tag_service
+            .add_game_tag(&pool, &game.id, &tag.id, &user_id)
+            .await?;
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Tag added" })))
+}
+
+/// Admins can remove any tag, users only the `user` tags they added
+pub async fn remove_game_tag(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    tag_service: web::Data<TagService>,
+    path: web::Path<(Uuid, Uuid)>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let (game_id, tag_id) = path.into_inner();
+    let game = game_service.get_visible_game(&pool, &game_id, &user_id).await?;
+    let tag = tag_service.get_tag(&pool, &tag_id).await?;
+    let is_admin = auth_service.is_admin(&pool, &user_id).await?;
+
+    if !is_admin && tag.kind != "user" {
+        return Err(ApiError::Forbidden("Admin privileges required".to_string()));
+    }
+
+    if tag.kind == "genre" {
+        let genres = game
+            .tags
+            .iter()
+            .filter(|t| t.kind == "genre" && t.id != tag.id)
+            .map(|t| t.name.clone())
+            .collect();
+        edit_genres(&pool, &game_service, &game_id, genres, &user_id).await?;
+    } else {
+        let added_by = if is_admin { None } else { Some(&*user_id) };
+
+        if !tag_service
+            .remove_game_tag(&pool, &game.id, &tag.id, added_by)
+            .await?
+        {
+            return Err(ApiError::NotFound("Tag not found on this game".to_string()));
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Tag removed" })))
+}
+
+/// Genre tags go through the metadata pipeline, so the change is logged and locked like any admin edit
+async fn edit_genres(
+    pool: &PgPool,
+    game_service: &GameService,
+    game_id: &Uuid,
+    genres: Vec<String>,
+    user_id: &Uuid,
+) -> Result<(), ApiError> {
+    let request = UpdateGameRequest {
+        metadata: GameMetadata {
+            genres: Some(genres),
+            ..Default::default()
+        },
+        locked_fields: None,
+    };
+
+    game_service.update_game(pool, game_id, &request, user_id).await?;
+
+    Ok(())
+}
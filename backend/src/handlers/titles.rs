@@ -0,0 +1,65 @@
+use crate::models::{RegionPreference, SetGameTitleRequest};
+use crate::services::{AuthService, GameService, TitleService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_preferred_regions(
+    pool: web::Data<PgPool>,
+    title_service: web::Data<TitleService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let preferred_regions = title_service.get_preferred_regions(&pool, &user_id).await?;
+    Ok(HttpResponse::Ok().json(RegionPreference { preferred_regions }))
+}
+
+pub async fn update_preferred_regions(
+    pool: web::Data<PgPool>,
+    title_service: web::Data<TitleService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<RegionPreference>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+
+    let preferred_regions = title_service
+        .set_preferred_regions(&pool, &user_id, &req.preferred_regions)
+        .await?;
+    Ok(HttpResponse::Ok().json(RegionPreference { preferred_regions }))
+}
+
+pub async fn group_titles(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    title_service: web::Data<TitleService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let grouped = title_service.assign_titles(&pool).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": format!("Grouped {} games into titles", grouped),
+        "grouped": grouped
+    })))
+}
+
+/// Corrects the automatic grouping: moves a release to another title or splits it off
+pub async fn set_game_title(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    title_service: web::Data<TitleService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<SetGameTitleRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let game = game_service.get_game(&pool, &game_id).await?;
+    let title = title_service
+        .set_game_title(&pool, &game, req.title_id.as_ref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(title))
+}
@@ -0,0 +1,137 @@
+use crate::models::{
+    BulkVisibilityRequest, CreateUserGroupRequest, Game, UpdateGroupMembersRequest,
+    UpdateVisibilityRequest,
+};
+use crate::services::{AuthService, GameService, VisibilityService};
+use crate::utils::ApiError;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Returns the game if the user may change who sees it: its uploader or an admin
+async fn get_managed_game(
+    pool: &PgPool,
+    auth_service: &AuthService,
+    game_service: &GameService,
+    game_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Game, ApiError> {
+    let game = game_service.get_visible_game(pool, game_id, user_id).await?;
+
+    if game.uploaded_by != Some(*user_id) {
+        auth_service.require_admin(pool, user_id).await?;
+    }
+
+    Ok(game)
+}
+
+pub async fn get_game_visibility(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    visibility_service: web::Data<VisibilityService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let game = get_managed_game(&pool, &auth_service, &game_service, &game_id, &user_id).await?;
+
+    let visibility = visibility_service.get_game_visibility(&pool, &game.id).await?;
+    Ok(HttpResponse::Ok().json(visibility))
+}
+
+pub async fn update_game_visibility(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    game_service: web::Data<GameService>,
+    visibility_service: web::Data<VisibilityService>,
+    game_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateVisibilityRequest>,
+) -> Result<HttpResponse, ApiError> {
+    req.validate()?;
+    let game = get_managed_game(&pool, &auth_service, &game_service, &game_id, &user_id).await?;
+
+    visibility_service
+        .set_visibility(&pool, &[game.id], &req, None)
+        .await?;
+
+    let visibility = visibility_service.get_game_visibility(&pool, &game.id).await?;
+    Ok(HttpResponse::Ok().json(visibility))
+}
+
+pub async fn bulk_update_visibility(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    visibility_service: web::Data<VisibilityService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<BulkVisibilityRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()?;
+
+    let updated = visibility_service
+        .set_visibility(&pool, &req.game_ids, &req.update, req.uploaded_by.as_ref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": format!("Updated visibility of {} games", updated),
+        "updated": updated
+    })))
+}
+
+pub async fn get_user_groups(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    visibility_service: web::Data<VisibilityService>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    let groups = visibility_service.get_groups(&pool).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "groups": groups })))
+}
+
+pub async fn create_user_group(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    visibility_service: web::Data<VisibilityService>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<CreateUserGroupRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()?;
+
+    let group = visibility_service.create_group(&pool, &req).await?;
+    Ok(HttpResponse::Created().json(group))
+}
+
+pub async fn delete_user_group(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    visibility_service: web::Data<VisibilityService>,
+    group_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+
+    visibility_service.delete_group(&pool, &group_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Group deleted" })))
+}
+
+pub async fn update_user_group_members(
+    pool: web::Data<PgPool>,
+    auth_service: web::Data<AuthService>,
+    visibility_service: web::Data<VisibilityService>,
+    group_id: web::Path<Uuid>,
+    user_id: web::ReqData<Uuid>,
+    req: web::Json<UpdateGroupMembersRequest>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.require_admin(&pool, &user_id).await?;
+    req.validate()?;
+
+    visibility_service
+        .set_group_members(&pool, &group_id, &req.user_ids)
+        .await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Group members updated" })))
+}
@@ -7,10 +7,18 @@ mod services;
 mod utils;
 
 use actix_cors::Cors;
-use actix_web::{middleware::Logger, web, App, HttpServer};
+use actix_files::Files;
+use actix_web::{
+    middleware::{Compress, DefaultHeaders, Logger},
+    web, App, HttpServer,
+};
 use config::Config;
 use db::{create_pool, run_migrations};
-use services::{AuthService, GameService, SaveService};
+use services::{
+    AuthService, BiosService, CheatService, CollectionService, ConsoleService, DocumentService, DuplicateService, EmulatorJsService, GameService, GamelistService, InputService, LaunchBoxService,
+    LibraryService, LocalMetadataProvider, MediaService, PatchService, RetroArchService, ReviewService, SaveService, SettingsService, ShaderService,
+    SmartCollectionService, TagService, TitleService, VisibilityService,
+};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -33,7 +41,11 @@ async fn main() -> std::io::Result<()> {
         .expect("Failed to run migrations");
 
     // Create auth service
-    let auth_service = AuthService::new(config.jwt_secret.clone(), config.jwt_expiration);
+    let auth_service = AuthService::new(
+        config.jwt_secret.clone(),
+        config.jwt_expiration,
+        config.download_url_ttl,
+    );
 
     // Create game service
     let game_service = GameService::new(config.rom_storage_path.clone());
@@ -41,6 +53,77 @@ async fn main() -> std::io::Result<()> {
     // Create save service
     let save_service = SaveService::new(config.save_storage_path.clone());
 
+    // Create media service
+    let media_service = MediaService::new(config.media_storage_path.clone());
+
+    // Create patch service
+    let patch_service = PatchService::new(config.patch_storage_path.clone());
+
+    // Create BIOS service
+    let bios_service = BiosService::new(config.bios_storage_path.clone());
+
+    // Create document service
+    let document_service = DocumentService::new(config.document_storage_path.clone());
+
+    // Create shader preset service
+    let shader_service = ShaderService::new(config.shader_storage_path.clone());
+
+    // Create EmulatorJS asset service
+    let emulatorjs_service = EmulatorJsService::new(
+        config.emulatorjs_path.clone(),
+        config.emulatorjs_version.clone(),
+    );
+    match emulatorjs_service.current_version() {
+        Ok(version) => log::info!("Serving EmulatorJS {}", version),
+        Err(e) => log::warn!("{}", e),
+    }
+
+    // Create gamelist service
+    let gamelist_service = GamelistService::new(config.rom_storage_path.clone());
+
+    // Create RetroArch and LaunchBox interop services
+    let retroarch_service = RetroArchService::new(config.retroarch_rom_root.clone());
+    let launchbox_service = LaunchBoxService::new();
+
+    // Create offline metadata provider
+    let metadata_provider = LocalMetadataProvider::new();
+
+    // Create console service
+    let console_service = ConsoleService::new();
+
+    // Create collection service
+    let collection_service = CollectionService::new();
+
+    // Create smart collection service
+    let smart_collection_service = SmartCollectionService::new();
+
+    // Create review service
+    let review_service = ReviewService::new();
+
+    // Create tag service
+    let tag_service = TagService::new();
+
+    // Create visibility service
+    let visibility_service = VisibilityService::new();
+
+    // Create duplicate finder
+    let duplicate_service = DuplicateService::new();
+
+    // Create title service
+    let title_service = TitleService::new();
+
+    // Create library export/import service
+    let library_service = LibraryService::new();
+
+    // Create cheat service
+    let cheat_service = CheatService::new();
+
+    // Create input profile service
+    let input_service = InputService::new();
+
+    // Create settings service
+    let settings_service = SettingsService::new();
+
     let server_address = config.server_address();
     let cors_origin = config.cors_origin.clone();
 
@@ -51,7 +134,7 @@ async fn main() -> std::io::Result<()> {
         // Configure CORS
         let cors = Cors::default()
             .allowed_origin(&cors_origin)
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
+            .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
             .allowed_headers(vec![
                 actix_web::http::header::AUTHORIZATION,
                 actix_web::http::header::ACCEPT,
@@ -65,6 +148,28 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(auth_service.clone()))
             .app_data(web::Data::new(game_service.clone()))
             .app_data(web::Data::new(save_service.clone()))
+            .app_data(web::Data::new(media_service.clone()))
+            .app_data(web::Data::new(patch_service.clone()))
+            .app_data(web::Data::new(bios_service.clone()))
+            .app_data(web::Data::new(document_service.clone()))
+            .app_data(web::Data::new(emulatorjs_service.clone()))
+            .app_data(web::Data::new(gamelist_service.clone()))
+            .app_data(web::Data::new(retroarch_service.clone()))
+            .app_data(web::Data::new(launchbox_service.clone()))
+            .app_data(web::Data::new(metadata_provider.clone()))
+            .app_data(web::Data::new(console_service.clone()))
+            .app_data(web::Data::new(collection_service.clone()))
+            .app_data(web::Data::new(smart_collection_service.clone()))
+            .app_data(web::Data::new(review_service.clone()))
+            .app_data(web::Data::new(tag_service.clone()))
+            .app_data(web::Data::new(visibility_service.clone()))
+            .app_data(web::Data::new(duplicate_service.clone()))
+            .app_data(web::Data::new(title_service.clone()))
+            .app_data(web::Data::new(library_service.clone()))
+            .app_data(web::Data::new(cheat_service.clone()))
+            .app_data(web::Data::new(input_service.clone()))
+            .app_data(web::Data::new(settings_service.clone()))
+            .app_data(web::Data::new(shader_service.clone()))
             .wrap(cors)
             .wrap(Logger::default())
             .service(
@@ -87,13 +192,201 @@ async fn main() -> std::io::Result<()> {
                             .wrap(middleware::AuthMiddleware)
                             .route("", web::get().to(handlers::get_games))
                             .route("/{id}", web::get().to(handlers::get_game))
-                            .route("/{id}/rom", web::get().to(handlers::get_rom))
+                            .route("/{id}", web::patch().to(handlers::update_game))
+                            .route("/{id}/history", web::get().to(handlers::get_game_history))
+                            .route("/{id}/emulator", web::get().to(handlers::get_game_emulator_config))
+                            .route("/{id}/emulator", web::put().to(handlers::update_game_emulator_config))
+                            .route("/{id}/emulator", web::delete().to(handlers::delete_game_emulator_config))
+                            .route("/{id}/media", web::get().to(handlers::get_game_media))
+                            .route("/{id}/media", web::post().to(handlers::upload_game_media))
+                            .route("/{id}/media/{media_id}", web::delete().to(handlers::delete_game_media))
+                            .route("/{id}/patches", web::get().to(handlers::get_game_patches))
+                            .route("/{id}/patches", web::post().to(handlers::upload_game_patch))
+                            .route("/{id}/patches/{patch_id}", web::patch().to(handlers::update_game_patch))
+                            .route("/{id}/patches/{patch_id}", web::delete().to(handlers::delete_game_patch))
+                            .route("/{id}/cheats", web::get().to(handlers::get_game_cheats))
+                            .route("/{id}/cheats", web::post().to(handlers::create_game_cheat))
+                            .route("/{id}/cheats/import", web::post().to(handlers::import_game_cheats))
+                            .route("/{id}/cheats/{cheat_id}", web::patch().to(handlers::update_game_cheat))
+                            .route("/{id}/cheats/{cheat_id}", web::delete().to(handlers::delete_game_cheat))
+                            .route("/{id}/documents", web::get().to(handlers::get_game_documents))
+                            .route("/{id}/documents", web::post().to(handlers::upload_game_document))
+                            .route("/{id}/documents/{document_id}", web::patch().to(handlers::update_game_document))
+                            .route("/{id}/documents/{document_id}", web::delete().to(handlers::delete_game_document))
+                            .service(
+                                web::resource("/{id}/documents/{document_id}/file")
+                                    .wrap(Compress::default())
+                                    .route(web::get().to(handlers::get_game_document_file)),
+                            )
+                            .route(
+                                "/{id}/documents/{document_id}/thumbnail",
+                                web::get().to(handlers::get_game_document_thumbnail),
+                            )
+                            .route("/{id}/input", web::get().to(handlers::get_game_input))
+                            .route("/{id}/input", web::put().to(handlers::update_game_input))
+                            .route("/{id}/input", web::delete().to(handlers::delete_game_input))
+                            .route("/{id}/settings", web::get().to(handlers::get_effective_game_settings))
+                            .route("/{id}/reviews", web::get().to(handlers::get_game_reviews))
+                            .route("/{id}/review", web::put().to(handlers::upsert_review))
+                            .route("/{id}/review", web::delete().to(handlers::delete_review))
+                            .route("/{id}/status", web::get().to(handlers::get_play_status))
+                            .route("/{id}/status", web::put().to(handlers::update_play_status))
+                            .route("/{id}/status", web::delete().to(handlers::clear_play_status))
+                            .route("/{id}/tags/{tag_id}", web::put().to(handlers::add_game_tag))
+                            .route("/{id}/tags/{tag_id}", web::delete().to(handlers::remove_game_tag))
+                            .route("/{id}/title", web::put().to(handlers::set_game_title))
+                            .route("/{id}/visibility", web::get().to(handlers::get_game_visibility))
+                            .route("/{id}/visibility", web::put().to(handlers::update_game_visibility))
+                            .service(
+                                web::resource("/{id}/rom")
+                                    .wrap(Compress::default())
+                                    .route(web::get().to(handlers::get_rom)),
+                            )
                             .route("/scan", web::post().to(handlers::scan_roms)),
                     )
                     // Console routes
                     .service(
                         web::scope("/consoles")
-                            .route("", web::get().to(handlers::get_consoles)),
+                            .route("", web::get().to(handlers::get_consoles))
+                            .route("/{id}", web::get().to(handlers::get_console)),
+                    )
+                    // Self-hosted EmulatorJS. Threaded cores need SharedArrayBuffer, which only works
+                    // in a cross-origin isolated page, so every asset must be embeddable under COEP.
+                    .service(
+                        web::scope("/emulatorjs")
+                            .route("", web::get().to(handlers::get_emulatorjs_info))
+                            .service(
+                                web::scope("/assets")
+                                    .wrap(
+                                        DefaultHeaders::new()
+                                            // Release directories are versioned, so files never change
+                                            .add(("Cache-Control", "public, max-age=31536000, immutable"))
+                                            .add(("Cross-Origin-Opener-Policy", "same-origin"))
+                                            .add(("Cross-Origin-Embedder-Policy", "require-corp"))
+                                            .add(("Cross-Origin-Resource-Policy", "cross-origin")),
+                                    )
+                                    .service(Files::new("", emulatorjs_service.root())),
+                            ),
+                    )
+                    // BIOS routes
+                    .service(
+                        web::scope("/bios")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("", web::get().to(handlers::get_bios_status))
+                            .route("", web::post().to(handlers::upload_bios))
+                            .route("/{id}", web::delete().to(handlers::delete_bios))
+                            .route("/{id}/file", web::get().to(handlers::get_bios_file)),
+                    )
+                    // Tag routes
+                    .service(
+                        web::scope("/tags")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("", web::get().to(handlers::get_tags))
+                            .route("", web::post().to(handlers::create_tag))
+                            .route("/{id}", web::patch().to(handlers::update_tag))
+                            .route("/{id}", web::delete().to(handlers::delete_tag))
+                            .route("/{id}/merge", web::post().to(handlers::merge_tag)),
+                    )
+                    // Favorites routes
+                    .service(
+                        web::scope("/favorites")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("", web::get().to(handlers::get_favorites))
+                            .route("/{game_id}", web::put().to(handlers::add_favorite))
+                            .route("/{game_id}", web::delete().to(handlers::remove_favorite)),
+                    )
+                    // Collection routes
+                    .service(
+                        web::scope("/collections")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("", web::get().to(handlers::get_collections))
+                            .route("", web::post().to(handlers::create_collection))
+                            .route("/{id}", web::get().to(handlers::get_collection))
+                            .route("/{id}", web::patch().to(handlers::update_collection))
+                            .route("/{id}", web::delete().to(handlers::delete_collection))
+                            .route("/{id}/games", web::post().to(handlers::add_collection_game))
+                            .route("/{id}/games/{game_id}", web::delete().to(handlers::remove_collection_game))
+                            .route("/{id}/order", web::put().to(handlers::reorder_collection)),
+                    )
+                    // User profile routes
+                    .service(
+                        web::scope("/users")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("/me/regions", web::get().to(handlers::get_preferred_regions))
+                            .route("/me/regions", web::put().to(handlers::update_preferred_regions))
+                            .route("/{id}/profile", web::get().to(handlers::get_user_profile)),
+                    )
+                    // Input profile and controller routes
+                    .service(
+                        web::scope("/input")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("/layouts", web::get().to(handlers::get_controller_layouts))
+                            .route("/profiles", web::get().to(handlers::get_input_profiles))
+                            .route("/profiles", web::post().to(handlers::create_input_profile))
+                            .route("/profiles/{id}", web::patch().to(handlers::update_input_profile))
+                            .route("/profiles/{id}", web::delete().to(handlers::delete_input_profile))
+                            .route("/controllers", web::get().to(handlers::find_controllers)),
+                    )
+                    // Settings routes
+                    .service(
+                        web::scope("/settings")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("", web::get().to(handlers::get_settings))
+                            .route("", web::patch().to(handlers::update_settings))
+                            .route("/consoles", web::get().to(handlers::get_console_settings_overrides))
+                            .route("/consoles/{console_id}", web::get().to(handlers::get_console_settings))
+                            .route("/consoles/{console_id}", web::put().to(handlers::update_console_settings))
+                            .route("/consoles/{console_id}", web::delete().to(handlers::delete_console_settings))
+                            .route("/games/{game_id}", web::get().to(handlers::get_game_settings_override))
+                            .route("/games/{game_id}", web::put().to(handlers::update_game_settings))
+                            .route("/games/{game_id}", web::delete().to(handlers::delete_game_settings)),
+                    )
+                    // Shader preset routes
+                    .service(
+                        web::scope("/shaders")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("", web::get().to(handlers::get_shader_presets))
+                            .route("", web::post().to(handlers::upload_shader_preset))
+                            .route("/{id}", web::get().to(handlers::get_shader_preset))
+                            .route("/{id}", web::patch().to(handlers::update_shader_preset))
+                            .route("/{id}", web::delete().to(handlers::delete_shader_preset))
+                            .route("/{id}/parameters", web::put().to(handlers::update_shader_parameters))
+                            .route("/{id}/parameters", web::delete().to(handlers::delete_shader_parameters))
+                            .route("/{id}/files/{path:.*}", web::get().to(handlers::get_shader_file)),
+                    )
+                    // Smart collection routes
+                    .service(
+                        web::scope("/smart-collections")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("", web::get().to(handlers::get_smart_collections))
+                            .route("", web::post().to(handlers::create_smart_collection))
+                            .route("/{id}", web::get().to(handlers::get_smart_collection))
+                            .route("/{id}", web::patch().to(handlers::update_smart_collection))
+                            .route("/{id}", web::delete().to(handlers::delete_smart_collection)),
+                    )
+                    // Media routes (public, content-addressed)
+                    .service(
+                        web::scope("/media")
+                            .route("/thumbs/{filename}", web::get().to(handlers::get_media_thumbnail))
+                            .route("/{filename}", web::get().to(handlers::get_media_file)),
+                    )
+                    // Signed download routes: issuing needs a session, fetching only the signature
+                    .service(
+                        web::scope("/downloads")
+                            // Fetched by EmulatorJS from a cross-origin isolated page
+                            .wrap(DefaultHeaders::new().add(("Cross-Origin-Resource-Policy", "cross-origin")))
+                            .service(
+                                web::resource("")
+                                    .wrap(middleware::AuthMiddleware)
+                                    .route(web::post().to(handlers::create_download_url)),
+                            )
+                            .service(
+                                web::resource("/roms/{id}")
+                                    .wrap(Compress::default())
+                                    .route(web::get().to(handlers::download_signed_rom)),
+                            )
+                            .route("/saves/{id}", web::get().to(handlers::download_signed_save))
+                            .route("/bios/{id}", web::get().to(handlers::download_signed_bios)),
                     )
                     // Save state routes
                     .service(
@@ -104,6 +397,57 @@ async fn main() -> std::io::Result<()> {
                             .route("/{id}/download", web::get().to(handlers::download_save_state))
                             .route("/{id}", web::delete().to(handlers::delete_save_state)),
                     )
+                    // Admin import/export routes
+                    .service(
+                        web::scope("/admin")
+                            .wrap(middleware::AuthMiddleware)
+                            .route("/consoles", web::post().to(handlers::create_console))
+                            .route("/consoles/{id}", web::patch().to(handlers::update_console))
+                            .route("/consoles/{id}", web::delete().to(handlers::delete_console))
+                            .route("/duplicates", web::get().to(handlers::find_duplicates))
+                            .route("/duplicates/merge", web::post().to(handlers::merge_games))
+                            .route("/emulatorjs/cores", web::get().to(handlers::get_emulatorjs_cores))
+                            .route("/games/visibility", web::post().to(handlers::bulk_update_visibility))
+                            .route("/titles/group", web::post().to(handlers::group_titles))
+                            .route("/groups", web::get().to(handlers::get_user_groups))
+                            .route("/groups", web::post().to(handlers::create_user_group))
+                            .route("/groups/{id}", web::delete().to(handlers::delete_user_group))
+                            .route("/groups/{id}/members", web::put().to(handlers::update_user_group_members))
+                            .route("/gamelists/import", web::post().to(handlers::import_gamelists))
+                            .route("/gamelists/export", web::post().to(handlers::export_gamelists))
+                            .route("/gamelists/{console_id}", web::get().to(handlers::download_gamelist))
+                            .route("/retroarch/playlists", web::get().to(handlers::get_retroarch_playlists))
+                            .route(
+                                "/retroarch/playlists/{console_id}",
+                                web::get().to(handlers::download_retroarch_playlist),
+                            )
+                            .route("/library/export", web::get().to(handlers::export_library))
+                            .service(
+                                web::resource("/library/import")
+                                    // ZIP bundles carry the media files of the whole library
+                                    .app_data(web::PayloadConfig::new(512 * 1024 * 1024))
+                                    .route(web::post().to(handlers::import_library)),
+                            )
+                            .service(
+                                web::resource("/launchbox/import")
+                                    // Platform files for large libraries run to several megabytes
+                                    .app_data(web::PayloadConfig::new(50 * 1024 * 1024))
+                                    .route(web::post().to(handlers::import_launchbox)),
+                            )
+                            .service(
+                                web::resource("/metadata/import")
+                                    // libretro-database .dat files for big systems exceed 10 MB
+                                    .app_data(web::PayloadConfig::new(100 * 1024 * 1024))
+                                    .route(web::post().to(handlers::import_metadata)),
+                            )
+                            .route("/metadata/enrich", web::post().to(handlers::enrich_metadata))
+                            .service(
+                                web::resource("/controllers/import")
+                                    // SDL's gamecontrollerdb.txt is well over the default payload limit
+                                    .app_data(web::PayloadConfig::new(10 * 1024 * 1024))
+                                    .route(web::post().to(handlers::import_controller_db)),
+                            ),
+                    )
                     // Future routes
                     // .service(web::scope("/achievements").wrap(middleware::AuthMiddleware))
                     // .service(web::scope("/stats").wrap(middleware::AuthMiddleware))
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A registry entry joined with the uploaded copy, if any
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BiosFile {
+    pub id: Uuid,
+    pub console_id: String,
+    pub filename: String,
+    pub description: Option<String>,
+    pub required: bool,
+    /// Entries sharing a group are alternatives, one present file satisfies the group
+    pub choice_group: Option<String>,
+    /// Known-good dumps
+    pub md5_hashes: Vec<String>,
+    /// MD5 of the uploaded file, `None` when missing
+    pub md5: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub uploaded_by: Option<Uuid>,
+    pub uploaded_at: Option<DateTime<Utc>>,
+}
+
+impl BiosFile {
+    pub fn is_present(&self) -> bool {
+        self.md5.is_some()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsoleBiosStatus {
+    pub console_id: String,
+    /// Every required file (or one file of each required group) is present
+    pub ready: bool,
+    pub files: Vec<BiosFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BiosStatusResponse {
+    pub consoles: Vec<ConsoleBiosStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BiosQuery {
+    pub console: Option<String>,
+}
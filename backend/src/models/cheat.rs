@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Cheat code formats. Which ones a game accepts depends on its console.
+pub const CHEAT_FORMATS: &[&str] = &["raw", "game_genie", "action_replay", "gameshark"];
+
+/// A cheat as embedded in the `Game` JSON, private to its owner unless shared
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameCheat {
+    pub id: Uuid,
+    pub game_id: Uuid,
+    pub user_id: Uuid,
+    pub description: String,
+    pub format: String,
+    /// Normalised code, several codes are joined with `+` as in RetroArch cheat files
+    pub code: String,
+    pub decoded: Json<Vec<CheatWrite>>,
+    pub is_shared: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A decoded code: write `value` to `address`, only if it currently holds `compare` when set.
+/// Values are uppercase hex.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheatWrite {
+    pub address: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compare: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCheatRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub description: String,
+    #[validate(length(min = 1, max = 2000))]
+    pub code: String,
+    /// Detected from the code when omitted
+    pub format: Option<String>,
+    #[serde(default)]
+    pub is_shared: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCheatRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 2000))]
+    pub code: Option<String>,
+    pub format: Option<String>,
+    pub is_shared: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CheatImportParams {
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CheatImportSummary {
+    pub imported: usize,
+    /// Entries skipped because their code doesn't decode, as `description: reason`
+    pub invalid: Vec<String>,
+}
@@ -0,0 +1,70 @@
+use crate::models::Game;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Collection {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CollectionSummary {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub collection: Collection,
+    pub game_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CollectionWithGames {
+    #[serde(flatten)]
+    pub collection: Collection,
+    pub games: Vec<Game>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCollectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub is_public: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCollectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    pub is_public: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddCollectionGameRequest {
+    pub game_id: Uuid,
+    /// Zero-based insert position, appended when omitted
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderCollectionRequest {
+    /// Every game of the collection, in the new order
+    pub game_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionQueryParams {
+    /// `mine` (default) or `public` for collections shared by other users
+    pub scope: Option<String>,
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Emulator settings for every game of a console, stored in `consoles.emulator_config`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct EmulatorConfig {
+    /// libretro core options, e.g. `"pcsx_rearmed_drc": "enabled"`
+    pub core_options: BTreeMap<String, String>,
+    /// Shader preset applied by default
+    #[validate(length(max = 255))]
+    pub shader: Option<String>,
+    /// Run the core threaded, needs cross-origin isolation in the browser
+    pub threads: Option<bool>,
+    /// BIOS filenames from the console's registry the core can't boot without
+    pub required_bios: Vec<String>,
+}
+
+/// Per-game changes on top of the console's `EmulatorConfig`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct EmulatorOverride {
+    /// Core to use instead of the console's `emulator_core`
+    #[validate(length(min = 1, max = 50))]
+    pub core: Option<String>,
+    /// Merged into the console's options, overriding keys present in both
+    pub core_options: BTreeMap<String, String>,
+    #[validate(length(max = 255))]
+    pub shader: Option<String>,
+    pub threads: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameEmulatorOverride {
+    pub game_id: Uuid,
+    pub config: Json<EmulatorOverride>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The configuration the frontend starts a game with: console settings plus game overrides
+#[derive(Debug, Serialize)]
+pub struct GameEmulatorConfig {
+    pub game_id: Uuid,
+    pub console_id: String,
+    pub core: String,
+    pub core_options: BTreeMap<String, String>,
+    pub shader: Option<String>,
+    pub threads: bool,
+    pub required_bios: Vec<String>,
+    /// Uploaded BIOS to boot with
+    pub bios_id: Option<Uuid>,
+    /// Entries of `required_bios` that haven't been uploaded
+    pub missing_bios: Vec<String>,
+    /// The game's own overrides, if any
+    pub overrides: Option<EmulatorOverride>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateConsoleRequest {
+    #[validate(length(min = 1, max = 20))]
+    pub id: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 100))]
+    pub manufacturer: Option<String>,
+    #[validate(range(min = 1950, max = 2100))]
+    pub release_year: Option<i32>,
+    #[validate(length(max = 500))]
+    pub icon_url: Option<String>,
+    #[validate(length(min = 1, max = 50))]
+    pub emulator_core: String,
+    #[validate(length(min = 1))]
+    pub supported_extensions: Vec<String>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub emulator_config: EmulatorConfig,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateConsoleRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 100))]
+    pub manufacturer: Option<String>,
+    #[validate(range(min = 1950, max = 2100))]
+    pub release_year: Option<i32>,
+    #[validate(length(max = 500))]
+    pub icon_url: Option<String>,
+    #[validate(length(min = 1, max = 50))]
+    pub emulator_core: Option<String>,
+    #[validate(length(min = 1))]
+    pub supported_extensions: Option<Vec<String>>,
+    /// Replaces the whole config when present
+    #[validate(nested)]
+    pub emulator_config: Option<EmulatorConfig>,
+}
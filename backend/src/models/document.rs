@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+pub const DOCUMENT_KINDS: &[&str] = &["manual", "map", "faq", "other"];
+
+/// A manual, map or FAQ attached to a game: a PDF, an image or a plain text file
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameDocument {
+    pub id: Uuid,
+    pub game_id: Uuid,
+    pub kind: String,
+    pub title: String,
+    /// Name of the uploaded file, used for downloads
+    pub original_filename: String,
+    #[serde(skip)]
+    pub filename: String,
+    pub content_type: String,
+    pub file_size: i64,
+    pub sha256: String,
+    /// Number of pages, for PDFs
+    pub page_count: Option<i32>,
+    #[serde(skip)]
+    pub has_thumbnail: bool,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// Authenticated viewer URL, add `?download=true` to get the file as an attachment
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub url: String,
+    /// Preview of the first page of PDFs, or of the image itself
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+}
+
+impl GameDocument {
+    /// Fills the URLs, which are derived from the ids rather than stored
+    pub fn with_urls(mut self) -> Self {
+        let base = format!("/api/v1/games/{}/documents/{}", self.game_id, self.id);
+        self.thumbnail_url = self.has_thumbnail.then(|| format!("{}/thumbnail", base));
+        self.url = format!("{}/file", base);
+        self
+    }
+}
+
+/// Text fields of a document upload; the file itself comes as a multipart field
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateDocumentRequest {
+    pub kind: String,
+    /// Defaults to the uploaded filename without its extension
+    #[validate(length(min = 1, max = 255))]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateDocumentRequest {
+    pub kind: Option<String>,
+    #[validate(length(min = 1, max = 255))]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DocumentFileParams {
+    /// Serve as an attachment instead of inline
+    #[serde(default)]
+    pub download: bool,
+}
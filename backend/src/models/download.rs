@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Resources that can be fetched through a signed URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadKind {
+    Rom,
+    Save,
+    Bios,
+}
+
+impl DownloadKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DownloadKind::Rom => "rom",
+            DownloadKind::Save => "save",
+            DownloadKind::Bios => "bios",
+        }
+    }
+
+    /// Path segment under `/api/v1/downloads`
+    pub fn path_segment(&self) -> &'static str {
+        match self {
+            DownloadKind::Rom => "roms",
+            DownloadKind::Save => "saves",
+            DownloadKind::Bios => "bios",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDownloadUrlRequest {
+    pub kind: DownloadKind,
+    pub id: Uuid,
+    /// ROM downloads only: serve the ROM with this patch applied
+    #[serde(default)]
+    pub patch: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DownloadUrl {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Query string of a signed download URL
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedDownloadQuery {
+    pub user: Uuid,
+    pub expires: i64,
+    pub signature: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Uuid>,
+}
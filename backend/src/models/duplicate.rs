@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// A game in a duplicate group, with how much user data hangs off it
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCandidate {
+    pub id: Uuid,
+    pub title: String,
+    pub rom_filename: String,
+    pub rom_size_bytes: i64,
+    pub rom_crc32: Option<String>,
+    pub region: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub save_states: i64,
+    /// Users with play stats for the game
+    pub players: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    pub console_id: String,
+    /// The shared CRC32 for exact duplicates, the normalised title for variants
+    pub key: String,
+    /// Most user data first, so the first game is the suggested primary
+    pub games: Vec<DuplicateCandidate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateReport {
+    /// Same console and ROM checksum, e.g. a zipped and an unzipped copy
+    pub exact: Vec<DuplicateGroup>,
+    /// Same console and normalised title but different ROMs, e.g. region releases
+    pub variants: Vec<DuplicateGroup>,
+    /// Games left out because their ROM couldn't be read to compute a checksum
+    pub unreadable: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct MergeGamesRequest {
+    /// The game that is kept
+    pub primary_id: Uuid,
+    /// Games whose user data moves to the primary before they are removed
+    #[validate(length(min = 1, max = 100))]
+    pub duplicate_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MergeSummary {
+    pub primary_id: Uuid,
+    pub merged_games: u64,
+    pub save_states_moved: u64,
+    /// Saves dropped because a newer save existed for the same user and slot
+    pub save_states_replaced: u64,
+    /// Users whose play stats were added to the primary's
+    pub stats_merged: u64,
+    pub achievements_moved: u64,
+    pub collections_updated: u64,
+    /// ROM files of the merged games removed from storage
+    pub roms_deleted: u64,
+}
@@ -0,0 +1,39 @@
+use serde::Serialize;
+
+/// Where the frontend loads EmulatorJS from
+#[derive(Debug, Serialize)]
+pub struct EmulatorJsInfo {
+    pub version: String,
+    /// Value for `EJS_pathtodata`
+    pub path_to_data: String,
+    pub installed_versions: Vec<String>,
+}
+
+/// A core build found in the `cores/` directory, e.g. `mgba-thread-wasm.data`
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledCore {
+    pub name: String,
+    /// Builds present: `wasm`, `thread`, `legacy`, `thread-legacy`
+    pub variants: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsoleCoreStatus {
+    pub console_id: String,
+    pub emulator_core: String,
+    /// Core files EmulatorJS can load for `emulator_core`, in its order of preference
+    pub candidate_cores: Vec<String>,
+    /// The first candidate that is installed
+    pub installed_core: Option<String>,
+    /// The console's emulator config asks for threads
+    pub threads_requested: bool,
+    /// A threaded build of the installed core is present
+    pub threads_available: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmulatorJsCoreReport {
+    pub version: String,
+    pub consoles: Vec<ConsoleCoreStatus>,
+    pub cores: Vec<InstalledCore>,
+}
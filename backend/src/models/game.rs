@@ -1,7 +1,24 @@
+use crate::models::{EmulatorConfig, GameCheat, GameDocument, GamePatch, GameTag, GameVariant};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 use uuid::Uuid;
+use validator::Validate;
+
+/// Metadata fields that can be edited by admins and locked against imports
+pub const LOCKABLE_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "release_year",
+    "developer",
+    "publisher",
+    "genres",
+    "players",
+    "rating",
+    "region",
+    "cover_url",
+];
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Console {
@@ -12,6 +29,7 @@ pub struct Console {
     pub icon_url: Option<String>,
     pub emulator_core: String,
     pub supported_extensions: Vec<String>,
+    pub emulator_config: Json<EmulatorConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -21,13 +39,58 @@ pub struct Game {
     pub title: String,
     pub rom_filename: String,
     pub rom_size_bytes: i64,
+    pub rom_crc32: Option<String>,
+    /// Modification time of the ROM file `rom_crc32` was computed from
+    pub rom_modified_at: Option<DateTime<Utc>>,
     pub cover_url: Option<String>,
     pub description: Option<String>,
     pub release_year: Option<i32>,
     pub developer: Option<String>,
-    pub genre: Option<String>,
+    pub publisher: Option<String>,
+    pub players: Option<String>,
+    pub rating: Option<f32>,
+    pub region: Option<String>,
+    /// Genre, theme, feature and user tags, loaded separately from the `games` row
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub tags: Vec<GameTag>,
+    /// Soft patches that turn this ROM into a translated or hacked variant
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub patches: Vec<GamePatch>,
+    /// The title's other releases, filled when games are listed by title
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub variants: Vec<GameVariant>,
+    /// The viewer's own and shared cheats, filled when a single game is fetched
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub cheats: Vec<GameCheat>,
+    /// Manuals, maps and FAQs, filled when a single game is fetched
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub documents: Vec<GameDocument>,
+    /// Average user rating on a 1-10 scale
+    pub user_rating: Option<f32>,
+    pub user_rating_count: i32,
+    pub locked_fields: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub uploaded_by: Option<Uuid>,
+    /// `instance`, `private` or `shared`, see `GAME_VISIBILITIES`
+    pub visibility: String,
+    /// Title grouping this game with its other regional releases and revisions
+    pub title_id: Option<Uuid>,
+}
+
+impl Game {
+    /// Names of the game's genre tags
+    pub fn genres(&self) -> Vec<String> {
+        self.tags
+            .iter()
+            .filter(|t| t.kind == "genre")
+            .map(|t| t.name.clone())
+            .collect()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -37,13 +100,147 @@ pub struct GameUploadRequest {
     pub description: Option<String>,
     pub release_year: Option<i32>,
     pub developer: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+}
+
+/// A partial set of metadata values, as submitted by an admin or produced by an importer
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Validate)]
+pub struct GameMetadata {
+    #[validate(length(min = 1, max = 255))]
+    pub title: Option<String>,
+    #[validate(length(max = 10000))]
+    pub description: Option<String>,
+    #[validate(range(min = 1970, max = 2100))]
+    pub release_year: Option<i32>,
+    #[validate(length(max = 100))]
+    pub developer: Option<String>,
+    #[validate(length(max = 100))]
+    pub publisher: Option<String>,
+    /// Replaces the game's genre tags
+    #[validate(length(max = 20))]
+    pub genres: Option<Vec<String>>,
+    #[validate(length(max = 20))]
+    pub players: Option<String>,
+    /// Community rating between 0 and 1, as scraped into gamelist.xml
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub rating: Option<f32>,
+    #[validate(length(max = 100))]
+    pub region: Option<String>,
+    #[validate(length(max = 500))]
+    pub cover_url: Option<String>,
+}
+
+impl GameMetadata {
+    /// Truncates text fields to their column sizes, for imported data that skips validation
+    pub fn clamp_lengths(mut self) -> Self {
+        fn clamp(value: &mut Option<String>, max: usize) {
+            if let Some(v) = value {
+                if v.chars().count() > max {
+                    *v = v.chars().take(max).collect();
+                }
+            }
+        }
+
+        clamp(&mut self.title, 255);
+        clamp(&mut self.developer, 100);
+        clamp(&mut self.publisher, 100);
+        if let Some(genres) = &mut self.genres {
+            for genre in genres.iter_mut() {
+                if genre.chars().count() > 50 {
+                    *genre = genre.chars().take(50).collect();
+                }
+            }
+        }
+        clamp(&mut self.players, 20);
+        clamp(&mut self.region, 100);
+        clamp(&mut self.cover_url, 500);
+        self
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateGameRequest {
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub metadata: GameMetadata,
+    /// Replaces the set of locked fields; when omitted, edited fields are locked
+    pub locked_fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameEdit {
+    pub id: Uuid,
+    pub game_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub source: String,
+    pub field: String,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameSort {
+    Title,
+    Year,
+    Recent,
+    MostPlayed,
+    /// Average user rating
+    Rating,
+    Relevance,
+    /// Collection order, only meaningful together with `collection`
+    Position,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GameQuery {
+    pub q: Option<String>,
+    pub console: Option<String>,
+    /// Genre tag name or slug
     pub genre: Option<String>,
+    /// Comma-separated tag slugs, all of which must match
+    pub tags: Option<String>,
+    pub developer: Option<String>,
+    pub region: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub favorite: Option<bool>,
+    pub collection: Option<Uuid>,
+    pub smart_collection: Option<Uuid>,
+    /// One entry per title, the release picked by the user's preferred regions
+    pub group_titles: Option<bool>,
+    pub sort: Option<GameSort>,
+    pub order: Option<SortOrder>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameFacets {
+    pub consoles: Vec<FacetCount>,
+    pub genres: Vec<FacetCount>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct GameListResponse {
     pub games: Vec<Game>,
     pub total: i64,
+    pub facets: GameFacets,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -0,0 +1,18 @@
+use serde::Serialize;
+
+#[derive(Debug, Default, Serialize)]
+pub struct GamelistImportSummary {
+    pub console_id: String,
+    pub matched: usize,
+    pub updated: usize,
+    pub media_imported: usize,
+    pub unmatched: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GamelistExportSummary {
+    pub console_id: String,
+    pub path: String,
+    pub games: usize,
+    pub media_exported: usize,
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Button id -> binding, keyed by the ids of the profile's `ControllerLayout`
+pub type InputBindings = BTreeMap<String, InputBinding>;
+
+/// What triggers one button: a key, a gamepad input, or both
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputBinding {
+    /// `KeyboardEvent.code`, e.g. `KeyZ` or `ArrowUp`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyboard: Option<String>,
+    /// SDL game controller button (`a`, `dpup`, `leftshoulder`) or axis half (`+leftx`, `-lefty`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gamepad: Option<String>,
+}
+
+/// The buttons of a console's controller. Consoles pick a layout through their emulator core.
+#[derive(Debug, Serialize)]
+pub struct ControllerLayout {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub buttons: &'static [LayoutButton],
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LayoutButton {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// libretro RetroPad input the core reads this button from, as EmulatorJS numbers them
+    pub retropad: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InputProfile {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub layout: String,
+    pub bindings: Json<InputBindings>,
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameInputOverride {
+    pub user_id: Uuid,
+    pub game_id: Uuid,
+    pub profile_id: Option<Uuid>,
+    pub bindings: Json<InputBindings>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The bindings a game starts with: the chosen or default profile plus the game's changes
+#[derive(Debug, Serialize)]
+pub struct GameInputConfig {
+    pub game_id: Uuid,
+    pub layout: &'static ControllerLayout,
+    /// Profile the bindings come from, `None` when the user has none for the layout
+    pub profile_id: Option<Uuid>,
+    pub bindings: InputBindings,
+    /// The game's own override, if any
+    pub overrides: Option<GameInputOverride>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct InputProfileQuery {
+    pub layout: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateInputProfileRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub layout: String,
+    #[serde(default)]
+    pub bindings: InputBindings,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateInputProfileRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    /// Replaces all bindings when present
+    pub bindings: Option<InputBindings>,
+    /// Only `true` is meaningful: the profile becomes the layout's default
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateGameInputRequest {
+    /// Profile to use instead of the layout's default
+    pub profile_id: Option<Uuid>,
+    /// Merged over the profile's bindings
+    #[serde(default)]
+    pub bindings: InputBindings,
+}
+
+/// A pad from SDL's `gamecontrollerdb.txt`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ControllerMapping {
+    pub guid: String,
+    pub platform: String,
+    pub name: String,
+    /// SDL element -> physical input, e.g. `"a": "b0"`, `"leftx": "a0"`, `"dpup": "h0.1"`
+    pub mapping: Json<BTreeMap<String, String>>,
+    pub vendor_id: Option<String>,
+    pub product_id: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Look a pad up by GUID, or by the USB ids browsers put in `Gamepad.id`
+#[derive(Debug, Default, Deserialize)]
+pub struct ControllerQuery {
+    pub guid: Option<String>,
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub platform: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ControllerImportSummary {
+    pub imported: usize,
+    /// Lines that aren't valid mappings, as `line N: reason`
+    pub invalid: Vec<String>,
+}
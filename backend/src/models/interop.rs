@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// A RetroArch `.lpl` playlist (JSON format, RetroArch 1.7.6+)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetroArchPlaylist {
+    pub version: String,
+    pub default_core_path: String,
+    pub default_core_name: String,
+    pub label_display_mode: i32,
+    pub right_thumbnail_mode: i32,
+    pub left_thumbnail_mode: i32,
+    pub sort_mode: i32,
+    pub items: Vec<RetroArchPlaylistItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetroArchPlaylistItem {
+    pub path: String,
+    pub label: String,
+    pub core_path: String,
+    pub core_name: String,
+    pub crc32: String,
+    pub db_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetroArchPlaylistExport {
+    pub console_id: String,
+    pub filename: String,
+    pub playlist: RetroArchPlaylist,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RetroArchExportParams {
+    /// ROM root as seen by RetroArch, defaults to `RETROARCH_ROM_ROOT`
+    pub rom_root: Option<String>,
+    /// RetroArch core directory; cores are auto-detected when omitted
+    pub core_dir: Option<String>,
+    /// Core library extension for the target platform
+    pub core_extension: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct LaunchBoxImportSummary {
+    pub platform: Option<String>,
+    pub console_id: Option<String>,
+    pub matched: usize,
+    pub updated: usize,
+    pub unmatched: Vec<String>,
+}
@@ -0,0 +1,89 @@
+use crate::models::{Console, GameMetadata};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Version of the library bundle layout, bumped on incompatible changes
+pub const LIBRARY_FORMAT_VERSION: u32 = 1;
+
+/// Name of the bundle document inside ZIP archives, next to a `media/` folder
+pub const LIBRARY_MANIFEST_FILENAME: &str = "library.json";
+
+/// Library metadata exported from one instance and imported into another.
+/// Games are identified by console and ROM CRC32, never by file path.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub consoles: Vec<Console>,
+    pub tags: Vec<LibraryTag>,
+    pub games: Vec<LibraryGame>,
+    pub collections: Vec<LibraryCollection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryTag {
+    pub kind: String,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryGame {
+    /// Id on the exporting instance, referenced by collections
+    pub id: Uuid,
+    pub console_id: String,
+    pub rom_filename: String,
+    pub rom_size_bytes: i64,
+    /// Missing when the ROM could not be read during the export
+    pub rom_crc32: Option<String>,
+    #[serde(flatten)]
+    pub metadata: GameMetadata,
+    #[serde(default)]
+    pub locked_fields: Vec<String>,
+    /// Theme, feature and user tags, genres are part of the metadata
+    #[serde(default)]
+    pub tags: Vec<LibraryTag>,
+    #[serde(default)]
+    pub media: Vec<LibraryMedia>,
+}
+
+/// A media file, stored as `media/<content_hash>.<file_extension>` in ZIP archives
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryMedia {
+    pub kind: String,
+    pub content_hash: String,
+    pub file_extension: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryCollection {
+    /// Username of the owner, collections of unknown users are skipped on import
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    /// Ids of `LibraryGame` entries, in collection order
+    pub game_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LibraryExportParams {
+    /// `json` (default) or `zip`, which also carries the media files
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct LibraryImportSummary {
+    pub consoles_created: usize,
+    pub tags_created: usize,
+    pub games_matched: usize,
+    pub games_updated: usize,
+    pub media_imported: usize,
+    pub media_missing: usize,
+    pub collections_imported: usize,
+    /// Games without a ROM with the same checksum here, as `console/filename`
+    pub unmatched: Vec<String>,
+    /// Collections whose owner does not exist here, as `owner/name`
+    pub skipped_collections: Vec<String>,
+}
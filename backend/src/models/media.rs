@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const MEDIA_KINDS: &[&str] = &["box_art", "title_screen", "screenshot", "cartridge"];
+
+/// Generated thumbnail sizes, as (name, bounding box in pixels)
+pub const THUMBNAIL_SIZES: &[(&str, u32)] = &[("small", 160), ("medium", 320), ("large", 640)];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameMedia {
+    pub id: Uuid,
+    pub game_id: Uuid,
+    pub kind: String,
+    pub content_hash: String,
+    pub file_extension: String,
+    pub mime_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_bytes: i64,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MediaThumbnail {
+    pub size: String,
+    pub webp_url: String,
+    pub png_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameMediaResponse {
+    #[serde(flatten)]
+    pub media: GameMedia,
+    pub url: String,
+    pub thumbnails: Vec<MediaThumbnail>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameMediaListResponse {
+    pub media: Vec<GameMediaResponse>,
+}
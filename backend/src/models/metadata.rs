@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MetadataEntry {
+    pub id: Uuid,
+    pub console_id: String,
+    pub match_key: String,
+    pub crc32: Option<String>,
+    pub serial: Option<String>,
+    pub title: Option<String>,
+    pub normalized_title: Option<String>,
+    pub description: Option<String>,
+    pub developer: Option<String>,
+    pub publisher: Option<String>,
+    pub genre: Option<String>,
+    pub release_year: Option<i32>,
+    pub players: Option<String>,
+    pub region: Option<String>,
+    pub source: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One record of a JSON metadata bundle, or a parsed .dat game entry
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct MetadataRecord {
+    pub console_id: Option<String>,
+    pub crc32: Option<String>,
+    pub serial: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub developer: Option<String>,
+    pub publisher: Option<String>,
+    pub genre: Option<String>,
+    pub release_year: Option<i32>,
+    pub players: Option<String>,
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetadataImportParams {
+    pub console: Option<String>,
+    /// `dat` (clrmamepro/libretro-database) or `json`
+    pub format: Option<String>,
+    /// Dataset name recorded on each entry, e.g. `libretro-database`
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetadataEnrichParams {
+    pub console: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MetadataEnrichSummary {
+    pub provider: String,
+    pub scanned: usize,
+    pub matched: usize,
+    pub updated: usize,
+}
@@ -4,6 +4,27 @@ pub mod achievement;
 pub mod save_state;
 pub mod stats;
 pub mod settings;
+pub mod shader;
+pub mod media;
+pub mod gamelist;
+pub mod interop;
+pub mod metadata;
+pub mod collection;
+pub mod smart_collection;
+pub mod review;
+pub mod tag;
+pub mod download;
+pub mod patch;
+pub mod bios;
+pub mod console;
+pub mod emulatorjs;
+pub mod visibility;
+pub mod duplicate;
+pub mod title;
+pub mod library;
+pub mod cheat;
+pub mod document;
+pub mod input;
 
 pub use user::*;
 pub use game::*;
@@ -11,3 +32,24 @@ pub use achievement::*;
 pub use save_state::*;
 pub use stats::*;
 pub use settings::*;
+pub use shader::*;
+pub use media::*;
+pub use gamelist::*;
+pub use interop::*;
+pub use metadata::*;
+pub use collection::*;
+pub use smart_collection::*;
+pub use review::*;
+pub use tag::*;
+pub use download::*;
+pub use patch::*;
+pub use bios::*;
+pub use console::*;
+pub use emulatorjs::*;
+pub use visibility::*;
+pub use duplicate::*;
+pub use title::*;
+pub use library::*;
+pub use cheat::*;
+pub use document::*;
+pub use input::*;
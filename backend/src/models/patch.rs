@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// A soft patch (fan translation, romhack) applied to its base game's ROM on download
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RomPatch {
+    pub id: Uuid,
+    pub game_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub format: String,
+    #[serde(skip_serializing)]
+    pub filename: String,
+    pub file_size: i64,
+    pub patch_crc32: String,
+    /// CRC32 the base ROM must have, recorded in BPS and UPS patches
+    pub source_crc32: Option<String>,
+    /// CRC32 of the patched ROM
+    pub target_crc32: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A patched variant as embedded in the `Game` JSON, playable as its own entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct GamePatch {
+    #[serde(skip)]
+    pub game_id: Uuid,
+    pub id: Uuid,
+    pub name: String,
+    pub format: String,
+}
+
+/// Text fields of a patch upload; the patch file itself comes as a multipart field
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRomPatchRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A patch upload: its text fields together with the patch file
+#[derive(Debug)]
+pub struct RomPatchUpload {
+    pub request: CreateRomPatchRequest,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRomPatchRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RomQuery {
+    /// Serve the ROM with this patch applied
+    pub patch: Option<Uuid>,
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+pub const PLAY_STATUSES: &[&str] = &["backlog", "playing", "beaten", "completed", "abandoned"];
+
+/// Rating scales users may rate on; ratings are stored on the 1-10 scale
+pub const RATING_SCALES: &[i16] = &[5, 10];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameReview {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub game_id: Uuid,
+    /// 1-10
+    pub rating: Option<i16>,
+    pub review: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct GameReviewWithUser {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub review: GameReview,
+    pub username: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpsertReviewRequest {
+    #[validate(range(min = 1, max = 10))]
+    pub rating: Option<i16>,
+    /// Scale of `rating`, 5 or 10 (default)
+    pub scale: Option<i16>,
+    #[validate(length(max = 5000))]
+    pub review: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserGameStatus {
+    pub user_id: Uuid,
+    pub game_id: Uuid,
+    pub status: String,
+    pub started_on: Option<NaiveDate>,
+    pub finished_on: Option<NaiveDate>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePlayStatusRequest {
+    pub status: String,
+    /// Defaults to today when a game is first marked as playing
+    pub started_on: Option<NaiveDate>,
+    /// Defaults to today when a game is marked as beaten, completed or abandoned
+    pub finished_on: Option<NaiveDate>,
+}
+
+/// A play status change, `None` meaning the game had or has no status
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserActivity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub game_id: Uuid,
+    pub game_title: String,
+    pub old_status: Option<String>,
+    pub new_status: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct StatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProfileUser {
+    pub id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserProfile {
+    pub user: ProfileUser,
+    pub status_counts: Vec<StatusCount>,
+    pub review_count: i64,
+    pub activity: Vec<UserActivity>,
+}
@@ -8,6 +8,8 @@ pub struct SaveState {
     pub id: Uuid,
     pub user_id: Uuid,
     pub game_id: Uuid,
+    /// Set when the save belongs to a patched variant of the game
+    pub patch_id: Option<Uuid>,
     pub slot: i32,
     pub save_data_filename: String,
     pub screenshot_filename: Option<String>,
@@ -15,6 +17,18 @@ pub struct SaveState {
     pub created_at: DateTime<Utc>,
 }
 
+/// A save slot upload, stored over whatever the slot held before
+#[derive(Debug)]
+pub struct NewSaveState {
+    pub game_id: Uuid,
+    /// Set when saving a patched variant of the game
+    pub patch_id: Option<Uuid>,
+    pub slot: i32,
+    pub save_data: Vec<u8>,
+    pub screenshot: Option<Vec<u8>>,
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SaveStateUploadRequest {
     pub game_id: Uuid,
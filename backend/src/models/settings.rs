@@ -1,9 +1,13 @@
+use crate::models::ResolvedShaderPreset;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
+use sqlx::types::Json;
 use sqlx::FromRow;
+use std::collections::BTreeMap;
 use uuid::Uuid;
+use validator::Validate;
 
+/// A user's global settings, the layer console and game overrides apply to
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct UserSettings {
     pub user_id: Uuid,
@@ -11,15 +15,83 @@ pub struct UserSettings {
     pub crt_effect: bool,
     pub scanline_intensity: f32,
     pub audio_volume: f32,
-    pub key_mappings: JsonValue,
+    /// Shader preset, `None` to use the emulator configuration's, empty for no shader
+    pub shader: Option<String>,
+    /// libretro core options merged over the emulator configuration's
+    pub core_options: Json<BTreeMap<String, String>>,
+    pub preferred_regions: Vec<String>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct UpdateSettingsRequest {
+    #[validate(length(min = 1, max = 50))]
     pub theme: Option<String>,
     pub crt_effect: Option<bool>,
+    #[validate(range(min = 0.0, max = 1.0))]
     pub scanline_intensity: Option<f32>,
+    #[validate(range(min = 0.0, max = 1.0))]
     pub audio_volume: Option<f32>,
-    pub key_mappings: Option<JsonValue>,
+    #[validate(length(max = 255))]
+    pub shader: Option<String>,
+    /// Replaces all core options when present
+    pub core_options: Option<BTreeMap<String, String>>,
+}
+
+/// Settings a console or game changes, absent fields keep the value of the layer below
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct SettingsOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crt_effect: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub scanline_intensity: Option<f32>,
+    /// Empty for no shader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 255))]
+    pub shader: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub audio_volume: Option<f32>,
+    /// Merged into the layer below's options, overriding keys present in both
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub core_options: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ConsoleSettingsOverride {
+    pub user_id: Uuid,
+    pub console_id: String,
+    pub settings: Json<SettingsOverride>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameSettingsOverride {
+    pub user_id: Uuid,
+    pub game_id: Uuid,
+    pub settings: Json<SettingsOverride>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The settings a game runs with: emulator configuration, then the user's global settings,
+/// console override and game override
+#[derive(Debug, Serialize)]
+pub struct EffectiveSettings {
+    pub game_id: Uuid,
+    pub console_id: String,
+    pub theme: String,
+    pub crt_effect: bool,
+    pub scanline_intensity: f32,
+    pub shader: Option<String>,
+    /// The uploaded preset `shader` names, if it isn't one of EmulatorJS's built-in shaders
+    pub shader_preset: Option<ResolvedShaderPreset>,
+    pub audio_volume: f32,
+    pub core_options: BTreeMap<String, String>,
+    /// Setting -> layer its value comes from: `emulator`, `global`, `console` or `game`.
+    /// Core options are listed one by one as `core_options.<name>`.
+    pub sources: BTreeMap<String, &'static str>,
+    pub console_overrides: Option<SettingsOverride>,
+    pub game_overrides: Option<SettingsOverride>,
 }
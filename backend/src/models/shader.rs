@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use validator::Validate;
+
+pub const SHADER_FORMATS: &[&str] = &["glslp", "slangp"];
+
+/// A RetroArch shader preset, with the passes and textures it loads from its folder
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShaderPreset {
+    pub id: Uuid,
+    /// What the `shader` field of settings refers to the preset by
+    pub name: String,
+    pub description: Option<String>,
+    /// `glslp` or `slangp`
+    pub format: String,
+    /// Path of the preset file inside the preset's folder
+    pub preset_path: String,
+    pub passes: Json<Vec<ShaderPass>>,
+    pub textures: Json<BTreeMap<String, ShaderTexture>>,
+    /// Parameters the passes declare, in declaration order
+    pub parameters: Json<Vec<ShaderParameter>>,
+    /// Parameter values set by the preset file
+    pub preset_values: Json<BTreeMap<String, f64>>,
+    /// Every stored file, relative to the preset's folder
+    pub files: Vec<String>,
+    pub total_size: i64,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Consoles the preset is meant for, empty when it suits every console
+    pub consoles: Vec<String>,
+    /// Authenticated URL of the preset's folder, `files` are relative to it
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub files_url: String,
+}
+
+impl ShaderPreset {
+    /// Fills the URL, which is derived from the id rather than stored
+    pub fn with_urls(mut self) -> Self {
+        self.files_url = format!("/api/v1/shaders/{}/files/", self.id);
+        self
+    }
+
+    pub fn suits(&self, console_id: &str) -> bool {
+        self.consoles.is_empty() || self.consoles.iter().any(|c| c == console_id)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShaderPass {
+    /// Path of the pass's source, relative to the preset's folder
+    pub shader: String,
+    /// The pass's settings from the preset without their index, e.g. `filter_linear`, `scale_type`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub options: BTreeMap<String, String>,
+}
+
+/// A lookup texture passes sample by name, e.g. a shadow mask
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShaderTexture {
+    pub path: String,
+    /// `linear`, `wrap_mode` and `mipmap`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub options: BTreeMap<String, String>,
+}
+
+/// A `#pragma parameter` of one of the passes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShaderParameter {
+    pub name: String,
+    pub description: String,
+    pub default: f64,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub step: f64,
+}
+
+/// A preset with the value each parameter takes for the user
+#[derive(Debug, Serialize)]
+pub struct ResolvedShaderPreset {
+    #[serde(flatten)]
+    pub preset: ShaderPreset,
+    /// Every parameter's value: the user's, else the preset's, else the declared default
+    pub values: BTreeMap<String, f64>,
+    /// The user's own values
+    pub user_values: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserShaderParameters {
+    pub user_id: Uuid,
+    pub preset_id: Uuid,
+    pub parameters: Json<BTreeMap<String, f64>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ShaderPresetQuery {
+    /// Only presets suiting this console
+    pub console: Option<String>,
+}
+
+/// Text fields of a preset upload; the preset and its files come as multipart fields
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct CreateShaderPresetRequest {
+    /// Defaults to the preset's filename without its extension
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    pub consoles: Vec<String>,
+    /// Which preset to use when the upload holds several, as its path in the upload
+    pub preset: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateShaderPresetRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    /// Replaces the compatible consoles when present, empty for every console
+    pub consoles: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateShaderParametersRequest {
+    /// Replaces the user's values for the preset
+    pub parameters: BTreeMap<String, f64>,
+}
@@ -0,0 +1,103 @@
+use crate::models::{Game, GameSort};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::types::Json;
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// A smart collection rule, e.g.
+///
+/// ```json
+/// { "all": [
+///     { "field": "console_id", "op": "eq", "value": "snes" },
+///     { "field": "genre", "op": "contains", "value": "RPG" },
+///     { "field": "release_year", "op": "lt", "value": 1995 },
+///     { "field": "play_count", "op": "eq", "value": 0 }
+/// ] }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Rule {
+    All {
+        all: Vec<Rule>,
+    },
+    Any {
+        any: Vec<Rule>,
+    },
+    Not {
+        not: Box<Rule>,
+    },
+    Condition {
+        field: String,
+        op: RuleOp,
+        #[serde(default)]
+        value: JsonValue,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOp {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    In,
+    Contains,
+    StartsWith,
+    IsNull,
+    NotNull,
+    /// Timestamp within the last `value` days
+    WithinDays,
+    /// Timestamp since the start of the current `day`, `week`, `month` or `year`
+    SinceStartOf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SmartCollection {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub rules: Json<Rule>,
+    pub sort: Option<Json<GameSort>>,
+    pub is_public: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A smart collection evaluated for the requesting user
+#[derive(Debug, Serialize)]
+pub struct SmartCollectionWithGames {
+    #[serde(flatten)]
+    pub smart_collection: SmartCollection,
+    pub games: Vec<Game>,
+    pub total: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateSmartCollectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    pub rules: Rule,
+    pub sort: Option<GameSort>,
+    #[serde(default)]
+    pub is_public: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateSmartCollectionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    pub rules: Option<Rule>,
+    pub sort: Option<GameSort>,
+    pub is_public: Option<bool>,
+}
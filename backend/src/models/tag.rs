@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Tag kinds. Genres are maintained through game metadata, `user` tags can be created by anyone.
+pub const TAG_KINDS: &[&str] = &["genre", "theme", "feature", "user"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tag {
+    pub id: Uuid,
+    pub kind: String,
+    pub name: String,
+    pub slug: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TagSummary {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub tag: Tag,
+    pub game_count: i64,
+}
+
+/// A tag as embedded in the `Game` JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct GameTag {
+    #[serde(skip)]
+    pub game_id: Uuid,
+    pub id: Uuid,
+    pub kind: String,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TagQueryParams {
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTagRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTagRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub name: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeTagRequest {
+    /// Tag that receives the games of the merged tag
+    pub into: Uuid,
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Regions tried, in order, when the user hasn't set any preference
+pub const DEFAULT_PREFERRED_REGIONS: &[&str] = &["World", "USA", "Europe", "Japan"];
+
+/// One game released in several regions or revisions, each release being a `Game`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameTitle {
+    pub id: Uuid,
+    pub console_id: String,
+    pub name: String,
+    /// `normalize_title` of the releases, `None` for titles split off by an admin
+    pub normalized_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Another release of the same title, as embedded in the `Game` JSON
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameVariant {
+    #[serde(skip)]
+    pub title_id: Uuid,
+    pub id: Uuid,
+    pub title: String,
+    pub region: Option<String>,
+    pub rom_filename: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RegionPreference {
+    /// Region names as they appear in ROM filenames, most preferred first
+    #[validate(length(max = 10))]
+    pub preferred_regions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetGameTitleRequest {
+    /// Title to move the game to, a new title of its own when `None`
+    pub title_id: Option<Uuid>,
+}
@@ -12,6 +12,7 @@ pub struct User {
     #[serde(skip_serializing)]
     pub password_hash: String,
     pub avatar_url: Option<String>,
+    pub is_admin: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_login: Option<DateTime<Utc>>,
@@ -55,28 +56,3 @@ pub struct Claims {
     pub exp: i64,     // expiration time
     pub iat: i64,     // issued at
 }
-
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-pub struct UserSettings {
-    pub user_id: Uuid,
-    pub theme: String,
-    pub crt_effect: bool,
-    pub scanline_intensity: f32,
-    pub audio_volume: f32,
-    pub key_mappings: Option<serde_json::Value>,
-    pub updated_at: DateTime<Utc>,
-}
-
-impl Default for UserSettings {
-    fn default() -> Self {
-        Self {
-            user_id: Uuid::nil(),
-            theme: "classic-xp".to_string(),
-            crt_effect: true,
-            scanline_intensity: 0.5,
-            audio_volume: 0.8,
-            key_mappings: None,
-            updated_at: Utc::now(),
-        }
-    }
-}
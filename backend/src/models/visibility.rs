@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Who can see a game: everyone on the instance, only its uploader, or the users and groups
+/// it is shared with (plus its uploader). Admins see every game.
+pub const GAME_VISIBILITIES: &[&str] = &["instance", "private", "shared"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct UserGroupSummary {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub group: UserGroup,
+    pub member_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateUserGroupRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateGroupMembersRequest {
+    /// Every member of the group, replacing the current list
+    #[validate(length(max = 1000))]
+    pub user_ids: Vec<Uuid>,
+}
+
+/// A game's visibility with the users and groups it is shared with
+#[derive(Debug, Serialize)]
+pub struct GameVisibility {
+    pub game_id: Uuid,
+    pub visibility: String,
+    pub uploaded_by: Option<Uuid>,
+    pub user_ids: Vec<Uuid>,
+    pub group_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateVisibilityRequest {
+    /// One of `GAME_VISIBILITIES`
+    pub visibility: String,
+    /// Replaces the users the game is shared with, kept when omitted
+    #[validate(length(max = 1000))]
+    pub user_ids: Option<Vec<Uuid>>,
+    /// Replaces the groups the game is shared with, kept when omitted
+    #[validate(length(max = 100))]
+    pub group_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkVisibilityRequest {
+    #[validate(length(min = 1, max = 1000))]
+    pub game_ids: Vec<Uuid>,
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub update: UpdateVisibilityRequest,
+    /// New uploader, e.g. to make scanned games, which have none, private to someone
+    pub uploaded_by: Option<Uuid>,
+}
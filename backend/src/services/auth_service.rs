@@ -1,22 +1,28 @@
-use crate::models::{Claims, User};
+use crate::models::{Claims, DownloadKind, SignedDownloadQuery, User};
 use crate::utils::ApiError;
 use bcrypt::{hash, verify, DEFAULT_COST};
 use chrono::Utc;
+use hmac::{Hmac, Mac};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use sha2::Sha256;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+type HmacSha256 = Hmac<Sha256>;
+
 #[derive(Clone)]
 pub struct AuthService {
     jwt_secret: String,
     jwt_expiration: i64,
+    download_url_ttl: i64,
 }
 
 impl AuthService {
-    pub fn new(jwt_secret: String, jwt_expiration: i64) -> Self {
+    pub fn new(jwt_secret: String, jwt_expiration: i64, download_url_ttl: i64) -> Self {
         Self {
             jwt_secret,
             jwt_expiration,
+            download_url_ttl,
         }
     }
 
@@ -54,6 +60,66 @@ impl AuthService {
         .map_err(|e| e.into())
     }
 
+    /// Signs a download of a single resource for a single user. Used where the client can't send
+    /// an `Authorization` header, such as EmulatorJS fetching the ROM itself.
+    pub fn sign_download(
+        &self,
+        kind: DownloadKind,
+        resource_id: &Uuid,
+        patch_id: Option<&Uuid>,
+        user_id: &Uuid,
+    ) -> Result<SignedDownloadQuery, ApiError> {
+        let expires = Utc::now().timestamp() + self.download_url_ttl;
+        let signature = self
+            .download_mac(kind, resource_id, patch_id, user_id, expires)?
+            .finalize()
+            .into_bytes();
+
+        Ok(SignedDownloadQuery {
+            user: *user_id,
+            expires,
+            signature: hex::encode(signature),
+            patch: patch_id.copied(),
+        })
+    }
+
+    pub fn verify_download(
+        &self,
+        kind: DownloadKind,
+        resource_id: &Uuid,
+        query: &SignedDownloadQuery,
+    ) -> Result<(), ApiError> {
+        if query.expires < Utc::now().timestamp() {
+            return Err(ApiError::Unauthorized("Download link has expired".to_string()));
+        }
+
+        let signature = hex::decode(&query.signature)
+            .map_err(|_| ApiError::Unauthorized("Invalid download signature".to_string()))?;
+
+        self.download_mac(kind, resource_id, query.patch.as_ref(), &query.user, query.expires)?
+            .verify_slice(&signature)
+            .map_err(|_| ApiError::Unauthorized("Invalid download signature".to_string()))
+    }
+
+    fn download_mac(
+        &self,
+        kind: DownloadKind,
+        resource_id: &Uuid,
+        patch_id: Option<&Uuid>,
+        user_id: &Uuid,
+        expires: i64,
+    ) -> Result<HmacSha256, ApiError> {
+        let mut mac = HmacSha256::new_from_slice(self.jwt_secret.as_bytes())
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+        // The prefix keeps these signatures distinct from anything else derived from the secret
+        mac.update(format!("download:{}:{}:{}:{}", kind.as_str(), resource_id, user_id, expires).as_bytes());
+        if let Some(patch_id) = patch_id {
+            mac.update(format!(":patch:{}", patch_id).as_bytes());
+        }
+
+        Ok(mac)
+    }
+
     pub async fn get_user_by_id(&self, pool: &PgPool, user_id: &Uuid) -> Result<User, ApiError> {
         sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
             .bind(user_id)
@@ -104,10 +170,10 @@ impl AuthService {
         // Hash password
         let password_hash = self.hash_password(password)?;
 
-        // Insert user
+        // Insert user (the first account becomes the instance admin)
         let user = sqlx::query_as::<_, User>(
-            "INSERT INTO users (username, email, password_hash, created_at, updated_at)
-             VALUES ($1, $2, $3, NOW(), NOW())
+            "INSERT INTO users (username, email, password_hash, is_admin, created_at, updated_at)
+             VALUES ($1, $2, $3, NOT EXISTS(SELECT 1 FROM users), NOW(), NOW())
              RETURNING *"
         )
         .bind(username)
@@ -136,4 +202,21 @@ impl AuthService {
 
         Ok(())
     }
+
+    pub async fn is_admin(&self, pool: &PgPool, user_id: &Uuid) -> Result<bool, ApiError> {
+        let is_admin: Option<bool> = sqlx::query_scalar("SELECT is_admin FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(is_admin == Some(true))
+    }
+
+    pub async fn require_admin(&self, pool: &PgPool, user_id: &Uuid) -> Result<(), ApiError> {
+        if !self.is_admin(pool, user_id).await? {
+            return Err(ApiError::Forbidden("Admin privileges required".to_string()));
+        }
+
+        Ok(())
+    }
 }
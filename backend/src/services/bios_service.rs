@@ -0,0 +1,176 @@
+use crate::models::{BiosFile, ConsoleBiosStatus};
+use crate::utils::ApiError;
+use md5::{Digest, Md5};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const MAX_BIOS_SIZE_BYTES: usize = 16 * 1024 * 1024;
+
+const BIOS_SELECT: &str = "SELECT cb.*, bf.md5, bf.size_bytes, bf.uploaded_by, bf.uploaded_at
+                           FROM console_bios cb
+                           LEFT JOIN bios_files bf ON bf.bios_id = cb.id";
+
+/// Keeps the BIOS files emulator cores need, checked against the known-good dumps in `console_bios`
+#[derive(Clone)]
+pub struct BiosService {
+    bios_storage_path: String,
+}
+
+impl BiosService {
+    pub fn new(bios_storage_path: String) -> Self {
+        Self { bios_storage_path }
+    }
+
+    pub async fn get_bios_files(
+        &self,
+        pool: &PgPool,
+        console_id: Option<&str>,
+    ) -> Result<Vec<BiosFile>, ApiError> {
+        let files = sqlx::query_as::<_, BiosFile>(&format!(
+            "{} WHERE ($1::VARCHAR IS NULL OR cb.console_id = $1)
+             ORDER BY cb.console_id, cb.required DESC, cb.filename",
+            BIOS_SELECT
+        ))
+        .bind(console_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(files)
+    }
+
+    pub async fn get_bios_file(&self, pool: &PgPool, bios_id: &Uuid) -> Result<BiosFile, ApiError> {
+        let file = sqlx::query_as::<_, BiosFile>(&format!("{} WHERE cb.id = $1", BIOS_SELECT))
+            .bind(bios_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(file)
+    }
+
+    /// Stores an uploaded dump under the registry entry whose known MD5 it matches, so the
+    /// uploaded file's name doesn't matter. `console_id` narrows the match when a dump is
+    /// shared between consoles.
+    pub async fn upload_bios(
+        &self,
+        pool: &PgPool,
+        console_id: Option<&str>,
+        data: Vec<u8>,
+        user_id: &Uuid,
+    ) -> Result<BiosFile, ApiError> {
+        if data.len() > MAX_BIOS_SIZE_BYTES {
+            return Err(ApiError::BadRequest("BIOS file exceeds maximum size".to_string()));
+        }
+
+        let md5 = hex::encode(Md5::digest(&data));
+        let matches: Vec<(Uuid, String, String)> = sqlx::query_as(
+            "SELECT id, console_id, filename FROM console_bios
+             WHERE $1 = ANY(md5_hashes) AND ($2::VARCHAR IS NULL OR console_id = $2)"
+        )
+        .bind(&md5)
+        .bind(console_id)
+        .fetch_all(pool)
+        .await?;
+
+        let (bios_id, console_id, filename) = match matches.as_slice() {
+            [entry] => entry.clone(),
+            [] => {
+                return Err(ApiError::ValidationError(format!(
+                    "Unrecognised BIOS dump (MD5 {}), it matches no known-good file{}",
+                    md5,
+                    console_id.map(|c| format!(" for {}", c)).unwrap_or_default()
+                )))
+            }
+            _ => {
+                return Err(ApiError::ValidationError(
+                    "BIOS dump is used by several consoles, pass console_id".to_string(),
+                ))
+            }
+        };
+
+        let path = self.file_path(&console_id, &filename);
+        let size_bytes = data.len() as i64;
+        tokio::task::spawn_blocking(move || write_bios(&path, &data))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))??;
+
+        sqlx::query(
+            "INSERT INTO bios_files (bios_id, md5, size_bytes, uploaded_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (bios_id) DO UPDATE SET
+                md5 = EXCLUDED.md5,
+                size_bytes = EXCLUDED.size_bytes,
+                uploaded_by = EXCLUDED.uploaded_by,
+                uploaded_at = NOW()"
+        )
+        .bind(bios_id)
+        .bind(&md5)
+        .bind(size_bytes)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        self.get_bios_file(pool, &bios_id).await
+    }
+
+    pub async fn delete_bios(&self, pool: &PgPool, bios: &BiosFile) -> Result<(), ApiError> {
+        let deleted = sqlx::query("DELETE FROM bios_files WHERE bios_id = $1")
+            .bind(bios.id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+
+        if deleted == 0 {
+            return Err(ApiError::NotFound("BIOS file not uploaded".to_string()));
+        }
+
+        fs::remove_file(self.file_path(&bios.console_id, &bios.filename)).ok();
+
+        Ok(())
+    }
+
+    /// Where an uploaded BIOS lives, under the name its cores look for
+    pub fn file_path(&self, console_id: &str, filename: &str) -> PathBuf {
+        Path::new(&self.bios_storage_path).join(console_id).join(filename)
+    }
+}
+
+/// Groups registry entries per console and works out whether each console can boot
+pub fn bios_status(files: Vec<BiosFile>) -> Vec<ConsoleBiosStatus> {
+    let mut consoles: Vec<ConsoleBiosStatus> = Vec::new();
+
+    for file in files {
+        match consoles.last_mut() {
+            Some(status) if status.console_id == file.console_id => status.files.push(file),
+            _ => consoles.push(ConsoleBiosStatus {
+                console_id: file.console_id.clone(),
+                ready: false,
+                files: vec![file],
+            }),
+        }
+    }
+
+    for status in &mut consoles {
+        // Ungrouped files form a group of their own
+        let mut groups: HashMap<String, bool> = HashMap::new();
+        for file in status.files.iter().filter(|f| f.required) {
+            let key = file.choice_group.clone().unwrap_or_else(|| file.id.to_string());
+            *groups.entry(key).or_default() |= file.is_present();
+        }
+        status.ready = groups.values().all(|present| *present);
+    }
+
+    consoles
+}
+
+fn write_bios(path: &Path, data: &[u8]) -> Result<(), ApiError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to create BIOS directory: {}", e)))?;
+    }
+
+    fs::write(path, data)
+        .map_err(|e| ApiError::InternalServerError(format!("Failed to write BIOS file: {}", e)))
+}
@@ -0,0 +1,735 @@
+use crate::models::{
+    CheatImportSummary, CheatWrite, Console, CreateCheatRequest, Game, GameCheat, UpdateCheatRequest,
+    CHEAT_FORMATS,
+};
+use crate::utils::ApiError;
+use sqlx::types::Json;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Letter values of NES Game Genie codes
+const NES_GENIE_LETTERS: &str = "APZLGITYEOXUKSVN";
+
+/// Digits of SNES Game Genie codes, in the order of the hex values they stand for
+const SNES_GENIE_DIGITS: &str = "DF4709156BC8A23E";
+
+/// Characters of Genesis Game Genie codes, worth 5 bits each
+const GENESIS_GENIE_LETTERS: &str = "ABCDEFGHJKLMNPRSTVWXYZ0123456789";
+
+/// Consoles grouped by the cheat devices sold for them, decided by the emulator core
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CheatSystem {
+    Nes,
+    Snes,
+    GameBoy,
+    Genesis,
+    /// Nintendo 64 and PlayStation GameShark codes share one layout
+    GameShark,
+    Other,
+}
+
+impl CheatSystem {
+    fn of(console: &Console) -> Self {
+        match console.emulator_core.to_ascii_lowercase().as_str() {
+            "nes" | "fceumm" | "nestopia" | "mesen" => CheatSystem::Nes,
+            "snes" | "snes9x" | "bsnes" => CheatSystem::Snes,
+            "gb" | "gbc" | "gambatte" | "sameboy" => CheatSystem::GameBoy,
+            "segamd" | "megadrive" | "genesis_plus_gx" | "picodrive" => CheatSystem::Genesis,
+            "n64" | "mupen64plus_next" | "parallel_n64" | "psx" | "pcsx_rearmed" | "mednafen_psx_hw" => {
+                CheatSystem::GameShark
+            }
+            _ => CheatSystem::Other,
+        }
+    }
+
+    /// Formats accepted for the system, in the order they are tried when detecting a code's format
+    fn formats(self) -> &'static [&'static str] {
+        match self {
+            CheatSystem::Nes => &["game_genie", "raw"],
+            CheatSystem::Snes => &["game_genie", "action_replay", "raw"],
+            CheatSystem::GameBoy => &["game_genie", "gameshark", "raw"],
+            CheatSystem::Genesis => &["game_genie", "action_replay", "raw"],
+            CheatSystem::GameShark => &["gameshark", "raw"],
+            CheatSystem::Other => &["raw"],
+        }
+    }
+}
+
+/// A validated cheat: its format, the normalised code and the writes it makes
+pub struct DecodedCheat {
+    pub format: &'static str,
+    pub code: String,
+    pub writes: Vec<CheatWrite>,
+}
+
+/// Stores per-game cheat codes, validated and decoded for the game's console
+#[derive(Clone, Default)]
+pub struct CheatService;
+
+impl CheatService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The user's own cheats for the game, followed by the ones other users shared
+    pub async fn get_cheats(&self, pool: &PgPool, game_id: &Uuid, user_id: &Uuid) -> Result<Vec<GameCheat>, ApiError> {
+        let cheats = sqlx::query_as::<_, GameCheat>(
+            "SELECT * FROM game_cheats
+             WHERE game_id = $1 AND (user_id = $2 OR is_shared)
+             ORDER BY user_id <> $2, description, created_at"
+        )
+        .bind(game_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(cheats)
+    }
+
+    /// Returns a cheat the user is allowed to see, other users' private cheats look like missing ones
+    pub async fn get_visible_cheat(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        cheat_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<GameCheat, ApiError> {
+        let cheat = sqlx::query_as::<_, GameCheat>(
+            "SELECT * FROM game_cheats
+             WHERE id = $1 AND game_id = $2 AND (user_id = $3 OR is_shared)"
+        )
+        .bind(cheat_id)
+        .bind(game_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Cheat not found".to_string()))?;
+
+        Ok(cheat)
+    }
+
+    pub async fn create_cheat(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game_id: &Uuid,
+        user_id: &Uuid,
+        request: &CreateCheatRequest,
+    ) -> Result<GameCheat, ApiError> {
+        let decoded = decode_cheat(console, &request.code, request.format.as_deref())?;
+        let description = request.description.trim();
+        if description.is_empty() {
+            return Err(ApiError::ValidationError("Cheat description must not be empty".to_string()));
+        }
+
+        self.insert_cheat(pool, game_id, user_id, description, &decoded, request.is_shared)
+            .await
+    }
+
+    pub async fn update_cheat(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        cheat: &GameCheat,
+        request: &UpdateCheatRequest,
+    ) -> Result<GameCheat, ApiError> {
+        let description = match &request.description {
+            Some(description) if description.trim().is_empty() => {
+                return Err(ApiError::ValidationError("Cheat description must not be empty".to_string()))
+            }
+            Some(description) => description.trim(),
+            None => &cheat.description,
+        };
+
+        // Switching the format alone re-decodes the stored code
+        let (format, code, writes) = if request.code.is_some() || request.format.is_some() {
+            let code = request.code.as_deref().unwrap_or(&cheat.code);
+            let decoded = decode_cheat(console, code, request.format.as_deref())?;
+            (decoded.format.to_string(), decoded.code, decoded.writes)
+        } else {
+            (cheat.format.clone(), cheat.code.clone(), cheat.decoded.0.clone())
+        };
+
+        let cheat = sqlx::query_as::<_, GameCheat>(
+            "UPDATE game_cheats
+             SET description = $2, format = $3, code = $4, decoded = $5, is_shared = $6, updated_at = NOW()
+             WHERE id = $1
+             RETURNING *"
+        )
+        .bind(cheat.id)
+        .bind(description)
+        .bind(&format)
+        .bind(&code)
+        .bind(Json(&writes))
+        .bind(request.is_shared.unwrap_or(cheat.is_shared))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(cheat)
+    }
+
+    pub async fn delete_cheat(&self, pool: &PgPool, cheat_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM game_cheats WHERE id = $1")
+            .bind(cheat_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Adds the cheats of a RetroArch `.cht` file. Entries whose code doesn't decode
+    /// for the console are skipped and reported.
+    pub async fn import_cht(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game_id: &Uuid,
+        user_id: &Uuid,
+        contents: &str,
+        shared: bool,
+    ) -> Result<CheatImportSummary, ApiError> {
+        let entries = parse_cht(contents);
+        if entries.is_empty() {
+            return Err(ApiError::BadRequest("No cheats found in the cheat file".to_string()));
+        }
+
+        let mut summary = CheatImportSummary::default();
+        for (description, code) in entries {
+            let decoded = match decode_cheat(console, &code, None) {
+                Ok(decoded) => decoded,
+                Err(ApiError::ValidationError(reason)) => {
+                    summary.invalid.push(format!("{}: {}", description, reason));
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            self.insert_cheat(pool, game_id, user_id, &description, &decoded, shared)
+                .await?;
+            summary.imported += 1;
+        }
+
+        Ok(summary)
+    }
+
+    async fn insert_cheat(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        user_id: &Uuid,
+        description: &str,
+        decoded: &DecodedCheat,
+        shared: bool,
+    ) -> Result<GameCheat, ApiError> {
+        let cheat = sqlx::query_as::<_, GameCheat>(
+            "INSERT INTO game_cheats (game_id, user_id, description, format, code, decoded, is_shared)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING *"
+        )
+        .bind(game_id)
+        .bind(user_id)
+        .bind(description)
+        .bind(decoded.format)
+        .bind(&decoded.code)
+        .bind(Json(&decoded.writes))
+        .bind(shared)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(cheat)
+    }
+}
+
+/// Fills `Game::cheats` with the cheats the user can use
+pub async fn load_game_cheats(pool: &PgPool, games: &mut [Game], user_id: &Uuid) -> Result<(), ApiError> {
+    let ids: Vec<Uuid> = games.iter().map(|g| g.id).collect();
+    let cheats = sqlx::query_as::<_, GameCheat>(
+        "SELECT * FROM game_cheats
+         WHERE game_id = ANY($1) AND (user_id = $2 OR is_shared)
+         ORDER BY user_id <> $2, description, created_at"
+    )
+    .bind(&ids)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    for game in games.iter_mut() {
+        game.cheats = cheats.iter().filter(|c| c.game_id == game.id).cloned().collect();
+    }
+
+    Ok(())
+}
+
+/// Validates a cheat for the console and decodes it into raw writes. Codes joined with `+`
+/// must all have the same format, which is detected from the first one when not given.
+pub fn decode_cheat(console: &Console, code: &str, format: Option<&str>) -> Result<DecodedCheat, ApiError> {
+    let system = CheatSystem::of(console);
+
+    let mut detected = match format {
+        Some(format) => {
+            if !CHEAT_FORMATS.contains(&format) {
+                return Err(ApiError::ValidationError(format!(
+                    "Invalid cheat format '{}', expected one of: {}",
+                    format,
+                    CHEAT_FORMATS.join(", ")
+                )));
+            }
+            let supported = system.formats().iter().copied().find(|f| *f == format);
+            if supported.is_none() {
+                return Err(ApiError::ValidationError(format!(
+                    "{} codes are not supported for {}",
+                    format_label(format),
+                    console.name
+                )));
+            }
+            supported
+        }
+        None => None,
+    };
+
+    let parts: Vec<&str> = code.split('+').map(str::trim).filter(|p| !p.is_empty()).collect();
+    if parts.is_empty() {
+        return Err(ApiError::ValidationError("Cheat code must not be empty".to_string()));
+    }
+
+    let mut codes = Vec::new();
+    let mut writes = Vec::new();
+    for part in parts {
+        let candidates = match detected {
+            Some(format) => vec![format],
+            None => system.formats().to_vec(),
+        };
+
+        let Some((format, (code, write))) = candidates
+            .into_iter()
+            .find_map(|format| decode_code(system, format, part).map(|decoded| (format, decoded)))
+        else {
+            return Err(ApiError::ValidationError(format!(
+                "'{}' is not a valid {} code for {}",
+                part,
+                detected.map(format_label).unwrap_or("cheat"),
+                console.name
+            )));
+        };
+
+        detected = Some(format);
+        codes.push(code);
+        writes.push(write);
+    }
+
+    Ok(DecodedCheat {
+        format: detected.unwrap_or("raw"),
+        code: codes.join("+"),
+        writes,
+    })
+}
+
+fn format_label(format: &str) -> &'static str {
+    match format {
+        "game_genie" => "Game Genie",
+        "action_replay" => "Pro Action Replay",
+        "gameshark" => "GameShark",
+        _ => "raw",
+    }
+}
+
+/// Decodes a single code, returning it normalised along with its write
+fn decode_code(system: CheatSystem, format: &str, code: &str) -> Option<(String, CheatWrite)> {
+    if !code.is_ascii() {
+        return None;
+    }
+    let code = code.to_ascii_uppercase();
+    let compact: String = code.chars().filter(|c| *c != '-' && *c != ' ').collect();
+
+    match (format, system) {
+        ("raw", _) => decode_raw(&code),
+        ("game_genie", CheatSystem::Nes) => decode_nes_genie(&compact),
+        // SNES Game Genie digits are all hex, the dash tells them apart from Action Replay codes
+        ("game_genie", CheatSystem::Snes) if code.contains('-') => decode_snes_genie(&compact),
+        ("game_genie", CheatSystem::GameBoy) => decode_gb_genie(&compact),
+        ("game_genie", CheatSystem::Genesis) => decode_genesis_genie(&compact),
+        ("action_replay", CheatSystem::Snes) => decode_snes_action_replay(&compact),
+        ("action_replay", CheatSystem::Genesis) => decode_genesis_action_replay(&compact),
+        ("gameshark", CheatSystem::GameBoy) => decode_gb_gameshark(&compact),
+        ("gameshark", CheatSystem::GameShark) => decode_gameshark(&compact),
+        _ => None,
+    }
+}
+
+fn hex(value: &str, min_len: usize, max_len: usize) -> Option<u32> {
+    if value.len() < min_len || value.len() > max_len || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(value, 16).ok()
+}
+
+fn digits(code: &str, alphabet: &str) -> Option<Vec<u32>> {
+    code.chars()
+        .map(|c| alphabet.find(c).map(|i| i as u32))
+        .collect()
+}
+
+fn cheat_write(address: String, value: String, compare: Option<String>) -> CheatWrite {
+    CheatWrite { address, value, compare }
+}
+
+/// `ADDRESS:VALUE` or `ADDRESS?COMPARE:VALUE` in hex
+fn decode_raw(code: &str) -> Option<(String, CheatWrite)> {
+    let (target, value) = code.split_once(':')?;
+    let (address, compare) = match target.split_once('?') {
+        Some((address, compare)) => (address.trim(), Some(compare.trim())),
+        None => (target.trim(), None),
+    };
+    let value = value.trim();
+
+    hex(address, 1, 8)?;
+    hex(value, 1, 8)?;
+    if let Some(compare) = compare {
+        hex(compare, 1, 8)?;
+    }
+
+    let normalized = match compare {
+        Some(compare) => format!("{}?{}:{}", address, compare, value),
+        None => format!("{}:{}", address, value),
+    };
+
+    Some((
+        normalized,
+        cheat_write(address.to_string(), value.to_string(), compare.map(str::to_string)),
+    ))
+}
+
+/// Six or eight letters, the eight letter form adds a compare value
+fn decode_nes_genie(code: &str) -> Option<(String, CheatWrite)> {
+    if code.len() != 6 && code.len() != 8 {
+        return None;
+    }
+    let n = digits(code, NES_GENIE_LETTERS)?;
+
+    let address = 0x8000
+        + (((n[3] & 7) << 12)
+            | ((n[5] & 7) << 8)
+            | ((n[4] & 8) << 8)
+            | ((n[2] & 7) << 4)
+            | ((n[1] & 8) << 4)
+            | (n[4] & 7)
+            | (n[3] & 8));
+
+    let (value, compare) = if code.len() == 6 {
+        (((n[1] & 7) << 4) | ((n[0] & 8) << 4) | (n[0] & 7) | (n[5] & 8), None)
+    } else {
+        (
+            ((n[1] & 7) << 4) | ((n[0] & 8) << 4) | (n[0] & 7) | (n[7] & 8),
+            Some(((n[7] & 7) << 4) | ((n[6] & 8) << 4) | (n[6] & 7) | (n[5] & 8)),
+        )
+    };
+
+    Some((
+        code.to_string(),
+        cheat_write(
+            format!("{:04X}", address),
+            format!("{:02X}", value),
+            compare.map(|c| format!("{:02X}", c)),
+        ),
+    ))
+}
+
+/// `VVAA-AAAA` in the Game Genie's own digits, the address bits are scrambled
+fn decode_snes_genie(code: &str) -> Option<(String, CheatWrite)> {
+    if code.len() != 8 {
+        return None;
+    }
+    let data = digits(code, SNES_GENIE_DIGITS)?
+        .into_iter()
+        .fold(0u32, |acc, digit| (acc << 4) | digit);
+
+    let value = data >> 24;
+    let scrambled = data & 0xFF_FFFF;
+    let address = ((scrambled & 0x003C00) << 10)
+        | ((scrambled & 0x00003C) << 14)
+        | ((scrambled & 0xF00000) >> 8)
+        | ((scrambled & 0x000003) << 10)
+        | ((scrambled & 0x00C000) >> 6)
+        | ((scrambled & 0x0F0000) >> 12)
+        | ((scrambled & 0x0003C0) >> 6);
+
+    Some((
+        format!("{}-{}", &code[..4], &code[4..]),
+        cheat_write(format!("{:06X}", address), format!("{:02X}", value), None),
+    ))
+}
+
+/// `AAAAAAVV`, a plain bank/address and value
+fn decode_snes_action_replay(code: &str) -> Option<(String, CheatWrite)> {
+    if code.len() != 8 {
+        return None;
+    }
+    let data = hex(code, 8, 8)?;
+
+    Some((
+        code.to_string(),
+        cheat_write(format!("{:06X}", data >> 8), format!("{:02X}", data & 0xFF), None),
+    ))
+}
+
+/// `VVA-AAA` or `VVA-AAA-CCC` in hex, with the address and compare value obfuscated
+fn decode_gb_genie(code: &str) -> Option<(String, CheatWrite)> {
+    if code.len() != 6 && code.len() != 9 {
+        return None;
+    }
+    hex(code, 6, 9)?;
+    let d: Vec<u32> = code.chars().filter_map(|c| c.to_digit(16)).collect();
+
+    let value = (d[0] << 4) | d[1];
+    let address = (((d[5] ^ 0xF) << 12) | (d[2] << 8) | (d[3] << 4) | d[4]) & 0x7FFF;
+    let compare = (code.len() == 9).then(|| {
+        let c = ((d[6] << 4) | d[8]) ^ 0xFF;
+        (((c >> 2) | (c << 6)) & 0xFF) ^ 0x45
+    });
+
+    let normalized = if code.len() == 9 {
+        format!("{}-{}-{}", &code[..3], &code[3..6], &code[6..])
+    } else {
+        format!("{}-{}", &code[..3], &code[3..])
+    };
+
+    Some((
+        normalized,
+        cheat_write(
+            format!("{:04X}", address),
+            format!("{:02X}", value),
+            compare.map(|c| format!("{:02X}", c)),
+        ),
+    ))
+}
+
+/// `TTVVLLHH`: code type (RAM bank), value and little-endian address
+fn decode_gb_gameshark(code: &str) -> Option<(String, CheatWrite)> {
+    if code.len() != 8 {
+        return None;
+    }
+    let data = hex(code, 8, 8)?;
+    let value = (data >> 16) & 0xFF;
+    let address = ((data & 0xFF) << 8) | ((data >> 8) & 0xFF);
+
+    Some((
+        code.to_string(),
+        cheat_write(format!("{:04X}", address), format!("{:02X}", value), None),
+    ))
+}
+
+/// `ABCD-EFGH`, 40 bits spread over the 24 bit address and 16 bit value
+fn decode_genesis_genie(code: &str) -> Option<(String, CheatWrite)> {
+    if code.len() != 8 {
+        return None;
+    }
+    let n = digits(code, GENESIS_GENIE_LETTERS)?;
+
+    let mut address = 0u32;
+    let mut value = 0u32;
+    value |= n[0] << 3;
+    value |= n[1] >> 2;
+    address |= (n[1] & 3) << 14;
+    address |= n[2] << 9;
+    address |= ((n[3] & 0xF) << 20) | ((n[3] >> 4) << 8);
+    value |= (n[4] & 1) << 12;
+    address |= (n[4] >> 1) << 16;
+    value |= ((n[5] & 1) << 15) | ((n[5] >> 1) << 8);
+    value |= (n[6] >> 3) << 13;
+    address |= (n[6] & 7) << 5;
+    address |= n[7];
+
+    Some((
+        format!("{}-{}", &code[..4], &code[4..]),
+        cheat_write(format!("{:06X}", address), format!("{:04X}", value), None),
+    ))
+}
+
+/// `AAAAAA:VVVV`, also written without the colon
+fn decode_genesis_action_replay(code: &str) -> Option<(String, CheatWrite)> {
+    let (address, value) = match code.split_once(':') {
+        Some(parts) => parts,
+        None if code.len() == 10 => code.split_at(6),
+        None => return None,
+    };
+    hex(address, 6, 6)?;
+    hex(value, 4, 4)?;
+
+    Some((
+        format!("{}:{}", address, value),
+        cheat_write(address.to_string(), value.to_string(), None),
+    ))
+}
+
+/// `TTAAAAAA VVVV` on the Nintendo 64 and PlayStation, the code type stays part of the address
+fn decode_gameshark(code: &str) -> Option<(String, CheatWrite)> {
+    if code.len() != 12 {
+        return None;
+    }
+    let (address, value) = code.split_at(8);
+    hex(address, 8, 8)?;
+    hex(value, 4, 4)?;
+
+    Some((
+        format!("{} {}", address, value),
+        cheat_write(address.to_string(), value.to_string(), None),
+    ))
+}
+
+/// Reads the `cheatN_desc`/`cheatN_code` pairs of a RetroArch cheat file, in file order
+fn parse_cht(contents: &str) -> Vec<(String, String)> {
+    let mut entries: BTreeMap<usize, (Option<String>, Option<String>)> = BTreeMap::new();
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some((index, field)) = key.trim().strip_prefix("cheat").and_then(|k| k.split_once('_')) else {
+            continue;
+        };
+        let Ok(index) = index.parse::<usize>() else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').trim().to_string();
+
+        let entry = entries.entry(index).or_default();
+        match field {
+            "desc" => entry.0 = Some(value),
+            "code" => entry.1 = Some(value),
+            _ => {}
+        }
+    }
+
+    entries
+        .into_iter()
+        .filter_map(|(index, (description, code))| {
+            let code = code.filter(|c| !c.is_empty())?;
+            let description = description
+                .filter(|d| !d.is_empty())
+                .unwrap_or_else(|| format!("Cheat {}", index + 1));
+            Some((description.chars().take(255).collect(), code))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(address: &str, value: &str, compare: Option<&str>) -> CheatWrite {
+        cheat_write(address.to_string(), value.to_string(), compare.map(str::to_string))
+    }
+
+    #[test]
+    fn decodes_nes_game_genie() {
+        // Super Mario Bros. infinite lives
+        let (code, decoded) = decode_nes_genie("SXIOPO").unwrap();
+        assert_eq!(code, "SXIOPO");
+        assert_eq!(decoded, write("91D9", "AD", None));
+
+        assert_eq!(decode_nes_genie("GOSSIP").unwrap().1, write("D1DD", "14", None));
+        assert_eq!(decode_nes_genie("ZEXPYGLA").unwrap().1, write("94A7", "02", Some("03")));
+    }
+
+    #[test]
+    fn rejects_invalid_nes_game_genie() {
+        assert!(decode_nes_genie("SXIOP").is_none());
+        assert!(decode_nes_genie("SXIOPOA").is_none());
+        // B isn't one of the Game Genie's letters
+        assert!(decode_nes_genie("SXIOPB").is_none());
+    }
+
+    #[test]
+    fn decodes_snes_game_genie() {
+        // Substituted to AD82 8203: value AD, address bits unscrambled to 008E28
+        let (code, decoded) = decode_snes_genie("C26464D7").unwrap();
+        assert_eq!(code, "C264-64D7");
+        assert_eq!(decoded, write("008E28", "AD", None));
+
+        assert_eq!(decode_snes_genie("F4C13F67").unwrap().1, write("80AF66", "12", None));
+    }
+
+    #[test]
+    fn rejects_invalid_snes_game_genie() {
+        assert!(decode_snes_genie("C26464D").is_none());
+        assert!(decode_snes_genie("C26464D7D").is_none());
+        // G isn't a Game Genie digit
+        assert!(decode_snes_genie("C26464DG").is_none());
+    }
+
+    #[test]
+    fn decodes_game_boy_game_genie() {
+        let (code, decoded) = decode_gb_genie("00A17B").unwrap();
+        assert_eq!(code, "00A-17B");
+        assert_eq!(decoded, write("4A17", "00", None));
+
+        let (code, decoded) = decode_gb_genie("00A17BC49").unwrap();
+        assert_eq!(code, "00A-17B-C49");
+        assert_eq!(decoded, write("4A17", "00", Some("C8")));
+    }
+
+    #[test]
+    fn rejects_invalid_game_boy_game_genie() {
+        assert!(decode_gb_genie("00A17").is_none());
+        assert!(decode_gb_genie("00A17BC4").is_none());
+        assert!(decode_gb_genie("00G17B").is_none());
+    }
+
+    #[test]
+    fn decodes_genesis_game_genie() {
+        let (code, decoded) = decode_genesis_genie("SCRABJX0").unwrap();
+        assert_eq!(code, "SCRA-BJX0");
+        assert_eq!(decoded, write("009C76", "5478", None));
+
+        assert_eq!(decode_genesis_genie("RFAA8A2J").unwrap().1, write("0F4008", "6071", None));
+    }
+
+    #[test]
+    fn rejects_invalid_genesis_game_genie() {
+        assert!(decode_genesis_genie("SCRABJX").is_none());
+        assert!(decode_genesis_genie("SCRABJX00").is_none());
+        // I, O, Q and U are left out of the alphabet
+        assert!(decode_genesis_genie("SCRABJXO").is_none());
+    }
+
+    #[test]
+    fn decode_code_normalises_input() {
+        let (code, decoded) = decode_code(CheatSystem::Snes, "game_genie", "c264-64d7").unwrap();
+        assert_eq!(code, "C264-64D7");
+        assert_eq!(decoded, write("008E28", "AD", None));
+
+        // Without the dash a SNES code is taken for an Action Replay one
+        assert!(decode_code(CheatSystem::Snes, "game_genie", "C26464D7").is_none());
+    }
+
+    #[test]
+    fn parses_cht_files() {
+        let contents = r#"cheats = 3
+
+cheat0_desc = "Infinite Lives"
+cheat0_code = "SXIOPO"
+cheat0_enable = false
+
+cheat2_code = "ZEXPYGLA"
+cheat2_desc = ""
+
+cheat1_desc = "No code"
+cheat1_code = ""
+"#;
+
+        assert_eq!(
+            parse_cht(contents),
+            vec![
+                ("Infinite Lives".to_string(), "SXIOPO".to_string()),
+                ("Cheat 3".to_string(), "ZEXPYGLA".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_cht_skips_malformed_lines() {
+        let contents = "not a cheat file\ncheatX_code = \"SXIOPO\"\ncheat0 = \"SXIOPO\"\n= \"GOSSIP\"\n";
+        assert!(parse_cht(contents).is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::models::{
+    Collection, CollectionSummary, CollectionWithGames, CreateCollectionRequest, Game,
+    UpdateCollectionRequest,
+};
+use crate::services::{game_visible_to, load_game_patches, load_game_tags};
+use crate::utils::ApiError;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone, Default)]
+pub struct CollectionService;
+
+impl CollectionService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn get_favorites(&self, pool: &PgPool, user_id: &Uuid) -> Result<Vec<Game>, ApiError> {
+        let mut games = sqlx::query_as::<_, Game>(&format!(
+            "SELECT g.* FROM games g
+             JOIN user_favorites f ON f.game_id = g.id
+             WHERE f.user_id = $1 AND {}
+             ORDER BY f.created_at DESC",
+            game_visible_to("$1")
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        load_game_tags(pool, &mut games).await?;
+        load_game_patches(pool, &mut games).await?;
+
+        Ok(games)
+    }
+
+    pub async fn add_favorite(&self, pool: &PgPool, user_id: &Uuid, game_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO user_favorites (user_id, game_id) VALUES ($1, $2)
+             ON CONFLICT (user_id, game_id) DO NOTHING"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_favorite(&self, pool: &PgPool, user_id: &Uuid, game_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM user_favorites WHERE user_id = $1 AND game_id = $2")
+            .bind(user_id)
+            .bind(game_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lists the user's own collections, or public collections of other users
+    pub async fn get_collections(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        public: bool,
+    ) -> Result<Vec<CollectionSummary>, ApiError> {
+        let condition = if public {
+            "c.is_public AND c.user_id <> $1"
+        } else {
+            "c.user_id = $1"
+        };
+
+        let collections = sqlx::query_as::<_, CollectionSummary>(&format!(
+            "SELECT c.*, (SELECT COUNT(*) FROM collection_games cg WHERE cg.collection_id = c.id) AS game_count
+             FROM collections c
+             WHERE {}
+             ORDER BY c.name",
+            condition
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(collections)
+    }
+
+    /// Returns a collection the user owns or that is public
+    pub async fn get_visible_collection(
+        &self,
+        pool: &PgPool,
+        collection_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<Collection, ApiError> {
+        let collection = self.get_collection(pool, collection_id).await?;
+
+        if collection.user_id != *user_id && !collection.is_public {
+            return Err(ApiError::NotFound("Collection not found".to_string()));
+        }
+
+        Ok(collection)
+    }
+
+    /// Returns a collection the user owns
+    pub async fn get_owned_collection(
+        &self,
+        pool: &PgPool,
+        collection_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<Collection, ApiError> {
+        let collection = self.get_visible_collection(pool, collection_id, user_id).await?;
+
+        if collection.user_id != *user_id {
+            return Err(ApiError::Forbidden("Not authorized to modify this collection".to_string()));
+        }
+
+        Ok(collection)
+    }
+
+    async fn get_collection(&self, pool: &PgPool, collection_id: &Uuid) -> Result<Collection, ApiError> {
+        let collection = sqlx::query_as::<_, Collection>("SELECT * FROM collections WHERE id = $1")
+            .bind(collection_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(collection)
+    }
+
+    /// Loads the collection's games, leaving out those hidden from `user_id`
+    pub async fn get_collection_with_games(
+        &self,
+        pool: &PgPool,
+        collection: Collection,
+        user_id: &Uuid,
+    ) -> Result<CollectionWithGames, ApiError> {
+        let mut games = sqlx::query_as::<_, Game>(&format!(
+            "SELECT g.* FROM games g
+             JOIN collection_games cg ON cg.game_id = g.id
+             WHERE cg.collection_id = $1 AND {}
+             ORDER BY cg.position",
+            game_visible_to("$2")
+        ))
+        .bind(collection.id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        load_game_tags(pool, &mut games).await?;
+        load_game_patches(pool, &mut games).await?;
+
+        Ok(CollectionWithGames { collection, games })
+    }
+
+    pub async fn create_collection(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        request: &CreateCollectionRequest,
+    ) -> Result<Collection, ApiError> {
+        self.ensure_unique_name(pool, user_id, &request.name, None).await?;
+
+        let collection = sqlx::query_as::<_, Collection>(
+            "INSERT INTO collections (user_id, name, description, is_public)
+             VALUES ($1, $2, $3, $4)
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(request.name.trim())
+        .bind(&request.description)
+        .bind(request.is_public)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    pub async fn update_collection(
+        &self,
+        pool: &PgPool,
+        collection: &Collection,
+        request: &UpdateCollectionRequest,
+    ) -> Result<Collection, ApiError> {
+        if let Some(name) = &request.name {
+            self.ensure_unique_name(pool, &collection.user_id, name, Some(&collection.id))
+                .await?;
+        }
+
+        let collection = sqlx::query_as::<_, Collection>(
+            "UPDATE collections
+             SET name = COALESCE($2, name),
+                 description = COALESCE($3, description),
+                 is_public = COALESCE($4, is_public),
+                 updated_at = NOW()
+             WHERE id = $1
+             RETURNING *"
+        )
+        .bind(collection.id)
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(&request.description)
+        .bind(request.is_public)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    async fn ensure_unique_name(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        name: &str,
+        except: Option<&Uuid>,
+    ) -> Result<(), ApiError> {
+        let exists: Option<bool> = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM collections WHERE user_id = $1 AND name = $2 AND id IS DISTINCT FROM $3)"
+        )
+        .bind(user_id)
+        .bind(name.trim())
+        .bind(except)
+        .fetch_one(pool)
+        .await?;
+
+        if exists == Some(true) {
+            return Err(ApiError::BadRequest("A collection with this name already exists".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_collection(&self, pool: &PgPool, collection_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM collections WHERE id = $1")
+            .bind(collection_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Adds a game at `position` (appended when omitted), shifting later games down
+    pub async fn add_game(
+        &self,
+        pool: &PgPool,
+        collection_id: &Uuid,
+        game_id: &Uuid,
+        position: Option<i32>,
+    ) -> Result<(), ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let already: Option<bool> = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM collection_games WHERE collection_id = $1 AND game_id = $2)"
+        )
+        .bind(collection_id)
+        .bind(game_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if already == Some(true) {
+            return Err(ApiError::BadRequest("Game is already in this collection".to_string()));
+        }
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM collection_games WHERE collection_id = $1")
+            .bind(collection_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let position = position.map(|p| p.clamp(0, count as i32)).unwrap_or(count as i32);
+
+        sqlx::query("UPDATE collection_games SET position = position + 1 WHERE collection_id = $1 AND position >= $2")
+            .bind(collection_id)
+            .bind(position)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("INSERT INTO collection_games (collection_id, game_id, position) VALUES ($1, $2, $3)")
+            .bind(collection_id)
+            .bind(game_id)
+            .bind(position)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::touch(&mut tx, collection_id).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_game(&self, pool: &PgPool, collection_id: &Uuid, game_id: &Uuid) -> Result<(), ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let position: Option<i32> = sqlx::query_scalar(
+            "DELETE FROM collection_games WHERE collection_id = $1 AND game_id = $2 RETURNING position"
+        )
+        .bind(collection_id)
+        .bind(game_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let position = position.ok_or_else(|| ApiError::NotFound("Game is not in this collection".to_string()))?;
+
+        sqlx::query("UPDATE collection_games SET position = position - 1 WHERE collection_id = $1 AND position > $2")
+            .bind(collection_id)
+            .bind(position)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::touch(&mut tx, collection_id).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Replaces the order of the collection; `game_ids` must list exactly its current games
+    pub async fn reorder(&self, pool: &PgPool, collection_id: &Uuid, game_ids: &[Uuid]) -> Result<(), ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let mut current: Vec<Uuid> = sqlx::query_scalar("SELECT game_id FROM collection_games WHERE collection_id = $1")
+            .bind(collection_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        let mut requested = game_ids.to_vec();
+        current.sort();
+        requested.sort();
+
+        if current != requested {
+            return Err(ApiError::ValidationError(
+                "game_ids must contain every game of the collection exactly once".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE collection_games cg SET position = o.position - 1
+             FROM UNNEST($2::uuid[]) WITH ORDINALITY AS o(game_id, position)
+             WHERE cg.collection_id = $1 AND cg.game_id = o.game_id"
+        )
+        .bind(collection_id)
+        .bind(game_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        Self::touch(&mut tx, collection_id).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn touch(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, collection_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("UPDATE collections SET updated_at = NOW() WHERE id = $1")
+            .bind(collection_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+}
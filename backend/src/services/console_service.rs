@@ -0,0 +1,263 @@
+use crate::models::{
+    BiosFile, Console, CreateConsoleRequest, EmulatorOverride, Game, GameEmulatorConfig,
+    GameEmulatorOverride, UpdateConsoleRequest,
+};
+use crate::utils::ApiError;
+use sqlx::types::Json;
+use sqlx::{Acquire, PgPool, Postgres};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Admin management of consoles and of the emulator settings games are started with
+#[derive(Clone, Default)]
+pub struct ConsoleService;
+
+impl ConsoleService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `conn` is the pool, or a transaction when the console comes with other changes
+    pub async fn create_console<'c>(
+        &self,
+        conn: impl Acquire<'c, Database = Postgres>,
+        request: &CreateConsoleRequest,
+    ) -> Result<Console, ApiError> {
+        // The id doubles as the ROM directory name
+        if !request
+            .id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            return Err(ApiError::ValidationError(
+                "Console id may only contain lowercase letters, digits, '-' and '_'".to_string(),
+            ));
+        }
+        check_core_options(&request.emulator_config.core_options)?;
+        let mut conn = conn.acquire().await?;
+
+        let exists: Option<bool> = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM consoles WHERE id = $1)")
+            .bind(&request.id)
+            .fetch_one(&mut *conn)
+            .await?;
+        if exists == Some(true) {
+            return Err(ApiError::BadRequest(format!("Console '{}' already exists", request.id)));
+        }
+
+        let console = sqlx::query_as::<_, Console>(
+            "INSERT INTO consoles
+                (id, name, manufacturer, release_year, icon_url, emulator_core, supported_extensions, emulator_config)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING *"
+        )
+        .bind(&request.id)
+        .bind(request.name.trim())
+        .bind(&request.manufacturer)
+        .bind(request.release_year)
+        .bind(&request.icon_url)
+        .bind(request.emulator_core.trim())
+        .bind(normalize_extensions(&request.supported_extensions)?)
+        .bind(Json(&request.emulator_config))
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(console)
+    }
+
+    pub async fn update_console(
+        &self,
+        pool: &PgPool,
+        console_id: &str,
+        request: &UpdateConsoleRequest,
+    ) -> Result<Console, ApiError> {
+        if let Some(config) = &request.emulator_config {
+            check_core_options(&config.core_options)?;
+        }
+        let extensions = request
+            .supported_extensions
+            .as_deref()
+            .map(normalize_extensions)
+            .transpose()?;
+
+        let console = sqlx::query_as::<_, Console>(
+            "UPDATE consoles SET
+                name = COALESCE($2, name),
+                manufacturer = COALESCE($3, manufacturer),
+                release_year = COALESCE($4, release_year),
+                icon_url = COALESCE($5, icon_url),
+                emulator_core = COALESCE($6, emulator_core),
+                supported_extensions = COALESCE($7, supported_extensions),
+                emulator_config = COALESCE($8, emulator_config)
+             WHERE id = $1
+             RETURNING *"
+        )
+        .bind(console_id)
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(&request.manufacturer)
+        .bind(request.release_year)
+        .bind(&request.icon_url)
+        .bind(request.emulator_core.as_deref().map(str::trim))
+        .bind(extensions)
+        .bind(request.emulator_config.as_ref().map(Json))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(console)
+    }
+
+    /// Deletes a console that has no games left. Its BIOS registry goes with it.
+    pub async fn delete_console(&self, pool: &PgPool, console_id: &str) -> Result<(), ApiError> {
+        let games: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM games WHERE console_id = $1")
+            .bind(console_id)
+            .fetch_one(pool)
+            .await?;
+        if games > 0 {
+            return Err(ApiError::BadRequest(format!(
+                "Console '{}' still has {} games",
+                console_id, games
+            )));
+        }
+
+        let deleted = sqlx::query("DELETE FROM consoles WHERE id = $1")
+            .bind(console_id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(ApiError::NotFound("Console not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_game_override(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+    ) -> Result<Option<GameEmulatorOverride>, ApiError> {
+        let config = sqlx::query_as::<_, GameEmulatorOverride>(
+            "SELECT * FROM game_emulator_overrides WHERE game_id = $1"
+        )
+        .bind(game_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(config)
+    }
+
+    pub async fn set_game_override(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        config: &EmulatorOverride,
+        user_id: &Uuid,
+    ) -> Result<GameEmulatorOverride, ApiError> {
+        check_core_options(&config.core_options)?;
+
+        let config = sqlx::query_as::<_, GameEmulatorOverride>(
+            "INSERT INTO game_emulator_overrides (game_id, config, updated_by)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (game_id) DO UPDATE SET
+                config = EXCLUDED.config,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+             RETURNING *"
+        )
+        .bind(game_id)
+        .bind(Json(config))
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(config)
+    }
+
+    pub async fn delete_game_override(&self, pool: &PgPool, game_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM game_emulator_overrides WHERE game_id = $1")
+            .bind(game_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Layers a game's overrides over its console's settings and picks the BIOS to boot with
+pub fn resolve_emulator_config(
+    game: &Game,
+    console: &Console,
+    overrides: Option<EmulatorOverride>,
+    bios_files: &[BiosFile],
+) -> GameEmulatorConfig {
+    let base = &console.emulator_config.0;
+    let layer = overrides.clone().unwrap_or_default();
+
+    let mut core_options = base.core_options.clone();
+    core_options.extend(layer.core_options);
+
+    let present = |filename: &str| {
+        bios_files
+            .iter()
+            .find(|b| b.filename == filename && b.is_present())
+    };
+    let missing_bios = base
+        .required_bios
+        .iter()
+        .filter(|f| present(f).is_none())
+        .cloned()
+        .collect();
+    // Required files first, then whatever the registry marks as required, then anything uploaded
+    let bios_id = base
+        .required_bios
+        .iter()
+        .find_map(|f| present(f))
+        .or_else(|| bios_files.iter().find(|b| b.required && b.is_present()))
+        .or_else(|| bios_files.iter().find(|b| b.is_present()))
+        .map(|b| b.id);
+
+    GameEmulatorConfig {
+        game_id: game.id,
+        console_id: console.id.clone(),
+        core: layer.core.unwrap_or_else(|| console.emulator_core.clone()),
+        core_options,
+        shader: layer.shader.or_else(|| base.shader.clone()),
+        threads: layer.threads.or(base.threads).unwrap_or(false),
+        required_bios: base.required_bios.clone(),
+        bios_id,
+        missing_bios,
+        overrides,
+    }
+}
+
+/// Option names are libretro keys like `snes9x_overclock`, values whatever the core accepts
+pub fn check_core_options(options: &BTreeMap<String, String>) -> Result<(), ApiError> {
+    for (key, value) in options {
+        if key.is_empty() || key.len() > 100 || key.chars().any(char::is_whitespace) {
+            return Err(ApiError::ValidationError(format!("Invalid core option name '{}'", key)));
+        }
+        if value.len() > 255 {
+            return Err(ApiError::ValidationError(format!("Value of core option '{}' is too long", key)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lowercases extensions and adds the leading dot the ROM scanner compares against
+fn normalize_extensions(extensions: &[String]) -> Result<Vec<String>, ApiError> {
+    let mut normalized: Vec<String> = Vec::new();
+
+    for extension in extensions {
+        let extension = extension.trim().trim_start_matches('.').to_lowercase();
+        if extension.is_empty() || !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(ApiError::ValidationError(format!("Invalid file extension '{}'", extension)));
+        }
+
+        let extension = format!(".{}", extension);
+        if !normalized.contains(&extension) {
+            normalized.push(extension);
+        }
+    }
+
+    Ok(normalized)
+}
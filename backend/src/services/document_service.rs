@@ -0,0 +1,473 @@
+use crate::models::{CreateDocumentRequest, Game, GameDocument, UpdateDocumentRequest, DOCUMENT_KINDS};
+use crate::utils::ApiError;
+use image::{DynamicImage, GrayImage, ImageFormat, RgbImage};
+use lopdf::Document;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const MAX_DOCUMENT_SIZE_BYTES: usize = 200 * 1024 * 1024;
+
+/// Text files are FAQs and walkthroughs, anything bigger is not plain text
+const MAX_TEXT_DOCUMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Extensions accepted for plain text documents, all stored as `.txt`
+const TEXT_EXTENSIONS: [&str; 3] = ["txt", "md", "nfo"];
+
+/// Largest side of the preview generated for PDFs and images
+const THUMBNAIL_MAX_DIMENSION: u32 = 320;
+
+/// Stores scanned manuals, maps and FAQs per game, with a page count and a preview for PDFs
+#[derive(Clone)]
+pub struct DocumentService {
+    document_storage_path: String,
+}
+
+/// What an upload turned out to be, from its contents rather than its name
+struct DocumentType {
+    extension: &'static str,
+    content_type: &'static str,
+}
+
+struct StoredDocument {
+    page_count: Option<i32>,
+    has_thumbnail: bool,
+}
+
+impl DocumentService {
+    pub fn new(document_storage_path: String) -> Self {
+        Self { document_storage_path }
+    }
+
+    pub async fn get_documents(&self, pool: &PgPool, game_id: &Uuid) -> Result<Vec<GameDocument>, ApiError> {
+        let documents = sqlx::query_as::<_, GameDocument>(
+            "SELECT * FROM game_documents WHERE game_id = $1 ORDER BY kind, title"
+        )
+        .bind(game_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(documents.into_iter().map(GameDocument::with_urls).collect())
+    }
+
+    pub async fn get_document(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        document_id: &Uuid,
+    ) -> Result<GameDocument, ApiError> {
+        let document = sqlx::query_as::<_, GameDocument>(
+            "SELECT * FROM game_documents WHERE id = $1 AND game_id = $2"
+        )
+        .bind(document_id)
+        .bind(game_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(document.with_urls())
+    }
+
+    /// Validates an upload by its contents, stores it under the game's folder and, for PDFs,
+    /// counts its pages and renders a preview from the first page's scan.
+    pub async fn create_document(
+        &self,
+        pool: &PgPool,
+        game: &Game,
+        request: &CreateDocumentRequest,
+        original_filename: &str,
+        data: Vec<u8>,
+        user_id: &Uuid,
+    ) -> Result<GameDocument, ApiError> {
+        check_kind(&request.kind)?;
+        if data.is_empty() {
+            return Err(ApiError::BadRequest("Document is empty".to_string()));
+        }
+        if data.len() > MAX_DOCUMENT_SIZE_BYTES {
+            return Err(ApiError::BadRequest("Document exceeds maximum size".to_string()));
+        }
+
+        // Browsers may send a full client path, only the last component is meaningful
+        let original_filename = original_filename
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        let document_type = detect_type(original_filename, &data)?;
+        let original_filename = if original_filename.is_empty() {
+            format!("document.{}", document_type.extension)
+        } else {
+            original_filename.chars().take(255).collect()
+        };
+        let title = match request.title.as_deref().map(str::trim) {
+            Some(title) if !title.is_empty() => title.to_string(),
+            _ => Path::new(&original_filename)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| original_filename.clone()),
+        };
+
+        let id = Uuid::new_v4();
+        let filename = format!("{}/{}.{}", game.id, id, document_type.extension);
+        let root = PathBuf::from(&self.document_storage_path);
+        let sha256 = hex::encode(Sha256::digest(&data));
+        let file_size = data.len() as i64;
+
+        let relative = filename.clone();
+        let extension = document_type.extension;
+        let stored = tokio::task::spawn_blocking(move || store_document(&root, &relative, extension, &data))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))??;
+
+        let document = sqlx::query_as::<_, GameDocument>(
+            "INSERT INTO game_documents
+                (id, game_id, kind, title, original_filename, filename, content_type,
+                 file_size, sha256, page_count, has_thumbnail, uploaded_by)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             RETURNING *"
+        )
+        .bind(id)
+        .bind(game.id)
+        .bind(&request.kind)
+        .bind(&title)
+        .bind(&original_filename)
+        .bind(&filename)
+        .bind(document_type.content_type)
+        .bind(file_size)
+        .bind(&sha256)
+        .bind(stored.page_count)
+        .bind(stored.has_thumbnail)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(document.with_urls())
+    }
+
+    pub async fn update_document(
+        &self,
+        pool: &PgPool,
+        document: &GameDocument,
+        request: &UpdateDocumentRequest,
+    ) -> Result<GameDocument, ApiError> {
+        if let Some(kind) = &request.kind {
+            check_kind(kind)?;
+        }
+        let title = request.title.as_deref().map(str::trim);
+        if title.is_some_and(str::is_empty) {
+            return Err(ApiError::ValidationError("Document title must not be empty".to_string()));
+        }
+
+        let updated = sqlx::query_as::<_, GameDocument>(
+            "UPDATE game_documents SET
+                kind = COALESCE($2, kind),
+                title = COALESCE($3, title)
+             WHERE id = $1
+             RETURNING *"
+        )
+        .bind(document.id)
+        .bind(&request.kind)
+        .bind(title)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(updated.with_urls())
+    }
+
+    /// Deletes the document along with its file and preview
+    pub async fn delete_document(&self, pool: &PgPool, document: &GameDocument) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM game_documents WHERE id = $1")
+            .bind(document.id)
+            .execute(pool)
+            .await?;
+
+        fs::remove_file(self.file_path(document)).ok();
+        fs::remove_file(self.thumbnail_path(document)).ok();
+
+        Ok(())
+    }
+
+    pub fn file_path(&self, document: &GameDocument) -> PathBuf {
+        Path::new(&self.document_storage_path).join(&document.filename)
+    }
+
+    pub fn thumbnail_path(&self, document: &GameDocument) -> PathBuf {
+        Path::new(&self.document_storage_path).join(thumbnail_filename(&document.filename))
+    }
+}
+
+/// Fills `documents` on each game
+pub async fn load_game_documents(pool: &PgPool, games: &mut [Game]) -> Result<(), ApiError> {
+    let ids: Vec<Uuid> = games.iter().map(|g| g.id).collect();
+    let documents = sqlx::query_as::<_, GameDocument>(
+        "SELECT * FROM game_documents WHERE game_id = ANY($1) ORDER BY kind, title"
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    for game in games.iter_mut() {
+        game.documents = documents
+            .iter()
+            .filter(|d| d.game_id == game.id)
+            .cloned()
+            .map(GameDocument::with_urls)
+            .collect();
+    }
+
+    Ok(())
+}
+
+fn check_kind(kind: &str) -> Result<(), ApiError> {
+    if !DOCUMENT_KINDS.contains(&kind) {
+        return Err(ApiError::ValidationError(format!(
+            "Invalid document kind '{}', expected one of: {}",
+            kind,
+            DOCUMENT_KINDS.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// PDFs and images are recognised by their signature, text files by their extension as long
+/// as the contents don't look binary
+fn detect_type(original_filename: &str, data: &[u8]) -> Result<DocumentType, ApiError> {
+    if data.starts_with(b"%PDF-") {
+        return Ok(DocumentType { extension: "pdf", content_type: "application/pdf" });
+    }
+
+    match image::guess_format(data) {
+        Ok(ImageFormat::Png) => return Ok(DocumentType { extension: "png", content_type: "image/png" }),
+        Ok(ImageFormat::Jpeg) => return Ok(DocumentType { extension: "jpg", content_type: "image/jpeg" }),
+        Ok(ImageFormat::WebP) => return Ok(DocumentType { extension: "webp", content_type: "image/webp" }),
+        Ok(ImageFormat::Gif) => return Ok(DocumentType { extension: "gif", content_type: "image/gif" }),
+        _ => {}
+    }
+
+    let extension = Path::new(original_filename)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        if data.len() > MAX_TEXT_DOCUMENT_BYTES {
+            return Err(ApiError::BadRequest("Text document exceeds maximum size".to_string()));
+        }
+        if data.contains(&0) {
+            return Err(ApiError::BadRequest("Text document contains binary data".to_string()));
+        }
+        // Old FAQs and NFOs are often in a DOS code page, so only claim UTF-8 when it is
+        let content_type = if std::str::from_utf8(data).is_ok() {
+            "text/plain; charset=utf-8"
+        } else {
+            "text/plain"
+        };
+        return Ok(DocumentType { extension: "txt", content_type });
+    }
+
+    Err(ApiError::BadRequest(
+        "Unsupported document type, expected a PDF, a PNG, JPEG, WebP or GIF image, or a .txt, .md or .nfo file"
+            .to_string(),
+    ))
+}
+
+fn thumbnail_filename(filename: &str) -> String {
+    let stem = filename.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(filename);
+    format!("{}_thumb.png", stem)
+}
+
+/// Writes the document and its preview under `root`. A PDF that can't be parsed is rejected,
+/// but one without a usable scan on its first page is simply stored without a preview.
+fn store_document(root: &Path, filename: &str, extension: &str, data: &[u8]) -> Result<StoredDocument, ApiError> {
+    let (page_count, preview) = match extension {
+        "pdf" => {
+            let pdf = Document::load_mem(data)
+                .map_err(|e| ApiError::BadRequest(format!("Invalid PDF: {}", e)))?;
+            let page_count = pdf.get_pages().len();
+            if page_count == 0 {
+                return Err(ApiError::BadRequest("PDF has no pages".to_string()));
+            }
+            (Some(page_count as i32), first_page_image(&pdf))
+        }
+        "txt" => (None, None),
+        _ => {
+            let image = image::load_from_memory(data)
+                .map_err(|e| ApiError::BadRequest(format!("Invalid image: {}", e)))?;
+            (None, Some(image))
+        }
+    };
+
+    let path = root.join(filename);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to create document directory: {}", e)))?;
+    }
+    fs::write(&path, data)
+        .map_err(|e| ApiError::InternalServerError(format!("Failed to write document: {}", e)))?;
+
+    let has_thumbnail = match preview {
+        Some(image) => {
+            let thumbnail = if image.width() > THUMBNAIL_MAX_DIMENSION || image.height() > THUMBNAIL_MAX_DIMENSION {
+                image.thumbnail(THUMBNAIL_MAX_DIMENSION, THUMBNAIL_MAX_DIMENSION)
+            } else {
+                image
+            };
+            DynamicImage::ImageRgba8(thumbnail.to_rgba8())
+                .save_with_format(root.join(thumbnail_filename(filename)), ImageFormat::Png)
+                .map_err(|e| ApiError::InternalServerError(format!("Failed to write thumbnail: {}", e)))?;
+            true
+        }
+        None => false,
+    };
+
+    Ok(StoredDocument { page_count, has_thumbnail })
+}
+
+/// Decodes the largest image drawn on the first page, which for a scanned manual is the page
+/// itself. Only JPEG and 8-bit RGB or grayscale Flate streams are handled; bilevel scans
+/// (CCITT, JBIG2) and JPEG 2000 get no preview.
+fn first_page_image(pdf: &Document) -> Option<DynamicImage> {
+    let (_, page_id) = pdf.get_pages().into_iter().next()?;
+    let images = pdf.get_page_images(page_id).ok()?;
+    let image = images.into_iter().max_by_key(|image| image.width * image.height)?;
+    let width = u32::try_from(image.width).ok()?;
+    let height = u32::try_from(image.height).ok()?;
+    let filters = image.filters.clone().unwrap_or_default();
+
+    match filters.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["DCTDecode"] => image::load_from_memory_with_format(image.content, ImageFormat::Jpeg).ok(),
+        [] | ["FlateDecode"] => {
+            if image.bits_per_component != Some(8) {
+                return None;
+            }
+            let pixels = if filters.is_empty() {
+                image.content.to_vec()
+            } else {
+                // lopdf refuses to decode streams marked as images, so decode a plain copy
+                let mut stream = pdf.get_object(image.id).ok()?.as_stream().ok()?.clone();
+                stream.dict.remove(b"Subtype");
+                stream.decompressed_content().ok()?
+            };
+
+            // The colour space may be an ICC profile, the sample count tells the channels apart
+            let samples = width as usize * height as usize;
+            if pixels.len() == samples * 3 {
+                RgbImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8)
+            } else if pixels.len() == samples {
+                GrayImage::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Object, Stream};
+
+    /// A PDF of blank pages, the first one showing `scan` when given
+    fn pdf(pages: usize, mut scan: Option<Stream>) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let mut kids: Vec<Object> = Vec::new();
+        for _ in 0..pages {
+            let mut resources = dictionary! {};
+            if let Some(scan) = scan.take() {
+                let image_id = doc.add_object(scan);
+                resources.set("XObject", dictionary! { "Im0" => image_id });
+            }
+            let content_id = doc.add_object(Stream::new(dictionary! {}, b"q 612 0 0 792 0 0 cm /Im0 Do Q".to_vec()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                "Resources" => resources,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => kids, "Count" => pages as i64 }),
+        );
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut data = Vec::new();
+        doc.save_to(&mut data).unwrap();
+        data
+    }
+
+    fn scan(width: u32, height: u32, color_space: &str, pixels: Vec<u8>) -> Stream {
+        Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => width as i64,
+                "Height" => height as i64,
+                "ColorSpace" => color_space,
+                "BitsPerComponent" => 8,
+            },
+            pixels,
+        )
+    }
+
+    fn temp_root() -> PathBuf {
+        std::env::temp_dir().join(format!("zerver-documents-{}", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn counts_pages_and_previews_the_first_page_scan() {
+        let data = pdf(3, Some(scan(640, 400, "DeviceRGB", vec![200; 640 * 400 * 3])));
+        assert_eq!(detect_type("manual.pdf", &data).unwrap().extension, "pdf");
+
+        let root = temp_root();
+        let stored = store_document(&root, "game/manual.pdf", "pdf", &data).unwrap();
+        assert_eq!(stored.page_count, Some(3));
+        assert!(stored.has_thumbnail);
+
+        let thumbnail = image::open(root.join("game/manual_thumb.png")).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (320, 200));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn decodes_compressed_grayscale_scans() {
+        let mut gray = scan(100, 50, "DeviceGray", vec![128; 100 * 50]);
+        gray.compress().unwrap();
+        let pdf = Document::load_mem(&pdf(1, Some(gray))).unwrap();
+
+        let image = first_page_image(&pdf).unwrap();
+        assert_eq!((image.width(), image.height()), (100, 50));
+        assert_eq!(image.to_luma8().get_pixel(0, 0).0, [128]);
+    }
+
+    #[test]
+    fn stores_pdfs_without_a_scan_without_preview() {
+        let root = temp_root();
+        let stored = store_document(&root, "game/faq.pdf", "pdf", &pdf(1, None)).unwrap();
+        assert_eq!(stored.page_count, Some(1));
+        assert!(!stored.has_thumbnail);
+        assert!(!root.join("game/faq_thumb.png").exists());
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(store_document(&root, "game/bad.pdf", "pdf", b"%PDF-1.4 not really").is_err());
+    }
+
+    #[test]
+    fn detects_documents_by_contents() {
+        let faq = detect_type("FAQ.TXT", "Walkthrough – part 1".as_bytes()).unwrap();
+        assert_eq!((faq.extension, faq.content_type), ("txt", "text/plain; charset=utf-8"));
+        // CP437 box drawing from an old NFO
+        assert_eq!(detect_type("release.nfo", &[0xC9, 0xCD, 0xBB]).unwrap().content_type, "text/plain");
+
+        assert!(detect_type("notes.txt", b"text\0with nul").is_err());
+        assert!(detect_type("manual.pdf", b"not a pdf").is_err());
+        assert!(detect_type("manual.doc", b"plain words").is_err());
+    }
+}
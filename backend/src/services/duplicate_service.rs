@@ -0,0 +1,378 @@
+use crate::models::{
+    DuplicateCandidate, DuplicateGroup, DuplicateReport, Game, MergeSummary, SaveState,
+};
+use crate::services::{normalize_title, GameService, ReviewService, SaveService};
+use crate::utils::ApiError;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use uuid::Uuid;
+
+/// Finds games that are in the library more than once and folds them into one entry
+#[derive(Clone, Default)]
+pub struct DuplicateService;
+
+impl DuplicateService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Groups games by ROM checksum and by normalised title within each console.
+    /// Checksums that haven't been computed yet are computed first.
+    pub async fn find_duplicates(
+        &self,
+        pool: &PgPool,
+        game_service: &GameService,
+    ) -> Result<DuplicateReport, ApiError> {
+        let games = sqlx::query_as::<_, Game>("SELECT * FROM games ORDER BY console_id, title")
+            .fetch_all(pool)
+            .await?;
+
+        let counts: HashMap<Uuid, (i64, i64)> = sqlx::query_as::<_, (Uuid, i64, i64)>(
+            "SELECT g.id,
+                    (SELECT COUNT(*) FROM save_states s WHERE s.game_id = g.id),
+                    (SELECT COUNT(*) FROM user_game_stats st WHERE st.game_id = g.id)
+             FROM games g"
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|(id, saves, players)| (id, (saves, players)))
+        .collect();
+
+        let mut unreadable = Vec::new();
+        let mut by_checksum: BTreeMap<(String, String), Vec<DuplicateCandidate>> = BTreeMap::new();
+        let mut by_title: BTreeMap<(String, String), Vec<DuplicateCandidate>> = BTreeMap::new();
+
+        for game in &games {
+            let crc32 = match game_service.ensure_rom_crc32(pool, game).await {
+                Ok(crc) => crc,
+                Err(e) => {
+                    log::warn!("No CRC32 for {}: {}", game.rom_filename, e);
+                    unreadable.push(game.id);
+                    continue;
+                }
+            };
+
+            let (save_states, players) = counts.get(&game.id).copied().unwrap_or_default();
+            let candidate = DuplicateCandidate {
+                id: game.id,
+                title: game.title.clone(),
+                rom_filename: game.rom_filename.clone(),
+                rom_size_bytes: game.rom_size_bytes,
+                rom_crc32: Some(crc32.clone()),
+                region: game.region.clone(),
+                created_at: game.created_at,
+                save_states,
+                players,
+            };
+
+            by_checksum
+                .entry((game.console_id.clone(), crc32))
+                .or_default()
+                .push(candidate.clone());
+            by_title
+                .entry((game.console_id.clone(), normalize_title(&game.title)))
+                .or_default()
+                .push(candidate);
+        }
+
+        let exact = duplicate_groups(by_checksum, |_| true);
+        // Title groups made of a single ROM are already listed as exact duplicates
+        let variants = duplicate_groups(by_title, |games| {
+            games.iter().any(|g| g.rom_crc32 != games[0].rom_crc32)
+        });
+
+        Ok(DuplicateReport {
+            exact,
+            variants,
+            unreadable,
+        })
+    }
+
+    /// Moves saves, play stats, achievements, collections and the rest of the duplicates'
+    /// user data onto `primary_id`, then deletes the duplicates and their ROM files.
+    /// Where both games hold data that must be unique per user (reviews, play status),
+    /// the primary's is kept; for save slots the newest save wins.
+    pub async fn merge_games(
+        &self,
+        pool: &PgPool,
+        game_service: &GameService,
+        save_service: &SaveService,
+        primary_id: &Uuid,
+        duplicate_ids: &[Uuid],
+    ) -> Result<MergeSummary, ApiError> {
+        if duplicate_ids.contains(primary_id) {
+            return Err(ApiError::BadRequest("The primary game can't be one of the duplicates".to_string()));
+        }
+
+        let primary = game_service.get_game(pool, primary_id).await?;
+        let duplicates = sqlx::query_as::<_, Game>("SELECT * FROM games WHERE id = ANY($1)")
+            .bind(duplicate_ids)
+            .fetch_all(pool)
+            .await?;
+        if let Some(missing) = duplicate_ids.iter().find(|id| !duplicates.iter().any(|g| &g.id == *id)) {
+            return Err(ApiError::NotFound(format!("Game {} not found", missing)));
+        }
+        if let Some(other) = duplicates.iter().find(|g| g.console_id != primary.console_id) {
+            return Err(ApiError::BadRequest(format!(
+                "{} is a {} game, not {}",
+                other.title, other.console_id, primary.console_id
+            )));
+        }
+        self.check_movable_patches(pool, &primary, &duplicates).await?;
+
+        let mut summary = MergeSummary {
+            primary_id: primary.id,
+            ..Default::default()
+        };
+        let mut tx = pool.begin().await?;
+
+        // Saves: one per user, patch and slot, the newest one across all merged games
+        let replaced = sqlx::query_as::<_, SaveState>(
+            "DELETE FROM save_states s
+             WHERE (s.game_id = ANY($1) OR s.game_id = $2)
+               AND EXISTS (
+                   SELECT 1 FROM save_states n
+                   WHERE (n.game_id = ANY($1) OR n.game_id = $2)
+                     AND n.user_id = s.user_id
+                     AND n.patch_id IS NOT DISTINCT FROM s.patch_id
+                     AND n.slot = s.slot
+                     AND (COALESCE(n.created_at, 'epoch'), n.id) > (COALESCE(s.created_at, 'epoch'), s.id))
+             RETURNING s.*"
+        )
+        .bind(duplicate_ids)
+        .bind(primary.id)
+        .fetch_all(&mut *tx)
+        .await?;
+        summary.save_states_replaced = replaced.len() as u64;
+        summary.save_states_moved = sqlx::query("UPDATE save_states SET game_id = $2 WHERE game_id = ANY($1)")
+            .bind(duplicate_ids)
+            .bind(primary.id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // Play stats add up
+        summary.stats_merged = sqlx::query(
+            "INSERT INTO user_game_stats (user_id, game_id, total_playtime_seconds, last_played, first_played, play_count)
+             SELECT user_id, $2, SUM(total_playtime_seconds)::BIGINT, MAX(last_played), MIN(first_played), SUM(play_count)::INTEGER
+             FROM user_game_stats
+             WHERE game_id = ANY($1)
+             GROUP BY user_id
+             ON CONFLICT (user_id, game_id) DO UPDATE SET
+                total_playtime_seconds = user_game_stats.total_playtime_seconds + EXCLUDED.total_playtime_seconds,
+                last_played = GREATEST(user_game_stats.last_played, EXCLUDED.last_played),
+                first_played = LEAST(user_game_stats.first_played, EXCLUDED.first_played),
+                play_count = user_game_stats.play_count + EXCLUDED.play_count"
+        )
+        .bind(duplicate_ids)
+        .bind(primary.id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        summary.achievements_moved = move_rows(&mut tx, "achievements", &primary.id, duplicate_ids).await?;
+        for table in ["rom_patches", "game_cheats", "game_documents", "game_edits", "user_activity"] {
+            move_rows(&mut tx, table, &primary.id, duplicate_ids).await?;
+        }
+
+        // The primary takes the duplicate's place in collections it isn't in yet
+        summary.collections_updated =
+            move_unique_rows(&mut tx, "collection_games", &["collection_id"], "position", &primary.id, duplicate_ids)
+                .await?;
+        move_unique_rows(&mut tx, "user_favorites", &["user_id"], "created_at", &primary.id, duplicate_ids).await?;
+        move_unique_rows(&mut tx, "game_reviews", &["user_id"], "updated_at DESC", &primary.id, duplicate_ids).await?;
+        move_unique_rows(&mut tx, "user_game_status", &["user_id"], "updated_at DESC", &primary.id, duplicate_ids)
+            .await?;
+        move_unique_rows(&mut tx, "game_input_overrides", &["user_id"], "updated_at DESC", &primary.id, duplicate_ids)
+            .await?;
+        move_unique_rows(&mut tx, "user_game_settings", &["user_id"], "updated_at DESC", &primary.id, duplicate_ids)
+            .await?;
+        move_unique_rows(&mut tx, "game_tags", &["tag_id"], "created_at", &primary.id, duplicate_ids).await?;
+        move_unique_rows(&mut tx, "game_media", &["kind", "content_hash"], "created_at", &primary.id, duplicate_ids)
+            .await?;
+        move_unique_rows(&mut tx, "game_user_shares", &["user_id"], "user_id", &primary.id, duplicate_ids).await?;
+        move_unique_rows(&mut tx, "game_group_shares", &["group_id"], "group_id", &primary.id, duplicate_ids).await?;
+
+        // Emulator overrides only carry over when the primary has none
+        sqlx::query(
+            "INSERT INTO game_emulator_overrides (game_id, config, updated_by, updated_at)
+             SELECT $2, config, updated_by, updated_at FROM game_emulator_overrides
+             WHERE game_id = ANY($1)
+             ORDER BY updated_at DESC
+             LIMIT 1
+             ON CONFLICT (game_id) DO NOTHING"
+        )
+        .bind(duplicate_ids)
+        .bind(primary.id)
+        .execute(&mut *tx)
+        .await?;
+
+        ReviewService::refresh_rating(&mut tx, &primary.id).await?;
+
+        summary.merged_games = sqlx::query("DELETE FROM games WHERE id = ANY($1)")
+            .bind(duplicate_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // A duplicate may have been the last game of its title
+        let title_ids: Vec<Uuid> = duplicates.iter().filter_map(|g| g.title_id).collect();
+        sqlx::query(
+            "DELETE FROM game_titles t
+             WHERE t.id = ANY($1) AND NOT EXISTS (SELECT 1 FROM games g WHERE g.title_id = t.id)"
+        )
+        .bind(&title_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        // Files go once the database no longer points at them. A ROM left behind would come
+        // back as a new game on the next scan.
+        for save in &replaced {
+            fs::remove_file(save_service.get_save_file_path(&save.save_data_filename)).ok();
+            if let Some(screenshot_filename) = &save.screenshot_filename {
+                fs::remove_file(save_service.get_save_file_path(screenshot_filename)).ok();
+            }
+        }
+        for game in &duplicates {
+            let path = game_service.get_rom_path(&game.console_id, &game.rom_filename);
+            match fs::remove_file(&path) {
+                Ok(()) => summary.roms_deleted += 1,
+                Err(e) => log::warn!("Could not delete merged ROM {}: {}", path, e),
+            }
+        }
+
+        log::info!(
+            "Merged {} duplicates into {} ({})",
+            summary.merged_games,
+            primary.title,
+            primary.id
+        );
+
+        Ok(summary)
+    }
+
+    /// Patches only apply to the ROM they were made for, so they can only move between
+    /// games with the same checksum, and only when the primary has no patch of that name.
+    async fn check_movable_patches(
+        &self,
+        pool: &PgPool,
+        primary: &Game,
+        duplicates: &[Game],
+    ) -> Result<(), ApiError> {
+        let patches: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT game_id, name FROM rom_patches WHERE game_id = ANY($1) OR game_id = $2"
+        )
+        .bind(duplicates.iter().map(|g| g.id).collect::<Vec<_>>())
+        .bind(primary.id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut names: Vec<&str> = patches
+            .iter()
+            .filter(|(game_id, _)| *game_id == primary.id)
+            .map(|(_, name)| name.as_str())
+            .collect();
+
+        for duplicate in duplicates {
+            for (_, name) in patches.iter().filter(|(game_id, _)| *game_id == duplicate.id) {
+                if duplicate.rom_crc32.is_none() || duplicate.rom_crc32 != primary.rom_crc32 {
+                    return Err(ApiError::BadRequest(format!(
+                        "Patch '{}' of {} was made for a different ROM than {}, delete it before merging",
+                        name, duplicate.rom_filename, primary.rom_filename
+                    )));
+                }
+                if names.contains(&name.as_str()) {
+                    return Err(ApiError::BadRequest(format!(
+                        "More than one of the merged games has a patch named '{}'",
+                        name
+                    )));
+                }
+                names.push(name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps the groups with more than one game that satisfy `keep`
+fn duplicate_groups(
+    groups: BTreeMap<(String, String), Vec<DuplicateCandidate>>,
+    keep: impl Fn(&[DuplicateCandidate]) -> bool,
+) -> Vec<DuplicateGroup> {
+    groups
+        .into_iter()
+        .filter(|(_, games)| games.len() > 1 && keep(games))
+        .map(|((console_id, key), mut games)| {
+            games.sort_by(|a, b| {
+                (b.save_states + b.players)
+                    .cmp(&(a.save_states + a.players))
+                    .then(a.created_at.cmp(&b.created_at))
+            });
+            DuplicateGroup {
+                console_id,
+                key,
+                games,
+            }
+        })
+        .collect()
+}
+
+async fn move_rows(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &str,
+    primary_id: &Uuid,
+    duplicate_ids: &[Uuid],
+) -> Result<u64, ApiError> {
+    let moved = sqlx::query(&format!("UPDATE {} SET game_id = $2 WHERE game_id = ANY($1)", table))
+        .bind(duplicate_ids)
+        .bind(primary_id)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+    Ok(moved)
+}
+
+/// Moves rows of a table where `keys` are unique together with `game_id`. Keys the primary
+/// already has keep the primary's row, otherwise the first duplicate row by `order` moves.
+/// Rows left on the duplicates are removed along with them.
+async fn move_unique_rows(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &str,
+    keys: &[&str],
+    order: &str,
+    primary_id: &Uuid,
+    duplicate_ids: &[Uuid],
+) -> Result<u64, ApiError> {
+    let key_list = keys.join(", ");
+    let same_keys = keys
+        .iter()
+        .map(|k| format!("p.{k} = t.{k}", k = k))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    let moved = sqlx::query(&format!(
+        "UPDATE {table} t SET game_id = $2
+         WHERE t.ctid IN (
+             SELECT DISTINCT ON ({keys}) ctid FROM {table}
+             WHERE game_id = ANY($1)
+             ORDER BY {keys}, {order})
+           AND NOT EXISTS (SELECT 1 FROM {table} p WHERE p.game_id = $2 AND {same_keys})",
+        table = table,
+        keys = key_list,
+        order = order,
+        same_keys = same_keys
+    ))
+    .bind(duplicate_ids)
+    .bind(primary_id)
+    .execute(&mut **tx)
+    .await?
+    .rows_affected();
+
+    Ok(moved)
+}
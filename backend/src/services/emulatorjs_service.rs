@@ -0,0 +1,175 @@
+use crate::models::{
+    Console, ConsoleCoreStatus, EmulatorJsCoreReport, EmulatorJsInfo, InstalledCore,
+};
+use crate::utils::ApiError;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// URL the versioned data directories are served under
+pub const EMULATORJS_ASSETS_PATH: &str = "/api/v1/emulatorjs/assets";
+
+/// Core files behind each EmulatorJS system name, in the order EmulatorJS picks them.
+/// `emulator_core` values not listed here are taken to be a core name themselves.
+const SYSTEM_CORES: &[(&str, &[&str])] = &[
+    ("nes", &["fceumm", "nestopia"]),
+    ("snes", &["snes9x"]),
+    ("gb", &["gambatte"]),
+    ("gbc", &["gambatte"]),
+    ("gba", &["mgba"]),
+    ("nds", &["melonds", "desmume2015"]),
+    ("n64", &["mupen64plus_next", "parallel_n64"]),
+    ("vb", &["beetle_vb"]),
+    ("segaMS", &["smsplus", "genesis_plus_gx"]),
+    ("segaGG", &["genesis_plus_gx"]),
+    ("segaMD", &["genesis_plus_gx"]),
+    ("segaCD", &["genesis_plus_gx"]),
+    ("sega32x", &["picodrive"]),
+    ("segaSaturn", &["yabause"]),
+    ("dreamcast", &["flycast"]),
+    ("psx", &["pcsx_rearmed", "mednafen_psx_hw"]),
+    ("psp", &["ppsspp"]),
+    ("ps2", &["pcsx2", "play"]),
+    ("atari2600", &["stella2014"]),
+    ("arcade", &["fbneo", "mame2003_plus"]),
+];
+
+/// Build suffixes of the files in `cores/`, longest first so `-thread-legacy-wasm.data` wins
+const CORE_VARIANTS: &[(&str, &str)] = &[
+    ("-thread-legacy-wasm.data", "thread-legacy"),
+    ("-legacy-wasm.data", "legacy"),
+    ("-thread-wasm.data", "thread"),
+    ("-wasm.data", "wasm"),
+];
+
+/// Self-hosted EmulatorJS: each subdirectory of the root is the `data/` directory of one release
+#[derive(Clone)]
+pub struct EmulatorJsService {
+    root: String,
+    /// Pinned release; the newest installed one when unset
+    version: Option<String>,
+}
+
+impl EmulatorJsService {
+    pub fn new(root: String, version: Option<String>) -> Self {
+        Self { root, version }
+    }
+
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Installed releases, oldest first. A release needs at least `loader.js`.
+    pub fn installed_versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = fs::read_dir(&self.root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| e.path().join("loader.js").is_file())
+                    .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        versions.sort_by_key(|v| version_key(v));
+        versions
+    }
+
+    pub fn current_version(&self) -> Result<String, ApiError> {
+        let installed = self.installed_versions();
+
+        match &self.version {
+            Some(version) if installed.contains(version) => Ok(version.clone()),
+            Some(version) => Err(ApiError::NotFound(format!(
+                "EmulatorJS {} is not installed in {}",
+                version, self.root
+            ))),
+            None => installed.last().cloned().ok_or_else(|| {
+                ApiError::NotFound(format!("No EmulatorJS release installed in {}", self.root))
+            }),
+        }
+    }
+
+    pub fn info(&self) -> Result<EmulatorJsInfo, ApiError> {
+        let version = self.current_version()?;
+
+        Ok(EmulatorJsInfo {
+            path_to_data: format!("{}/{}/", EMULATORJS_ASSETS_PATH, version),
+            version,
+            installed_versions: self.installed_versions(),
+        })
+    }
+
+    /// Core builds present in a release's `cores/` directory
+    pub fn installed_cores(&self, version: &str) -> Vec<InstalledCore> {
+        let mut cores: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let entries = fs::read_dir(self.version_dir(version).join("cores"));
+
+        for entry in entries.into_iter().flatten().flatten() {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            if let Some((name, variant)) = CORE_VARIANTS
+                .iter()
+                .find_map(|(suffix, variant)| filename.strip_suffix(suffix).map(|name| (name, variant)))
+            {
+                cores.entry(name.to_string()).or_default().push(variant.to_string());
+            }
+        }
+
+        cores
+            .into_iter()
+            .map(|(name, mut variants)| {
+                variants.sort();
+                InstalledCore { name, variants }
+            })
+            .collect()
+    }
+
+    /// Which consoles can run with the installed cores of the current release
+    pub fn core_report(&self, consoles: &[Console]) -> Result<EmulatorJsCoreReport, ApiError> {
+        let version = self.current_version()?;
+        let cores = self.installed_cores(&version);
+
+        let consoles = consoles
+            .iter()
+            .map(|console| {
+                let candidate_cores: Vec<String> = SYSTEM_CORES
+                    .iter()
+                    .find(|(system, _)| *system == console.emulator_core)
+                    .map(|(_, cores)| cores.iter().map(|c| c.to_string()).collect())
+                    .unwrap_or_else(|| vec![console.emulator_core.clone()]);
+                let installed = candidate_cores
+                    .iter()
+                    .find_map(|name| cores.iter().find(|core| &core.name == name));
+
+                ConsoleCoreStatus {
+                    console_id: console.id.clone(),
+                    emulator_core: console.emulator_core.clone(),
+                    installed_core: installed.map(|core| core.name.clone()),
+                    threads_requested: console.emulator_config.threads.unwrap_or(false),
+                    threads_available: installed
+                        .is_some_and(|core| core.variants.iter().any(|v| v.starts_with("thread"))),
+                    candidate_cores,
+                }
+            })
+            .collect();
+
+        Ok(EmulatorJsCoreReport {
+            version,
+            consoles,
+            cores,
+        })
+    }
+
+    fn version_dir(&self, version: &str) -> PathBuf {
+        Path::new(&self.root).join(version)
+    }
+}
+
+/// Orders "4.2.10" after "4.2.3"; non-numeric parts compare as text
+fn version_key(version: &str) -> Vec<(u64, String)> {
+    version
+        .trim_start_matches('v')
+        .split(['.', '-'])
+        .map(|part| (part.parse().unwrap_or(0), part.to_string()))
+        .collect()
+}
@@ -1,10 +1,44 @@
-use crate::models::{Console, Game};
+use crate::models::{
+    Console, FacetCount, Game, GameEdit, GameFacets, GameMetadata, GameQuery, GameSort,
+    MetadataEnrichSummary, Rule, SortOrder, UpdateGameRequest, DEFAULT_PREFERRED_REGIONS, LOCKABLE_FIELDS,
+};
+use crate::services::{
+    escape_like, load_game_patches, load_game_tags, load_game_variants, normalize_tag_names, push_rule,
+    replace_genre_tags, tag_slug,
+};
 use crate::utils::ApiError;
-use sqlx::PgPool;
+use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sqlx::{Acquire, PgPool, Postgres, QueryBuilder, Transaction};
 use std::fs;
-use std::path::Path;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+/// A source of game metadata used by the enrichment job
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Looks up metadata for a game; `crc32` is the ROM checksum when it could be computed
+    async fn lookup(
+        &self,
+        pool: &PgPool,
+        game: &Game,
+        crc32: Option<&str>,
+    ) -> Result<Option<GameMetadata>, ApiError>;
+}
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+/// Archives and compressed or disc image formats that `get_rom` serves without compression
+const INCOMPRESSIBLE_ROM_EXTENSIONS: [&str; 12] =
+    ["zip", "7z", "rar", "gz", "chd", "cso", "pbp", "iso", "bin", "img", "cue", "rvz"];
+const MAX_COMPRESSIBLE_ROM_BYTES: u64 = 64 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct GameService {
     rom_storage_path: String,
@@ -82,14 +116,17 @@ impl GameService {
                                 .replace('_', " ")
                                 .replace('-', " ");
 
+                            let region = extract_region(filename);
+
                             sqlx::query(
-                                "INSERT INTO games (console_id, title, rom_filename, rom_size_bytes)
-                                 VALUES ($1, $2, $3, $4)"
+                                "INSERT INTO games (console_id, title, rom_filename, rom_size_bytes, region)
+                                 VALUES ($1, $2, $3, $4, $5)"
                             )
                             .bind(&console.id)
                             .bind(&title)
                             .bind(filename)
                             .bind(file_size)
+                            .bind(&region)
                             .execute(pool)
                             .await?;
 
@@ -107,56 +144,457 @@ impl GameService {
     pub async fn get_games(
         &self,
         pool: &PgPool,
-        console_id: Option<&str>,
-        limit: i64,
-        offset: i64,
-    ) -> Result<(Vec<Game>, i64), ApiError> {
-        let (games, total) = if let Some(console) = console_id {
-            let games = sqlx::query_as::<_, Game>(
-                "SELECT * FROM games WHERE console_id = $1 ORDER BY title LIMIT $2 OFFSET $3"
-            )
-            .bind(console)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(pool)
-            .await?;
+        query: &GameQuery,
+        user_id: &Uuid,
+        rule: Option<&Rule>,
+    ) -> Result<(Vec<Game>, i64, GameFacets), ApiError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0).max(0);
+        let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
 
-            let total: i64 = sqlx::query_scalar(
-                "SELECT COUNT(*) FROM games WHERE console_id = $1"
-            )
-            .bind(console)
-            .fetch_one(pool)
-            .await?;
+        let group_titles = query.group_titles.unwrap_or(false);
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT g.* FROM ");
+        if group_titles {
+            // The preferred release of each title among the matching games
+            builder.push("(SELECT DISTINCT ON (COALESCE(g.title_id, g.id)) g.* FROM games g WHERE TRUE");
+            Self::push_game_filters(&mut builder, query, user_id, rule)?;
+            builder.push(" ORDER BY COALESCE(g.title_id, g.id), ");
+            push_region_rank(&mut builder, user_id);
+            builder.push(", g.created_at, g.id) g");
+        } else {
+            builder.push("games g WHERE TRUE");
+            Self::push_game_filters(&mut builder, query, user_id, rule)?;
+        }
 
-            (games, total)
+        let sort = query.sort.unwrap_or(if search.is_some() {
+            GameSort::Relevance
+        } else if query.collection.is_some() {
+            GameSort::Position
         } else {
-            let games = sqlx::query_as::<_, Game>(
-                "SELECT * FROM games ORDER BY title LIMIT $1 OFFSET $2"
-            )
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(pool)
-            .await?;
+            GameSort::Title
+        });
+        let direction = match (sort, query.order) {
+            (_, Some(SortOrder::Asc)) => "ASC",
+            (_, Some(SortOrder::Desc)) => "DESC",
+            (GameSort::Title, None) | (GameSort::Year, None) | (GameSort::Position, None) => "ASC",
+            // Without a search, relevance falls back to title order
+            (GameSort::Relevance, None) if search.is_none() => "ASC",
+            _ => "DESC",
+        };
+
+        builder.push(" ORDER BY ");
+        match (sort, search) {
+            (GameSort::Relevance, Some(q)) => {
+                builder
+                    .push("ts_rank(g.search_vector, plainto_tsquery('simple', ")
+                    .push_bind(q.to_string())
+                    .push(")) + similarity(g.title, ")
+                    .push_bind(q.to_string())
+                    .push(") ")
+                    .push(direction);
+            }
+            (GameSort::Year, _) => {
+                builder.push(format!("g.release_year {} NULLS LAST", direction));
+            }
+            (GameSort::Recent, _) => {
+                builder.push(format!("g.created_at {}", direction));
+            }
+            (GameSort::Rating, _) => {
+                builder.push(format!("g.user_rating {} NULLS LAST, g.user_rating_count DESC", direction));
+            }
+            (GameSort::MostPlayed, _) => {
+                builder.push(format!(
+                    "(SELECT COALESCE(SUM(s.play_count), 0) FROM user_game_stats s WHERE s.game_id = g.id) {}",
+                    direction
+                ));
+            }
+            (GameSort::Position, _) if query.collection.is_some() => {
+                builder
+                    .push("(SELECT cg.position FROM collection_games cg WHERE cg.game_id = g.id AND cg.collection_id = ")
+                    .push_bind(query.collection)
+                    .push(") ")
+                    .push(direction);
+            }
+            (GameSort::Title, _) | (GameSort::Relevance, None) | (GameSort::Position, _) => {
+                builder.push(format!("g.title {}", direction));
+            }
+        }
+        builder.push(", g.title, g.id");
+
+        builder
+            .push(" LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let mut games = builder.build_query_as::<Game>().fetch_all(pool).await?;
+        load_game_tags(pool, &mut games).await?;
+        load_game_patches(pool, &mut games).await?;
+        if group_titles {
+            load_game_variants(pool, &mut games, user_id).await?;
+        }
 
-            let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM games")
-                .fetch_one(pool)
-                .await?;
+        let mut count = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM games g WHERE TRUE",
+            count_expression(query)
+        ));
+        Self::push_game_filters(&mut count, query, user_id, rule)?;
+        let total: i64 = count.build_query_scalar().fetch_one(pool).await?;
 
-            (games, total)
+        let facets = GameFacets {
+            consoles: self
+                .facet_counts(pool, query, user_id, rule, "g.console_id", "")
+                .await?,
+            genres: self
+                .facet_counts(
+                    pool,
+                    query,
+                    user_id,
+                    rule,
+                    "t.name",
+                    " JOIN game_tags gt ON gt.game_id = g.id JOIN tags t ON t.id = gt.tag_id AND t.kind = 'genre'",
+                )
+                .await?,
         };
 
-        Ok((games, total))
+        Ok((games, total, facets))
+    }
+
+    /// Counts matching games grouped by `column`, using the same filters as the listing.
+    /// `joins` brings in the tables `column` comes from.
+    async fn facet_counts(
+        &self,
+        pool: &PgPool,
+        query: &GameQuery,
+        user_id: &Uuid,
+        rule: Option<&Rule>,
+        column: &str,
+        joins: &str,
+    ) -> Result<Vec<FacetCount>, ApiError> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {col} AS value, {count} AS count FROM games g{joins} WHERE {col} IS NOT NULL",
+            col = column,
+            count = count_expression(query),
+            joins = joins
+        ));
+        Self::push_game_filters(&mut builder, query, user_id, rule)?;
+        builder.push(" GROUP BY 1 ORDER BY 2 DESC, 1");
+
+        let facets = builder.build_query_as::<FacetCount>().fetch_all(pool).await?;
+
+        Ok(facets)
+    }
+
+    /// Appends the `WHERE` conditions shared by the listing, count and facet queries.
+    /// `rule` holds the smart collection rules when the listing is restricted to one.
+    fn push_game_filters(
+        builder: &mut QueryBuilder<'_, Postgres>,
+        query: &GameQuery,
+        user_id: &Uuid,
+        rule: Option<&Rule>,
+    ) -> Result<(), ApiError> {
+        builder
+            .push(" AND EXISTS (SELECT 1 FROM (SELECT ")
+            .push_bind(*user_id)
+            .push("::uuid AS id) viewer WHERE ")
+            .push(game_visible_to("viewer.id"))
+            .push(")");
+
+        if let Some(q) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            // Full-text match first, trigram similarity catches typos and partial titles
+            builder
+                .push(" AND (g.search_vector @@ plainto_tsquery('simple', ")
+                .push_bind(q.to_string())
+                .push(") OR g.title % ")
+                .push_bind(q.to_string())
+                .push(" OR g.title ILIKE ")
+                .push_bind(format!("%{}%", escape_like(q)))
+                .push(" ESCAPE '\\')");
+        }
+
+        if let Some(console) = &query.console {
+            builder.push(" AND g.console_id = ").push_bind(console.clone());
+        }
+
+        if let Some(genre) = query.genre.as_deref().and_then(|g| tag_slug(g).ok()) {
+            builder
+                .push(" AND EXISTS (SELECT 1 FROM game_tags gt JOIN tags t ON t.id = gt.tag_id")
+                .push(" WHERE gt.game_id = g.id AND t.kind = 'genre' AND t.slug = ")
+                .push_bind(genre)
+                .push(")");
+        }
+
+        if let Some(tags) = &query.tags {
+            for slug in tags.split(',').filter_map(|t| tag_slug(t).ok()) {
+                builder
+                    .push(" AND EXISTS (SELECT 1 FROM game_tags gt JOIN tags t ON t.id = gt.tag_id")
+                    .push(" WHERE gt.game_id = g.id AND t.slug = ")
+                    .push_bind(slug)
+                    .push(")");
+            }
+        }
+
+        if let Some(developer) = &query.developer {
+            builder
+                .push(" AND g.developer ILIKE ")
+                .push_bind(format!("%{}%", escape_like(developer)))
+                .push(" ESCAPE '\\'");
+        }
+
+        if let Some(region) = &query.region {
+            builder
+                .push(" AND g.region ILIKE ")
+                .push_bind(format!("%{}%", escape_like(region)))
+                .push(" ESCAPE '\\'");
+        }
+
+        if let Some(year_from) = query.year_from {
+            builder.push(" AND g.release_year >= ").push_bind(year_from);
+        }
+
+        if let Some(year_to) = query.year_to {
+            builder.push(" AND g.release_year <= ").push_bind(year_to);
+        }
+
+        if let Some(favorite) = query.favorite {
+            builder
+                .push(if favorite { " AND EXISTS" } else { " AND NOT EXISTS" })
+                .push(" (SELECT 1 FROM user_favorites f WHERE f.game_id = g.id AND f.user_id = ")
+                .push_bind(*user_id)
+                .push(")");
+        }
+
+        // Access to the collection is checked by the handler
+        if let Some(collection_id) = query.collection {
+            builder
+                .push(" AND EXISTS (SELECT 1 FROM collection_games cg WHERE cg.game_id = g.id AND cg.collection_id = ")
+                .push_bind(collection_id)
+                .push(")");
+        }
+
+        // Access to the smart collection is checked by the handler
+        if let Some(rule) = rule {
+            push_rule(builder, rule, user_id)?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_console_games(&self, pool: &PgPool, console_id: &str) -> Result<Vec<Game>, ApiError> {
+        let mut games = sqlx::query_as::<_, Game>("SELECT * FROM games WHERE console_id = $1 ORDER BY title")
+            .bind(console_id)
+            .fetch_all(pool)
+            .await?;
+        load_game_tags(pool, &mut games).await?;
+        load_game_patches(pool, &mut games).await?;
+
+        Ok(games)
     }
 
     pub async fn get_game(&self, pool: &PgPool, game_id: &Uuid) -> Result<Game, ApiError> {
-        let game = sqlx::query_as::<_, Game>("SELECT * FROM games WHERE id = $1")
+        let mut game = sqlx::query_as::<_, Game>("SELECT * FROM games WHERE id = $1")
             .bind(game_id)
             .fetch_one(pool)
             .await?;
+        load_game_tags(pool, std::slice::from_mut(&mut game)).await?;
+        load_game_patches(pool, std::slice::from_mut(&mut game)).await?;
+
+        Ok(game)
+    }
+
+    /// Returns a game the user is allowed to see, hidden games look like missing ones
+    pub async fn get_visible_game(&self, pool: &PgPool, game_id: &Uuid, user_id: &Uuid) -> Result<Game, ApiError> {
+        let mut game = sqlx::query_as::<_, Game>(&format!(
+            "SELECT g.* FROM games g WHERE g.id = $1 AND {}",
+            game_visible_to("$2")
+        ))
+        .bind(game_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+        load_game_tags(pool, std::slice::from_mut(&mut game)).await?;
+        load_game_patches(pool, std::slice::from_mut(&mut game)).await?;
 
         Ok(game)
     }
 
+    /// Applies an admin edit. Locks are ignored, and edited fields become locked
+    /// unless the request sets `locked_fields` explicitly.
+    pub async fn update_game(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        request: &UpdateGameRequest,
+        user_id: &Uuid,
+    ) -> Result<Game, ApiError> {
+        if let Some(locked) = &request.locked_fields {
+            if let Some(field) = locked.iter().find(|f| !LOCKABLE_FIELDS.contains(&f.as_str())) {
+                return Err(ApiError::ValidationError(format!("Unknown field: {}", field)));
+            }
+        }
+
+        let game = self.get_game(pool, game_id).await?;
+        let (mut updated, genres, changes) = merge_metadata(&game, &request.metadata, &[]);
+
+        updated.locked_fields = match &request.locked_fields {
+            Some(locked) => locked.clone(),
+            None => {
+                let mut locked = game.locked_fields.clone();
+                for change in &changes {
+                    if !locked.iter().any(|f| f == change.field) {
+                        locked.push(change.field.to_string());
+                    }
+                }
+                locked
+            }
+        };
+
+        self.save_metadata(pool, &updated, &genres, &changes, Some(user_id), "manual")
+            .await
+    }
+
+    /// Applies metadata coming from a rescan or an import, skipping locked fields.
+    /// Returns whether anything changed. Imports pass their transaction as `conn`.
+    pub async fn apply_metadata<'c>(
+        &self,
+        conn: impl Acquire<'c, Database = Postgres>,
+        game: &Game,
+        metadata: &GameMetadata,
+        source: &str,
+    ) -> Result<bool, ApiError> {
+        let metadata = metadata.clone().clamp_lengths();
+        let (updated, genres, changes) = merge_metadata(game, &metadata, &game.locked_fields);
+
+        if changes.is_empty() {
+            return Ok(false);
+        }
+
+        let mut tx = conn.begin().await?;
+        write_metadata(&mut tx, &updated, &genres, &changes, None, source).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn save_metadata(
+        &self,
+        pool: &PgPool,
+        game: &Game,
+        genres: &[String],
+        changes: &[FieldChange],
+        user_id: Option<&Uuid>,
+        source: &str,
+    ) -> Result<Game, ApiError> {
+        let mut tx = pool.begin().await?;
+        let mut saved = write_metadata(&mut tx, game, genres, changes, user_id, source).await?;
+        tx.commit().await?;
+
+        load_game_tags(pool, std::slice::from_mut(&mut saved)).await?;
+        load_game_patches(pool, std::slice::from_mut(&mut saved)).await?;
+
+        Ok(saved)
+    }
+
+    pub async fn get_game_history(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+    ) -> Result<Vec<GameEdit>, ApiError> {
+        let edits = sqlx::query_as::<_, GameEdit>(
+            "SELECT * FROM game_edits WHERE game_id = $1 ORDER BY created_at DESC"
+        )
+        .bind(game_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(edits)
+    }
+
+    /// Fills game metadata from `provider`, leaving locked fields untouched
+    pub async fn enrich_metadata(
+        &self,
+        pool: &PgPool,
+        provider: &dyn MetadataProvider,
+        console_id: Option<&str>,
+    ) -> Result<MetadataEnrichSummary, ApiError> {
+        let consoles = match console_id {
+            Some(id) => vec![self.get_console(pool, id).await?],
+            None => self.get_all_consoles(pool).await?,
+        };
+
+        let mut summary = MetadataEnrichSummary {
+            provider: provider.name().to_string(),
+            ..Default::default()
+        };
+
+        for console in consoles {
+            for game in self.get_console_games(pool, &console.id).await? {
+                summary.scanned += 1;
+
+                let crc32 = match self.ensure_rom_crc32(pool, &game).await {
+                    Ok(crc) => Some(crc),
+                    Err(e) => {
+                        log::warn!("No CRC32 for {}: {}", game.rom_filename, e);
+                        None
+                    }
+                };
+
+                let Some(metadata) = provider.lookup(pool, &game, crc32.as_deref()).await? else {
+                    continue;
+                };
+                summary.matched += 1;
+
+                if self.apply_metadata(pool, &game, &metadata, provider.name()).await? {
+                    summary.updated += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Returns the ROM's CRC32 as uppercase hex, computing and storing it on first use.
+    /// For zip archives this is the CRC of the first file inside, as RetroArch expects.
+    /// The stored value is recomputed when the file on disk no longer has the recorded size
+    /// or modification time.
+    pub async fn ensure_rom_crc32(&self, pool: &PgPool, game: &Game) -> Result<String, ApiError> {
+        let rom_path = PathBuf::from(self.get_rom_path(&game.console_id, &game.rom_filename));
+        let metadata = tokio::fs::metadata(&rom_path)
+            .await
+            .map_err(|e| ApiError::NotFound(format!("ROM file not readable: {}", e)))?;
+        let size = metadata.len() as i64;
+        // Postgres keeps microseconds, so compare at that precision
+        let modified = metadata
+            .modified()
+            .ok()
+            .map(|t| DateTime::<Utc>::from(t).trunc_subsecs(6));
+
+        if let Some(crc) = game
+            .rom_crc32
+            .as_ref()
+            .filter(|_| size == game.rom_size_bytes && modified == game.rom_modified_at)
+        {
+            return Ok(crc.clone());
+        }
+
+        let crc = tokio::task::spawn_blocking(move || rom_crc32(&rom_path))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?
+            .map_err(|e| ApiError::NotFound(format!("ROM file not readable: {}", e)))?;
+        let crc = format!("{:08X}", crc);
+
+        sqlx::query("UPDATE games SET rom_crc32 = $2, rom_size_bytes = $3, rom_modified_at = $4 WHERE id = $1")
+            .bind(game.id)
+            .bind(&crc)
+            .bind(size)
+            .bind(modified)
+            .execute(pool)
+            .await?;
+
+        Ok(crc)
+    }
+
     pub fn get_rom_path(&self, console_id: &str, filename: &str) -> String {
         Path::new(&self.rom_storage_path)
             .join(console_id)
@@ -165,3 +603,251 @@ impl GameService {
             .to_string()
     }
 }
+
+/// What the listing counts: games, or titles when they are grouped
+fn count_expression(query: &GameQuery) -> &'static str {
+    if query.group_titles.unwrap_or(false) {
+        "COUNT(DISTINCT COALESCE(g.title_id, g.id))"
+    } else {
+        "COUNT(*)"
+    }
+}
+
+/// Pushes the position of the game's region in the user's preferred regions, lower being
+/// better. Games from none of the regions come last.
+fn push_region_rank(builder: &mut QueryBuilder<'_, Postgres>, user_id: &Uuid) {
+    let defaults: Vec<String> = DEFAULT_PREFERRED_REGIONS.iter().map(|r| r.to_string()).collect();
+
+    builder
+        .push("COALESCE((SELECT MIN(p.ord) FROM UNNEST(COALESCE(")
+        .push("(SELECT NULLIF(s.preferred_regions, '{}') FROM user_settings s WHERE s.user_id = ")
+        .push_bind(*user_id)
+        .push("), ")
+        .push_bind(defaults)
+        .push(")) WITH ORDINALITY AS p(region, ord) WHERE g.region ILIKE '%' || p.region || '%'), 2147483647)");
+}
+
+/// Saves `game` with its changed fields, recording each change in the edit history
+async fn write_metadata(
+    tx: &mut Transaction<'_, Postgres>,
+    game: &Game,
+    genres: &[String],
+    changes: &[FieldChange],
+    user_id: Option<&Uuid>,
+    source: &str,
+) -> Result<Game, ApiError> {
+    let saved = sqlx::query_as::<_, Game>(
+        "UPDATE games
+         SET title = $2, description = $3, release_year = $4, developer = $5,
+             publisher = $6, players = $7, rating = $8,
+             region = $9, cover_url = $10, locked_fields = $11
+         WHERE id = $1
+         RETURNING *"
+    )
+    .bind(game.id)
+    .bind(&game.title)
+    .bind(&game.description)
+    .bind(game.release_year)
+    .bind(&game.developer)
+    .bind(&game.publisher)
+    .bind(&game.players)
+    .bind(game.rating)
+    .bind(&game.region)
+    .bind(&game.cover_url)
+    .bind(&game.locked_fields)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    for change in changes {
+        sqlx::query(
+            "INSERT INTO game_edits (game_id, user_id, source, field, old_value, new_value)
+             VALUES ($1, $2, $3, $4, $5, $6)"
+        )
+        .bind(game.id)
+        .bind(user_id)
+        .bind(source)
+        .bind(change.field)
+        .bind(&change.old_value)
+        .bind(&change.new_value)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    if changes.iter().any(|c| c.field == "genres") {
+        replace_genre_tags(tx, &game.id, genres, user_id).await?;
+    }
+
+    Ok(saved)
+}
+
+/// SQL condition on the `games g` row: whether the user `viewer` (a SQL expression) may see it.
+/// Admins and the uploader always can, others when the game is instance-wide or shared with
+/// them directly or through one of their groups.
+pub fn game_visible_to(viewer: &str) -> String {
+    format!(
+        "(g.visibility = 'instance'
+          OR g.uploaded_by = {viewer}
+          OR EXISTS (SELECT 1 FROM users u WHERE u.id = {viewer} AND u.is_admin)
+          OR (g.visibility = 'shared' AND (
+              EXISTS (SELECT 1 FROM game_user_shares s WHERE s.game_id = g.id AND s.user_id = {viewer})
+              OR EXISTS (SELECT 1 FROM game_group_shares s
+                         JOIN user_group_members m ON m.group_id = s.group_id
+                         WHERE s.game_id = g.id AND m.user_id = {viewer}))))",
+        viewer = viewer
+    )
+}
+
+/// Whether a ROM is worth compressing on the fly: small cartridge dumps compress well, while
+/// archives and compressed disc formats don't and large disc images cost too much CPU per request
+pub fn is_compressible_rom(filename: &str, size: u64) -> bool {
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    size <= MAX_COMPRESSIBLE_ROM_BYTES && !INCOMPRESSIBLE_ROM_EXTENSIONS.contains(&extension.as_str())
+}
+
+/// Normalises a title for fuzzy matching: drops `(...)`/`[...]` tags, case and punctuation.
+/// `Legend of Zelda, The - A Link to the Past (USA)` and `The Legend of Zelda: A Link to the Past`
+/// both become `legendofzeldaalinktothepast`.
+pub fn normalize_title(title: &str) -> String {
+    let words: Vec<String> = strip_title_tags(title)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w != "the")
+        .collect();
+
+    words.concat()
+}
+
+/// Removes `(...)`/`[...]` tags such as regions and revisions: `Sonic (USA, Europe)` becomes `Sonic`
+pub fn strip_title_tags(title: &str) -> String {
+    let mut stripped = String::with_capacity(title.len());
+    let mut depth = 0;
+    for c in title.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = (depth - 1).max(0),
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extracts the region tag from a No-Intro style filename, e.g. `Sonic (USA, Europe).md`.
+fn extract_region(filename: &str) -> Option<String> {
+    let start = filename.find('(')?;
+    let end = filename[start..].find(')')? + start;
+    let region = filename[start + 1..end].trim();
+
+    if region.is_empty() {
+        None
+    } else {
+        Some(region.to_string())
+    }
+}
+
+struct FieldChange {
+    field: &'static str,
+    old_value: JsonValue,
+    new_value: JsonValue,
+}
+
+/// Returns `game` with the provided metadata applied, its resulting genres,
+/// plus the list of fields that changed.
+fn merge_metadata(
+    game: &Game,
+    metadata: &GameMetadata,
+    locked: &[String],
+) -> (Game, Vec<String>, Vec<FieldChange>) {
+    let mut g = game.clone();
+    let mut genres = game.genres();
+    let mut changes = Vec::new();
+    let m = metadata.clone();
+
+    merge_field("title", &mut g.title, m.title, locked, &mut changes);
+    merge_field("description", &mut g.description, m.description.map(Some), locked, &mut changes);
+    merge_field("release_year", &mut g.release_year, m.release_year.map(Some), locked, &mut changes);
+    merge_field("developer", &mut g.developer, m.developer.map(Some), locked, &mut changes);
+    merge_field("publisher", &mut g.publisher, m.publisher.map(Some), locked, &mut changes);
+    // Genres are compared by slug, so reordering or recasing them is not a change
+    let incoming_genres = m
+        .genres
+        .map(normalize_tag_names)
+        .filter(|incoming| genre_slugs(incoming) != genre_slugs(&genres));
+    merge_field("genres", &mut genres, incoming_genres, locked, &mut changes);
+    merge_field("players", &mut g.players, m.players.map(Some), locked, &mut changes);
+    merge_field("rating", &mut g.rating, m.rating.map(Some), locked, &mut changes);
+    merge_field("region", &mut g.region, m.region.map(Some), locked, &mut changes);
+    merge_field("cover_url", &mut g.cover_url, m.cover_url.map(Some), locked, &mut changes);
+
+    (g, genres, changes)
+}
+
+fn genre_slugs(genres: &[String]) -> Vec<String> {
+    let mut slugs: Vec<String> = genres.iter().filter_map(|g| tag_slug(g).ok()).collect();
+    slugs.sort();
+    slugs
+}
+
+fn merge_field<T: PartialEq + Serialize>(
+    field: &'static str,
+    current: &mut T,
+    value: Option<T>,
+    locked: &[String],
+    changes: &mut Vec<FieldChange>,
+) {
+    let Some(value) = value else { return };
+
+    if *current == value || locked.iter().any(|f| f == field) {
+        return;
+    }
+
+    changes.push(FieldChange {
+        field,
+        old_value: serde_json::to_value(&*current).unwrap_or(JsonValue::Null),
+        new_value: serde_json::to_value(&value).unwrap_or(JsonValue::Null),
+    });
+    *current = value;
+}
+
+fn rom_crc32(path: &Path) -> io::Result<u32> {
+    let mut file = fs::File::open(path)?;
+
+    let is_zip = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("zip"))
+        .unwrap_or(false);
+
+    if is_zip {
+        // Local file header: signature, then the CRC at offset 14 unless a data descriptor follows
+        let mut header = [0u8; 30];
+        file.read_exact(&mut header)?;
+        let signature = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let has_descriptor = header[6] & 0x08 != 0;
+
+        if signature == 0x0403_4b50 && !has_descriptor {
+            return Ok(u32::from_le_bytes([header[14], header[15], header[16], header[17]]));
+        }
+
+        file = fs::File::open(path)?;
+    }
+
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize())
+}
@@ -0,0 +1,442 @@
+use crate::models::{Console, Game, GameMetadata, GamelistExportSummary, GamelistImportSummary};
+use crate::services::{split_genres, GameService, MediaService};
+use crate::utils::ApiError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GAMELIST_FILENAME: &str = "gamelist.xml";
+const EXPORT_IMAGE_DIR: &str = "images";
+
+/// Gamelist image tags and the media kind they map to, in order of preference for the cover
+const IMAGE_TAGS: &[(&str, &str)] = &[
+    ("boxart", "box_art"),
+    ("thumbnail", "box_art"),
+    ("image", "screenshot"),
+    ("titleshot", "title_screen"),
+    ("cartridge", "cartridge"),
+];
+
+pub struct RenderedGamelist {
+    pub xml: String,
+    pub games: usize,
+    /// Media files referenced by the gamelist, as (source file, path relative to the console folder)
+    pub files: Vec<(PathBuf, String)>,
+}
+
+/// Reads and writes EmulationStation/Batocera `gamelist.xml` files in the console ROM folders
+#[derive(Clone)]
+pub struct GamelistService {
+    rom_storage_path: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename = "gameList")]
+struct GameList {
+    #[serde(rename = "game", default)]
+    games: Vec<GamelistEntry>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct GamelistEntry {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    desc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    boxart: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    titleshot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cartridge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rating: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    releasedate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    developer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    genre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    players: Option<String>,
+}
+
+impl GamelistEntry {
+    fn image_path(&self, tag: &str) -> Option<&str> {
+        match tag {
+            "boxart" => self.boxart.as_deref(),
+            "thumbnail" => self.thumbnail.as_deref(),
+            "image" => self.image.as_deref(),
+            "titleshot" => self.titleshot.as_deref(),
+            "cartridge" => self.cartridge.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn set_image_path(&mut self, tag: &str, path: String) {
+        match tag {
+            "boxart" => self.boxart = Some(path),
+            "thumbnail" => self.thumbnail = Some(path),
+            "image" => self.image = Some(path),
+            "titleshot" => self.titleshot = Some(path),
+            "cartridge" => self.cartridge = Some(path),
+            _ => {}
+        }
+    }
+
+    fn metadata(&self) -> GameMetadata {
+        GameMetadata {
+            title: non_empty(&self.name),
+            description: non_empty(&self.desc),
+            // Release dates are stored as e.g. 19910623T000000
+            release_year: self
+                .releasedate
+                .as_deref()
+                .and_then(|d| d.get(..4))
+                .and_then(|y| y.parse().ok()),
+            developer: non_empty(&self.developer),
+            publisher: non_empty(&self.publisher),
+            genres: non_empty(&self.genre).map(|g| split_genres(&g)),
+            players: non_empty(&self.players),
+            rating: self
+                .rating
+                .as_deref()
+                .and_then(|r| r.trim().parse::<f32>().ok())
+                .filter(|r| (0.0..=1.0).contains(r)),
+            ..Default::default()
+        }
+    }
+}
+
+impl GamelistService {
+    pub fn new(rom_storage_path: String) -> Self {
+        Self { rom_storage_path }
+    }
+
+    fn console_path(&self, console_id: &str) -> PathBuf {
+        Path::new(&self.rom_storage_path).join(console_id)
+    }
+
+    /// Imports the console's gamelist.xml onto matching games.
+    /// Returns `None` when the console folder has no gamelist.
+    pub async fn import_console(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game_service: &GameService,
+        media_service: &MediaService,
+    ) -> Result<Option<GamelistImportSummary>, ApiError> {
+        let console_path = self.console_path(&console.id);
+        let gamelist_path = console_path.join(GAMELIST_FILENAME);
+
+        if !gamelist_path.exists() {
+            return Ok(None);
+        }
+
+        let xml = fs::read_to_string(&gamelist_path)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to read gamelist: {}", e)))?;
+        let gamelist: GameList = quick_xml::de::from_str(&xml)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid gamelist for {}: {}", console.id, e)))?;
+
+        // Gamelist image paths are only trusted inside the console folder
+        let console_root = console_path
+            .canonicalize()
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to read console folder: {}", e)))?;
+
+        let games = game_service.get_console_games(pool, &console.id).await?;
+        let mut summary = GamelistImportSummary {
+            console_id: console.id.clone(),
+            ..Default::default()
+        };
+
+        for entry in &gamelist.games {
+            let Some(game) = find_game(&games, &entry.path) else {
+                summary.unmatched.push(entry.path.clone());
+                continue;
+            };
+            summary.matched += 1;
+
+            let mut metadata = entry.metadata();
+            let mut existing_media = media_service.get_game_media(pool, &game.id).await?;
+
+            for (tag, kind) in IMAGE_TAGS {
+                let Some(image_path) = entry.image_path(tag) else {
+                    continue;
+                };
+
+                let Some(file_path) = resolve_image_path(&console_root, image_path) else {
+                    log::warn!("Skipping gamelist image {:?}: outside of the console folder", image_path);
+                    continue;
+                };
+                let data = match fs::read(&file_path) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        log::warn!("Skipping gamelist image {:?}: {}", file_path, e);
+                        continue;
+                    }
+                };
+
+                // Every scan re-reads the gamelist, only decode images that are new for the game
+                let content_hash = hex::encode(Sha256::digest(&data));
+                if let Some(media) = existing_media
+                    .iter()
+                    .find(|m| m.kind == *kind && m.content_hash == content_hash)
+                {
+                    if *kind == "box_art" && metadata.cover_url.is_none() {
+                        metadata.cover_url = Some(media_service.cover_url(&media.content_hash));
+                    }
+                    continue;
+                }
+
+                match media_service
+                    .create_media(pool, &game.id, kind, data, None)
+                    .await
+                {
+                    Ok(media) => {
+                        summary.media_imported += 1;
+                        if *kind == "box_art" && metadata.cover_url.is_none() {
+                            metadata.cover_url = Some(media_service.cover_url(&media.content_hash));
+                        }
+                        existing_media.push(media);
+                    }
+                    Err(e) => log::warn!("Skipping gamelist image {:?}: {}", file_path, e),
+                }
+            }
+
+            if game_service
+                .apply_metadata(pool, game, &metadata, "gamelist")
+                .await?
+            {
+                summary.updated += 1;
+            }
+        }
+
+        log::info!(
+            "Imported gamelist for {}: {} matched, {} updated, {} unmatched",
+            console.id,
+            summary.matched,
+            summary.updated,
+            summary.unmatched.len()
+        );
+
+        Ok(Some(summary))
+    }
+
+    /// Renders the console's games as gamelist.xml, along with the media files it references
+    pub async fn render_gamelist(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game_service: &GameService,
+        media_service: &MediaService,
+    ) -> Result<RenderedGamelist, ApiError> {
+        let games = game_service.get_console_games(pool, &console.id).await?;
+        let mut gamelist = GameList::default();
+        let mut files = Vec::new();
+
+        for game in &games {
+            let mut entry = GamelistEntry {
+                path: format!("./{}", game.rom_filename),
+                name: Some(game.title.clone()),
+                desc: game.description.clone(),
+                rating: game.rating.map(|r| format!("{:.2}", r)),
+                releasedate: game.release_year.map(|y| format!("{:04}0101T000000", y)),
+                developer: game.developer.clone(),
+                publisher: game.publisher.clone(),
+                genre: Some(game.genres().join(", ")).filter(|g| !g.is_empty()),
+                players: game.players.clone(),
+                ..Default::default()
+            };
+
+            let stem = Path::new(&game.rom_filename)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&game.rom_filename)
+                .to_string();
+
+            let media = media_service.get_game_media(pool, &game.id).await?;
+            for (tag, kind) in [
+                ("thumbnail", "box_art"),
+                ("image", "screenshot"),
+                ("titleshot", "title_screen"),
+                ("cartridge", "cartridge"),
+            ] {
+                let Some(m) = media.iter().find(|m| m.kind == kind) else {
+                    continue;
+                };
+
+                let relative = format!("{}/{}-{}.{}", EXPORT_IMAGE_DIR, stem, tag, m.file_extension);
+                entry.set_image_path(tag, format!("./{}", relative));
+                files.push((media_service.get_media_path(m), relative));
+            }
+
+            gamelist.games.push(entry);
+        }
+
+        let mut body = String::new();
+        let mut serializer = quick_xml::se::Serializer::new(&mut body);
+        serializer.indent(' ', 2);
+        gamelist
+            .serialize(serializer)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to render gamelist: {}", e)))?;
+
+        Ok(RenderedGamelist {
+            xml: format!("<?xml version=\"1.0\"?>\n{}\n", body),
+            games: games.len(),
+            files,
+        })
+    }
+
+    /// Writes gamelist.xml and its images into the console ROM folder.
+    /// An existing gamelist is kept once as gamelist.xml.bak.
+    pub async fn export_console(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game_service: &GameService,
+        media_service: &MediaService,
+    ) -> Result<GamelistExportSummary, ApiError> {
+        let console_path = self.console_path(&console.id);
+        if !console_path.exists() {
+            return Err(ApiError::NotFound(format!(
+                "Console directory does not exist: {}",
+                console.id
+            )));
+        }
+
+        let rendered = self
+            .render_gamelist(pool, console, game_service, media_service)
+            .await?;
+
+        let gamelist_path = console_path.join(GAMELIST_FILENAME);
+        let backup_path = console_path.join(format!("{}.bak", GAMELIST_FILENAME));
+        if gamelist_path.exists() && !backup_path.exists() {
+            fs::copy(&gamelist_path, &backup_path)
+                .map_err(|e| ApiError::InternalServerError(format!("Failed to back up gamelist: {}", e)))?;
+        }
+
+        let mut media_exported = 0;
+        if !rendered.files.is_empty() {
+            fs::create_dir_all(console_path.join(EXPORT_IMAGE_DIR))
+                .map_err(|e| ApiError::InternalServerError(format!("Failed to create image directory: {}", e)))?;
+        }
+        for (source, relative) in &rendered.files {
+            match fs::copy(source, console_path.join(relative)) {
+                Ok(_) => media_exported += 1,
+                Err(e) => log::warn!("Skipping gamelist image {:?}: {}", source, e),
+            }
+        }
+
+        fs::write(&gamelist_path, rendered.xml)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to write gamelist: {}", e)))?;
+
+        Ok(GamelistExportSummary {
+            console_id: console.id.clone(),
+            path: gamelist_path.to_string_lossy().to_string(),
+            games: rendered.games,
+            media_exported,
+        })
+    }
+}
+
+/// Resolves a gamelist image path against the console folder, `None` when it points
+/// anywhere else (absolute paths, `../`, symlinks out of the folder)
+fn resolve_image_path(console_root: &Path, image_path: &str) -> Option<PathBuf> {
+    let relative = Path::new(image_path.trim_start_matches("./"));
+    if relative.is_absolute() {
+        return None;
+    }
+
+    let resolved = console_root.join(relative).canonicalize().ok()?;
+    resolved.starts_with(console_root).then_some(resolved)
+}
+
+/// Matches a gamelist `<path>` such as `./Sonic (USA).md` to a scanned game
+fn find_game<'a>(games: &'a [Game], path: &str) -> Option<&'a Game> {
+    let filename = Path::new(path).file_name().and_then(|f| f.to_str())?;
+    games.iter().find(|g| g.rom_filename == filename)
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(xml: &str) -> GameList {
+        quick_xml::de::from_str(xml).unwrap()
+    }
+
+    #[test]
+    fn reads_scraped_metadata() {
+        let list = parse(
+            r#"<?xml version="1.0"?>
+<gameList>
+  <game>
+    <path>./Sonic The Hedgehog (USA, Europe).md</path>
+    <name>Sonic The Hedgehog</name>
+    <desc>Sonic races through Green Hill Zone.</desc>
+    <image>./images/Sonic The Hedgehog (USA, Europe)-image.png</image>
+    <rating>0.8</rating>
+    <releasedate>19910623T000000</releasedate>
+    <developer>Sonic Team</developer>
+    <publisher>Sega</publisher>
+    <players>1</players>
+  </game>
+</gameList>"#,
+        );
+
+        let entry = &list.games[0];
+        assert_eq!(entry.path, "./Sonic The Hedgehog (USA, Europe).md");
+        assert_eq!(
+            entry.image_path("image"),
+            Some("./images/Sonic The Hedgehog (USA, Europe)-image.png")
+        );
+
+        let metadata = entry.metadata();
+        assert_eq!(metadata.title.as_deref(), Some("Sonic The Hedgehog"));
+        assert_eq!(metadata.description.as_deref(), Some("Sonic races through Green Hill Zone."));
+        assert_eq!(metadata.release_year, Some(1991));
+        assert_eq!(metadata.developer.as_deref(), Some("Sonic Team"));
+        assert_eq!(metadata.publisher.as_deref(), Some("Sega"));
+        assert_eq!(metadata.players.as_deref(), Some("1"));
+        assert_eq!(metadata.rating, Some(0.8));
+    }
+
+    #[test]
+    fn ignores_blank_fields_and_bad_values() {
+        let list = parse(
+            r#"<gameList>
+  <game><path>./a.md</path><name>  </name><releasedate>unknown</releasedate><rating>4</rating></game>
+  <game><path>./b.md</path><releasedate>1994</releasedate><rating>1</rating></game>
+  <game><path>./c.md</path><rating>-0.5</rating></game>
+  <game><path>./d.md</path><rating>n/a</rating></game>
+</gameList>"#,
+        );
+
+        let metadata: Vec<GameMetadata> = list.games.iter().map(GamelistEntry::metadata).collect();
+        assert_eq!(metadata[0].title, None);
+        assert_eq!(metadata[0].release_year, None);
+        // Ratings are fractions, a 0-5 star value is not a gamelist rating
+        assert_eq!(metadata[0].rating, None);
+        assert_eq!(metadata[1].release_year, Some(1994));
+        assert_eq!(metadata[1].rating, Some(1.0));
+        assert_eq!(metadata[2].rating, None);
+        assert_eq!(metadata[3].rating, None);
+    }
+}
@@ -0,0 +1,769 @@
+use crate::models::{
+    Console, ControllerImportSummary, ControllerLayout, ControllerMapping, ControllerQuery, CreateInputProfileRequest,
+    Game, GameInputConfig, GameInputOverride, InputBindings, InputProfile, LayoutButton, UpdateGameInputRequest,
+    UpdateInputProfileRequest,
+};
+use crate::utils::ApiError;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use uuid::Uuid;
+
+/// Rows per statement when storing an imported controller database
+const CONTROLLER_IMPORT_BATCH: usize = 500;
+
+/// SDL game controller elements usable as buttons
+const SDL_BUTTONS: [&str; 23] = [
+    "a", "b", "x", "y", "back", "guide", "start", "leftstick", "rightstick", "leftshoulder", "rightshoulder",
+    "dpup", "dpdown", "dpleft", "dpright", "misc1", "paddle1", "paddle2", "paddle3", "paddle4", "touchpad",
+    "lefttrigger", "righttrigger",
+];
+
+/// SDL game controller axes, bound per direction as `+leftx` / `-leftx`
+const SDL_AXES: [&str; 6] = ["leftx", "lefty", "rightx", "righty", "lefttrigger", "righttrigger"];
+
+/// `KeyboardEvent.code` values besides the `KeyX`, `DigitN`, `NumpadN` and `FN` families
+const KEY_CODES: [&str; 47] = [
+    "ArrowUp", "ArrowDown", "ArrowLeft", "ArrowRight", "Enter", "Space", "Tab", "Backspace", "Escape",
+    "ShiftLeft", "ShiftRight", "ControlLeft", "ControlRight", "AltLeft", "AltRight", "MetaLeft", "MetaRight",
+    "CapsLock", "Minus", "Equal", "BracketLeft", "BracketRight", "Backslash", "Semicolon", "Quote", "Backquote",
+    "Comma", "Period", "Slash", "IntlBackslash", "ContextMenu", "Insert", "Delete", "Home", "End", "PageUp",
+    "PageDown", "NumpadAdd", "NumpadSubtract", "NumpadMultiply", "NumpadDivide", "NumpadDecimal", "NumpadEnter",
+    "NumpadEqual", "NumLock", "ScrollLock", "Pause",
+];
+
+const fn button(id: &'static str, label: &'static str, retropad: u8) -> LayoutButton {
+    LayoutButton { id, label, retropad }
+}
+
+const DPAD: [LayoutButton; 4] = [
+    button("up", "Up", 4),
+    button("down", "Down", 5),
+    button("left", "Left", 6),
+    button("right", "Right", 7),
+];
+
+const LEFT_STICK: [LayoutButton; 4] = [
+    button("lstick_up", "Left Stick Up", 19),
+    button("lstick_down", "Left Stick Down", 18),
+    button("lstick_left", "Left Stick Left", 17),
+    button("lstick_right", "Left Stick Right", 16),
+];
+
+const RIGHT_STICK: [LayoutButton; 4] = [
+    button("rstick_up", "Right Stick Up", 23),
+    button("rstick_down", "Right Stick Down", 22),
+    button("rstick_left", "Right Stick Left", 21),
+    button("rstick_right", "Right Stick Right", 20),
+];
+
+const NES_BUTTONS: [LayoutButton; 8] = [
+    DPAD[0], DPAD[1], DPAD[2], DPAD[3],
+    button("a", "A", 8),
+    button("b", "B", 0),
+    button("select", "Select", 2),
+    button("start", "Start", 3),
+];
+
+const GBA_BUTTONS: [LayoutButton; 10] = [
+    DPAD[0], DPAD[1], DPAD[2], DPAD[3],
+    button("a", "A", 8),
+    button("b", "B", 0),
+    button("l", "L", 10),
+    button("r", "R", 11),
+    button("select", "Select", 2),
+    button("start", "Start", 3),
+];
+
+const SNES_BUTTONS: [LayoutButton; 12] = [
+    DPAD[0], DPAD[1], DPAD[2], DPAD[3],
+    button("a", "A", 8),
+    button("b", "B", 0),
+    button("x", "X", 9),
+    button("y", "Y", 1),
+    button("l", "L", 10),
+    button("r", "R", 11),
+    button("select", "Select", 2),
+    button("start", "Start", 3),
+];
+
+/// Six-button pad as Genesis Plus GX and PicoDrive read it from the RetroPad
+const GENESIS_BUTTONS: [LayoutButton; 12] = [
+    DPAD[0], DPAD[1], DPAD[2], DPAD[3],
+    button("a", "A", 1),
+    button("b", "B", 0),
+    button("c", "C", 8),
+    button("x", "X", 10),
+    button("y", "Y", 9),
+    button("z", "Z", 11),
+    button("mode", "Mode", 2),
+    button("start", "Start", 3),
+];
+
+/// C buttons sit on the right stick, as in Mupen64Plus-Next's default mapping
+const N64_BUTTONS: [LayoutButton; 18] = [
+    DPAD[0], DPAD[1], DPAD[2], DPAD[3],
+    LEFT_STICK[0], LEFT_STICK[1], LEFT_STICK[2], LEFT_STICK[3],
+    button("a", "A", 0),
+    button("b", "B", 1),
+    button("z", "Z", 12),
+    button("l", "L", 10),
+    button("r", "R", 11),
+    button("start", "Start", 3),
+    button("c_up", "C Up", 23),
+    button("c_down", "C Down", 22),
+    button("c_left", "C Left", 21),
+    button("c_right", "C Right", 20),
+];
+
+const PSX_BUTTONS: [LayoutButton; 22] = [
+    DPAD[0], DPAD[1], DPAD[2], DPAD[3],
+    LEFT_STICK[0], LEFT_STICK[1], LEFT_STICK[2], LEFT_STICK[3],
+    RIGHT_STICK[0], RIGHT_STICK[1], RIGHT_STICK[2], RIGHT_STICK[3],
+    button("cross", "Cross", 0),
+    button("circle", "Circle", 8),
+    button("square", "Square", 1),
+    button("triangle", "Triangle", 9),
+    button("l1", "L1", 10),
+    button("r1", "R1", 11),
+    button("l2", "L2", 12),
+    button("r2", "R2", 13),
+    button("select", "Select", 2),
+    button("start", "Start", 3),
+];
+
+/// The full libretro RetroPad, for consoles without a layout of their own
+const RETROPAD_BUTTONS: [LayoutButton; 24] = [
+    DPAD[0], DPAD[1], DPAD[2], DPAD[3],
+    LEFT_STICK[0], LEFT_STICK[1], LEFT_STICK[2], LEFT_STICK[3],
+    RIGHT_STICK[0], RIGHT_STICK[1], RIGHT_STICK[2], RIGHT_STICK[3],
+    button("a", "A", 8),
+    button("b", "B", 0),
+    button("x", "X", 9),
+    button("y", "Y", 1),
+    button("l", "L", 10),
+    button("r", "R", 11),
+    button("l2", "L2", 12),
+    button("r2", "R2", 13),
+    button("l3", "L3", 14),
+    button("r3", "R3", 15),
+    button("select", "Select", 2),
+    button("start", "Start", 3),
+];
+
+pub static CONTROLLER_LAYOUTS: [ControllerLayout; 8] = [
+    ControllerLayout { id: "nes", name: "NES", buttons: &NES_BUTTONS },
+    ControllerLayout { id: "snes", name: "Super NES", buttons: &SNES_BUTTONS },
+    ControllerLayout { id: "gb", name: "Game Boy", buttons: &NES_BUTTONS },
+    ControllerLayout { id: "gba", name: "Game Boy Advance", buttons: &GBA_BUTTONS },
+    ControllerLayout { id: "genesis", name: "Mega Drive / Genesis", buttons: &GENESIS_BUTTONS },
+    ControllerLayout { id: "n64", name: "Nintendo 64", buttons: &N64_BUTTONS },
+    ControllerLayout { id: "psx", name: "PlayStation", buttons: &PSX_BUTTONS },
+    ControllerLayout { id: "retropad", name: "RetroPad", buttons: &RETROPAD_BUTTONS },
+];
+
+pub fn find_layout(layout_id: &str) -> Result<&'static ControllerLayout, ApiError> {
+    CONTROLLER_LAYOUTS
+        .iter()
+        .find(|layout| layout.id == layout_id)
+        .ok_or_else(|| {
+            ApiError::ValidationError(format!(
+                "Unknown controller layout '{}', expected one of: {}",
+                layout_id,
+                CONTROLLER_LAYOUTS.iter().map(|l| l.id).collect::<Vec<_>>().join(", ")
+            ))
+        })
+}
+
+/// The controller of the console's emulator core
+pub fn console_layout(console: &Console) -> &'static ControllerLayout {
+    let id = match console.emulator_core.to_ascii_lowercase().as_str() {
+        "nes" | "fceumm" | "nestopia" | "mesen" => "nes",
+        "snes" | "snes9x" | "bsnes" => "snes",
+        "gb" | "gbc" | "gambatte" | "sameboy" => "gb",
+        "gba" | "mgba" | "vba_next" => "gba",
+        "segamd" | "megadrive" | "genesis_plus_gx" | "picodrive" => "genesis",
+        "n64" | "mupen64plus_next" | "parallel_n64" => "n64",
+        "psx" | "pcsx_rearmed" | "mednafen_psx_hw" | "ps2" | "pcsx2" => "psx",
+        _ => "retropad",
+    };
+    find_layout(id).unwrap_or(&CONTROLLER_LAYOUTS[CONTROLLER_LAYOUTS.len() - 1])
+}
+
+/// Named button mappings per controller layout, per-game overrides and the SDL controller database
+#[derive(Clone, Default)]
+pub struct InputService;
+
+impl InputService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn get_profiles(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        layout: Option<&str>,
+    ) -> Result<Vec<InputProfile>, ApiError> {
+        let profiles = sqlx::query_as::<_, InputProfile>(
+            "SELECT * FROM input_profiles
+             WHERE user_id = $1 AND ($2::text IS NULL OR layout = $2)
+             ORDER BY layout, NOT is_default, name"
+        )
+        .bind(user_id)
+        .bind(layout)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(profiles)
+    }
+
+    pub async fn get_profile(&self, pool: &PgPool, user_id: &Uuid, profile_id: &Uuid) -> Result<InputProfile, ApiError> {
+        let profile = sqlx::query_as::<_, InputProfile>(
+            "SELECT * FROM input_profiles WHERE id = $1 AND user_id = $2"
+        )
+        .bind(profile_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(profile)
+    }
+
+    /// Creates a profile. The first profile of a layout becomes its default.
+    pub async fn create_profile(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        request: &CreateInputProfileRequest,
+    ) -> Result<InputProfile, ApiError> {
+        let layout = find_layout(&request.layout)?;
+        check_bindings(layout, &request.bindings)?;
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(ApiError::ValidationError("Profile name must not be empty".to_string()));
+        }
+        self.ensure_unique_name(pool, user_id, layout.id, name, None).await?;
+
+        let mut tx = pool.begin().await?;
+        let has_default: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM input_profiles WHERE user_id = $1 AND layout = $2 AND is_default)"
+        )
+        .bind(user_id)
+        .bind(layout.id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let is_default = request.is_default || !has_default;
+        if is_default {
+            clear_default(&mut tx, user_id, layout.id).await?;
+        }
+
+        let profile = sqlx::query_as::<_, InputProfile>(
+            "INSERT INTO input_profiles (user_id, name, layout, bindings, is_default)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(layout.id)
+        .bind(Json(&request.bindings))
+        .bind(is_default)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(profile)
+    }
+
+    pub async fn update_profile(
+        &self,
+        pool: &PgPool,
+        profile: &InputProfile,
+        request: &UpdateInputProfileRequest,
+    ) -> Result<InputProfile, ApiError> {
+        let layout = find_layout(&profile.layout)?;
+        if let Some(bindings) = &request.bindings {
+            check_bindings(layout, bindings)?;
+        }
+        let name = request.name.as_deref().map(str::trim);
+        if let Some(name) = name {
+            if name.is_empty() {
+                return Err(ApiError::ValidationError("Profile name must not be empty".to_string()));
+            }
+            self.ensure_unique_name(pool, &profile.user_id, layout.id, name, Some(&profile.id)).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+        let make_default = request.is_default == Some(true) && !profile.is_default;
+        if make_default {
+            clear_default(&mut tx, &profile.user_id, layout.id).await?;
+        }
+
+        let updated = sqlx::query_as::<_, InputProfile>(
+            "UPDATE input_profiles SET
+                name = COALESCE($2, name),
+                bindings = COALESCE($3, bindings),
+                is_default = is_default OR $4,
+                updated_at = NOW()
+             WHERE id = $1
+             RETURNING *"
+        )
+        .bind(profile.id)
+        .bind(name)
+        .bind(request.bindings.as_ref().map(Json))
+        .bind(make_default)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    /// Deletes the profile; games that used it fall back to the layout's default
+    pub async fn delete_profile(&self, pool: &PgPool, profile: &InputProfile) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM input_profiles WHERE id = $1")
+            .bind(profile.id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_game_override(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        game_id: &Uuid,
+    ) -> Result<Option<GameInputOverride>, ApiError> {
+        let input_override = sqlx::query_as::<_, GameInputOverride>(
+            "SELECT * FROM game_input_overrides WHERE user_id = $1 AND game_id = $2"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(input_override)
+    }
+
+    /// Stores the user's profile choice and binding changes for a game. The profile must be one
+    /// of theirs for the game's layout, and the bindings may only name that layout's buttons.
+    pub async fn set_game_override(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game: &Game,
+        user_id: &Uuid,
+        request: &UpdateGameInputRequest,
+    ) -> Result<GameInputOverride, ApiError> {
+        let layout = console_layout(console);
+        check_bindings(layout, &request.bindings)?;
+        if let Some(profile_id) = &request.profile_id {
+            let profile = self.get_profile(pool, user_id, profile_id).await?;
+            if profile.layout != layout.id {
+                return Err(ApiError::ValidationError(format!(
+                    "Profile '{}' is for the {} layout, but {} games use the {} layout",
+                    profile.name, profile.layout, console.name, layout.id
+                )));
+            }
+        }
+
+        let input_override = sqlx::query_as::<_, GameInputOverride>(
+            "INSERT INTO game_input_overrides (user_id, game_id, profile_id, bindings)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id, game_id) DO UPDATE SET
+                profile_id = EXCLUDED.profile_id,
+                bindings = EXCLUDED.bindings,
+                updated_at = NOW()
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(game.id)
+        .bind(request.profile_id)
+        .bind(Json(&request.bindings))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(input_override)
+    }
+
+    pub async fn delete_game_override(&self, pool: &PgPool, user_id: &Uuid, game_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM game_input_overrides WHERE user_id = $1 AND game_id = $2")
+            .bind(user_id)
+            .bind(game_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The bindings the user plays the game with: the override's profile or the layout's
+    /// default, with the override's bindings replacing the profile's key or pad per button
+    pub async fn resolve_game_input(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game: &Game,
+        user_id: &Uuid,
+    ) -> Result<GameInputConfig, ApiError> {
+        let layout = console_layout(console);
+        let overrides = self.get_game_override(pool, user_id, &game.id).await?;
+
+        let profile = sqlx::query_as::<_, InputProfile>(
+            "SELECT * FROM input_profiles
+             WHERE user_id = $1 AND layout = $2 AND (id = $3 OR ($3::uuid IS NULL AND is_default))"
+        )
+        .bind(user_id)
+        .bind(layout.id)
+        .bind(overrides.as_ref().and_then(|o| o.profile_id))
+        .fetch_optional(pool)
+        .await?;
+
+        let mut bindings = profile.as_ref().map(|p| p.bindings.0.clone()).unwrap_or_default();
+        if let Some(overrides) = &overrides {
+            for (button, binding) in overrides.bindings.iter() {
+                let entry = bindings.entry(button.clone()).or_default();
+                if binding.keyboard.is_some() {
+                    entry.keyboard = binding.keyboard.clone();
+                }
+                if binding.gamepad.is_some() {
+                    entry.gamepad = binding.gamepad.clone();
+                }
+            }
+        }
+
+        Ok(GameInputConfig {
+            game_id: game.id,
+            layout,
+            profile_id: profile.map(|p| p.id),
+            bindings,
+            overrides,
+        })
+    }
+
+    pub async fn find_controllers(
+        &self,
+        pool: &PgPool,
+        query: &ControllerQuery,
+    ) -> Result<Vec<ControllerMapping>, ApiError> {
+        let platform = query.platform.as_deref().unwrap_or_default();
+        let controllers = match (&query.guid, &query.vendor, &query.product) {
+            (Some(guid), _, _) => {
+                sqlx::query_as::<_, ControllerMapping>(
+                    "SELECT * FROM controller_mappings WHERE guid = $1 ORDER BY platform <> $2, platform"
+                )
+                .bind(guid.trim().to_ascii_lowercase())
+                .bind(platform)
+                .fetch_all(pool)
+                .await?
+            }
+            (None, Some(vendor), Some(product)) => {
+                sqlx::query_as::<_, ControllerMapping>(
+                    "SELECT * FROM controller_mappings WHERE vendor_id = $1 AND product_id = $2
+                     ORDER BY platform <> $3, platform, name"
+                )
+                .bind(vendor.trim().to_ascii_lowercase())
+                .bind(product.trim().to_ascii_lowercase())
+                .bind(platform)
+                .fetch_all(pool)
+                .await?
+            }
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "Give either a guid, or a vendor and a product id".to_string(),
+                ))
+            }
+        };
+
+        Ok(controllers)
+    }
+
+    /// Imports SDL `gamecontrollerdb.txt` mappings, replacing entries with the same GUID and
+    /// platform. Unparseable lines are reported and skipped.
+    pub async fn import_controller_db(&self, pool: &PgPool, contents: &str) -> Result<ControllerImportSummary, ApiError> {
+        let mut summary = ControllerImportSummary::default();
+        // Later lines win, as they do when SDL loads the file
+        let mut entries: BTreeMap<(String, String), ParsedController> = BTreeMap::new();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match parse_controller_line(line) {
+                Ok(entry) => {
+                    entries.insert((entry.guid.clone(), entry.platform.clone()), entry);
+                }
+                Err(reason) => summary.invalid.push(format!("line {}: {}", index + 1, reason)),
+            }
+        }
+
+        let entries: Vec<ParsedController> = entries.into_values().collect();
+        let mut tx = pool.begin().await?;
+        for batch in entries.chunks(CONTROLLER_IMPORT_BATCH) {
+            let mut builder = QueryBuilder::<Postgres>::new(
+                "INSERT INTO controller_mappings (guid, platform, name, mapping, vendor_id, product_id) ",
+            );
+            builder.push_values(batch, |mut row, entry| {
+                row.push_bind(&entry.guid)
+                    .push_bind(&entry.platform)
+                    .push_bind(&entry.name)
+                    .push_bind(Json(&entry.mapping))
+                    .push_bind(&entry.vendor_id)
+                    .push_bind(&entry.product_id);
+            });
+            builder.push(
+                " ON CONFLICT (guid, platform) DO UPDATE SET
+                    name = EXCLUDED.name,
+                    mapping = EXCLUDED.mapping,
+                    vendor_id = EXCLUDED.vendor_id,
+                    product_id = EXCLUDED.product_id,
+                    updated_at = NOW()",
+            );
+            builder.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        summary.imported = entries.len();
+        Ok(summary)
+    }
+
+    async fn ensure_unique_name(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        layout: &str,
+        name: &str,
+        except: Option<&Uuid>,
+    ) -> Result<(), ApiError> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(
+                SELECT 1 FROM input_profiles
+                WHERE user_id = $1 AND layout = $2 AND name = $3 AND ($4::uuid IS NULL OR id <> $4)
+             )"
+        )
+        .bind(user_id)
+        .bind(layout)
+        .bind(name)
+        .bind(except)
+        .fetch_one(pool)
+        .await?;
+
+        if exists {
+            return Err(ApiError::BadRequest(format!("You already have a {} profile named '{}'", layout, name)));
+        }
+        Ok(())
+    }
+}
+
+async fn clear_default(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: &Uuid,
+    layout: &str,
+) -> Result<(), ApiError> {
+    sqlx::query("UPDATE input_profiles SET is_default = false WHERE user_id = $1 AND layout = $2 AND is_default")
+        .bind(user_id)
+        .bind(layout)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Checks that bindings only name buttons the layout has, with known keys and pad inputs
+fn check_bindings(layout: &ControllerLayout, bindings: &InputBindings) -> Result<(), ApiError> {
+    for (button, binding) in bindings {
+        if !layout.buttons.iter().any(|b| b.id == button) {
+            return Err(ApiError::ValidationError(format!(
+                "The {} controller has no '{}' button, expected one of: {}",
+                layout.name,
+                button,
+                layout.buttons.iter().map(|b| b.id).collect::<Vec<_>>().join(", ")
+            )));
+        }
+        if let Some(key) = binding.keyboard.as_deref().filter(|key| !is_key_code(key)) {
+            return Err(ApiError::ValidationError(format!(
+                "'{}' for {} is not a KeyboardEvent.code such as KeyZ or ArrowUp",
+                key, button
+            )));
+        }
+        if let Some(input) = binding.gamepad.as_deref().filter(|input| !is_gamepad_input(input)) {
+            return Err(ApiError::ValidationError(format!(
+                "'{}' for {} is not an SDL controller button or axis such as a, dpup or +leftx",
+                input, button
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn is_key_code(code: &str) -> bool {
+    let single = |suffix: Option<&str>, range: RangeInclusive<char>| {
+        suffix.is_some_and(|s| s.len() == 1 && s.chars().all(|c| range.contains(&c)))
+    };
+    let function_key = code
+        .strip_prefix('F')
+        .filter(|n| n.chars().all(|c| c.is_ascii_digit()) && !n.starts_with('0'))
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=24).contains(&n));
+
+    KEY_CODES.contains(&code)
+        || single(code.strip_prefix("Key"), 'A'..='Z')
+        || single(code.strip_prefix("Digit"), '0'..='9')
+        || single(code.strip_prefix("Numpad"), '0'..='9')
+        || function_key
+}
+
+fn is_gamepad_input(input: &str) -> bool {
+    match input.strip_prefix(['+', '-']) {
+        Some(axis) => SDL_AXES.contains(&axis),
+        None => SDL_BUTTONS.contains(&input),
+    }
+}
+
+struct ParsedController {
+    guid: String,
+    platform: String,
+    name: String,
+    mapping: BTreeMap<String, String>,
+    vendor_id: Option<String>,
+    product_id: Option<String>,
+}
+
+/// Parses one `GUID,name,element:input,...,platform:X,` line. Fields SDL adds for its own
+/// use (`crc`, `hint`, `sdk>=` and the like) are dropped.
+fn parse_controller_line(line: &str) -> Result<ParsedController, String> {
+    let mut fields = line.split(',');
+    let guid = fields.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let name = fields.next().unwrap_or_default().trim();
+    let is_hex_guid = guid.len() == 32 && guid.chars().all(|c| c.is_ascii_hexdigit());
+    if !is_hex_guid && guid != "xinput" {
+        return Err(format!("invalid GUID '{}'", guid));
+    }
+    if name.is_empty() || name.len() > 255 {
+        return Err("missing or overlong controller name".to_string());
+    }
+
+    let mut platform = String::new();
+    let mut mapping = BTreeMap::new();
+    for field in fields.map(str::trim).filter(|f| !f.is_empty()) {
+        let Some((element, input)) = field.split_once(':') else {
+            return Err(format!("'{}' is not an element:input pair", field));
+        };
+        if element == "platform" {
+            platform = input.chars().take(50).collect();
+            continue;
+        }
+        let is_element = SDL_BUTTONS.contains(&element) || SDL_AXES.contains(&element.trim_start_matches(['+', '-']));
+        if !is_element {
+            continue;
+        }
+        if !is_physical_input(input) {
+            return Err(format!("'{}' is not a button, axis or hat for {}", input, element));
+        }
+        mapping.insert(element.to_string(), input.to_string());
+    }
+    if mapping.is_empty() {
+        return Err("no mapped elements".to_string());
+    }
+
+    // SDL 2 GUIDs carry the USB vendor and product as little-endian words at bytes 4 and 8
+    let usb_id = |start: usize| {
+        let word = &guid[start..start + 4];
+        let id = format!("{}{}", &word[2..4], &word[0..2]);
+        (id != "0000").then_some(id)
+    };
+    let (vendor_id, product_id) = if is_hex_guid && &guid[12..16] == "0000" && &guid[20..24] == "0000" {
+        (usb_id(8), usb_id(16))
+    } else {
+        (None, None)
+    };
+
+    Ok(ParsedController {
+        name: name.to_string(),
+        guid,
+        platform,
+        mapping,
+        vendor_id,
+        product_id,
+    })
+}
+
+/// `b0` buttons, `a1` axes (optionally `+a1`, `-a1` or inverted `a1~`) and `h0.4` hat directions
+fn is_physical_input(input: &str) -> bool {
+    let input = input.strip_prefix(['+', '-']).unwrap_or(input);
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if let Some(button) = input.strip_prefix('b') {
+        digits(button)
+    } else if let Some(axis) = input.strip_prefix('a') {
+        digits(axis.strip_suffix('~').unwrap_or(axis))
+    } else if let Some(hat) = input.strip_prefix('h') {
+        hat.split_once('.').is_some_and(|(hat, mask)| digits(hat) && digits(mask))
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A DualShock 4 entry as published in SDL_GameControllerDB
+    const PS4_LINUX: &str = "030000004c050000c405000011010000,PS4 Controller,a:b0,b:b1,back:b8,dpdown:h0.4,\
+        dpleft:h0.8,dpright:h0.2,dpup:h0.1,guide:b10,leftshoulder:b4,leftstick:b11,lefttrigger:a2,leftx:a0,\
+        lefty:a1,rightshoulder:b5,rightstick:b12,righttrigger:a5,rightx:a3,righty:a4,start:b9,x:b3,y:b2,\
+        platform:Linux,";
+
+    fn error(line: &str) -> String {
+        parse_controller_line(line).err().expect("line should be rejected")
+    }
+
+    #[test]
+    fn parses_gamecontrollerdb_line() {
+        let controller = parse_controller_line(PS4_LINUX).unwrap();
+
+        assert_eq!(controller.guid, "030000004c050000c405000011010000");
+        assert_eq!(controller.name, "PS4 Controller");
+        assert_eq!(controller.platform, "Linux");
+        assert_eq!(controller.vendor_id.as_deref(), Some("054c"));
+        assert_eq!(controller.product_id.as_deref(), Some("05c4"));
+        assert_eq!(controller.mapping.len(), 21);
+        assert_eq!(controller.mapping["a"], "b0");
+        assert_eq!(controller.mapping["lefttrigger"], "a2");
+        assert_eq!(controller.mapping["dpup"], "h0.1");
+        assert!(!controller.mapping.contains_key("platform"));
+    }
+
+    #[test]
+    fn drops_sdl_only_fields() {
+        let line = "03000000de2800000112000001000000,Steam Controller,a:b0,b:b1,-leftx:-a0,+leftx:+a0,\
+            righttrigger:a4~,crc:ec5e,hint:!SDL_GAMECONTROLLER_USE_BUTTON_LABELS:=1,platform:Mac OS X,";
+        let controller = parse_controller_line(line).unwrap();
+
+        assert_eq!(controller.platform, "Mac OS X");
+        assert_eq!(controller.mapping["-leftx"], "-a0");
+        assert_eq!(controller.mapping["righttrigger"], "a4~");
+        assert!(!controller.mapping.contains_key("crc"));
+        assert!(!controller.mapping.contains_key("hint"));
+    }
+
+    #[test]
+    fn xinput_guid_has_no_usb_ids() {
+        let controller = parse_controller_line("xinput,XInput Controller,a:b0,b:b1,platform:Windows,").unwrap();
+
+        assert_eq!(controller.guid, "xinput");
+        assert_eq!(controller.vendor_id, None);
+        assert_eq!(controller.product_id, None);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(error("not-a-guid,Pad,a:b0,platform:Linux,").contains("invalid GUID"));
+        assert!(error("030000004c050000c4050000110100,Pad,a:b0,").contains("invalid GUID"));
+        assert!(error("030000004c050000c405000011010000,,a:b0,").contains("controller name"));
+        assert!(error("030000004c050000c405000011010000,Pad,a,b:b1,").contains("element:input"));
+        assert!(error("030000004c050000c405000011010000,Pad,a:q0,").contains("button, axis or hat"));
+        assert!(error("030000004c050000c405000011010000,Pad,dpup:h0,").contains("button, axis or hat"));
+        assert!(error("030000004c050000c405000011010000,Pad,platform:Linux,").contains("no mapped elements"));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::models::{Console, Game, GameMetadata, LaunchBoxImportSummary};
+use crate::services::{split_genres, GameService};
+use crate::utils::ApiError;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+/// LaunchBox platform names for the default consoles
+const PLATFORM_CONSOLES: &[(&str, &str)] = &[
+    ("Nintendo Entertainment System", "nes"),
+    ("Super Nintendo Entertainment System", "snes"),
+    ("Nintendo Game Boy", "gb"),
+    ("Nintendo Game Boy Color", "gbc"),
+    ("Nintendo Game Boy Advance", "gba"),
+    ("Sega Genesis", "megadrive"),
+    ("Sega Mega Drive", "megadrive"),
+    ("Nintendo 64", "n64"),
+    ("Sony Playstation", "psx"),
+    ("Sega Dreamcast", "dreamcast"),
+    ("Sony Playstation 2", "ps2"),
+];
+
+/// Imports LaunchBox `Data/Platforms/*.xml` metadata onto existing games
+#[derive(Clone, Default)]
+pub struct LaunchBoxService;
+
+#[derive(Debug, Deserialize)]
+struct LaunchBoxPlatform {
+    #[serde(rename = "Game", default)]
+    games: Vec<LaunchBoxGame>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LaunchBoxGame {
+    title: Option<String>,
+    application_path: Option<String>,
+    platform: Option<String>,
+    notes: Option<String>,
+    developer: Option<String>,
+    publisher: Option<String>,
+    genre: Option<String>,
+    release_date: Option<String>,
+    max_players: Option<String>,
+    community_star_rating: Option<String>,
+    region: Option<String>,
+}
+
+impl LaunchBoxGame {
+    fn metadata(&self) -> GameMetadata {
+        GameMetadata {
+            title: text(&self.title),
+            description: text(&self.notes),
+            // Release dates are ISO 8601, e.g. 1991-06-23T00:00:00-07:00
+            release_year: self
+                .release_date
+                .as_deref()
+                .and_then(|d| d.get(..4))
+                .and_then(|y| y.parse().ok()),
+            developer: text(&self.developer),
+            publisher: text(&self.publisher),
+            // Multiple genres are separated by semicolons
+            genres: text(&self.genre).map(|g| split_genres(&g)),
+            players: text(&self.max_players).filter(|p| p != "0"),
+            rating: self
+                .community_star_rating
+                .as_deref()
+                .and_then(|r| r.trim().parse::<f32>().ok())
+                .filter(|r| *r > 0.0)
+                .map(|r| (r / 5.0).clamp(0.0, 1.0)),
+            region: text(&self.region),
+            ..Default::default()
+        }
+    }
+}
+
+impl LaunchBoxService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Imports one platform XML file. The console is taken from `console_id` when given,
+    /// otherwise from the LaunchBox platform name.
+    pub async fn import_platform(
+        &self,
+        pool: &PgPool,
+        xml: &str,
+        console_id: Option<&str>,
+        game_service: &GameService,
+    ) -> Result<LaunchBoxImportSummary, ApiError> {
+        let platform: LaunchBoxPlatform = quick_xml::de::from_str(xml)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid LaunchBox platform file: {}", e)))?;
+
+        let platform_name = platform
+            .games
+            .iter()
+            .find_map(|g| text(&g.platform));
+
+        let consoles = game_service.get_all_consoles(pool).await?;
+        let console = resolve_console(&consoles, console_id, platform_name.as_deref())
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Cannot map LaunchBox platform {:?} to a console, pass ?console=<id>",
+                    platform_name
+                ))
+            })?;
+
+        let games = game_service.get_console_games(pool, &console.id).await?;
+        let mut summary = LaunchBoxImportSummary {
+            platform: platform_name,
+            console_id: Some(console.id.clone()),
+            ..Default::default()
+        };
+
+        for entry in &platform.games {
+            let Some(game) = find_game(&games, entry) else {
+                summary.unmatched.push(
+                    text(&entry.application_path)
+                        .or_else(|| text(&entry.title))
+                        .unwrap_or_default(),
+                );
+                continue;
+            };
+            summary.matched += 1;
+
+            if game_service
+                .apply_metadata(pool, game, &entry.metadata(), "launchbox")
+                .await?
+            {
+                summary.updated += 1;
+            }
+        }
+
+        log::info!(
+            "Imported LaunchBox metadata for {}: {} matched, {} updated, {} unmatched",
+            console.id,
+            summary.matched,
+            summary.updated,
+            summary.unmatched.len()
+        );
+
+        Ok(summary)
+    }
+}
+
+fn resolve_console<'a>(
+    consoles: &'a [Console],
+    console_id: Option<&str>,
+    platform: Option<&str>,
+) -> Option<&'a Console> {
+    if let Some(id) = console_id {
+        return consoles.iter().find(|c| c.id == id);
+    }
+
+    let platform = platform?;
+    let mapped = PLATFORM_CONSOLES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(platform))
+        .map(|(_, id)| *id);
+
+    consoles.iter().find(|c| {
+        Some(c.id.as_str()) == mapped || c.name.eq_ignore_ascii_case(platform)
+    })
+}
+
+/// Matches by ROM filename first, falling back to an exact title match
+fn find_game<'a>(games: &'a [Game], entry: &LaunchBoxGame) -> Option<&'a Game> {
+    let filename = entry
+        .application_path
+        .as_deref()
+        .and_then(|p| p.rsplit(['/', '\\']).next())
+        .filter(|f| !f.is_empty());
+
+    if let Some(filename) = filename {
+        if let Some(game) = games.iter().find(|g| g.rom_filename == filename) {
+            return Some(game);
+        }
+    }
+
+    let title = text(&entry.title)?;
+    games.iter().find(|g| g.title.eq_ignore_ascii_case(&title))
+}
+
+fn text(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(xml: &str) -> LaunchBoxPlatform {
+        quick_xml::de::from_str(xml).unwrap()
+    }
+
+    #[test]
+    fn reads_platform_games() {
+        let platform = parse(
+            r#"<?xml version="1.0" standalone="yes"?>
+<LaunchBox>
+  <Game>
+    <ApplicationPath>..\Games\Sega Genesis\Sonic The Hedgehog (USA, Europe).md</ApplicationPath>
+    <CommunityStarRating>4.25</CommunityStarRating>
+    <Developer>Sonic Team</Developer>
+    <MaxPlayers>1</MaxPlayers>
+    <Notes>Sonic races through Green Hill Zone.</Notes>
+    <Platform>Sega Genesis</Platform>
+    <Publisher>Sega</Publisher>
+    <Region>North America</Region>
+    <ReleaseDate>1991-06-23T00:00:00-07:00</ReleaseDate>
+    <Title>Sonic the Hedgehog</Title>
+  </Game>
+  <Game>
+    <Title>Unrated</Title>
+    <CommunityStarRating>0</CommunityStarRating>
+    <MaxPlayers>0</MaxPlayers>
+    <Notes>   </Notes>
+  </Game>
+  <Platform>
+    <Name>Sega Genesis</Name>
+  </Platform>
+</LaunchBox>"#,
+        );
+        assert_eq!(platform.games.len(), 2);
+
+        let metadata = platform.games[0].metadata();
+        assert_eq!(metadata.title.as_deref(), Some("Sonic the Hedgehog"));
+        assert_eq!(metadata.description.as_deref(), Some("Sonic races through Green Hill Zone."));
+        assert_eq!(metadata.release_year, Some(1991));
+        assert_eq!(metadata.developer.as_deref(), Some("Sonic Team"));
+        assert_eq!(metadata.publisher.as_deref(), Some("Sega"));
+        assert_eq!(metadata.players.as_deref(), Some("1"));
+        assert_eq!(metadata.region.as_deref(), Some("North America"));
+        // Star ratings out of 5 become fractions
+        assert_eq!(metadata.rating, Some(0.85));
+
+        // LaunchBox writes 0 for unknown ratings and player counts
+        let metadata = platform.games[1].metadata();
+        assert_eq!(metadata.rating, None);
+        assert_eq!(metadata.players, None);
+        assert_eq!(metadata.description, None);
+        assert_eq!(metadata.release_year, None);
+    }
+}
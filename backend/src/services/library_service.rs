@@ -0,0 +1,483 @@
+use crate::models::{
+    CreateConsoleRequest, Game, GameMetadata, LibraryBundle, LibraryCollection, LibraryGame,
+    LibraryImportSummary, LibraryMedia, LibraryTag, LIBRARY_FORMAT_VERSION, LIBRARY_MANIFEST_FILENAME,
+    LOCKABLE_FIELDS, TAG_KINDS,
+};
+use crate::services::{ConsoleService, GameService, MediaService};
+use crate::utils::ApiError;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use uuid::Uuid;
+use validator::Validate;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MEDIA_DIR: &str = "media";
+
+/// Most an uploaded archive may decompress to, whatever sizes its entries claim
+const MAX_ARCHIVE_CONTENT_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// An uploaded bundle along with the media files that came with it, keyed by file name
+pub struct LibraryArchive {
+    pub bundle: LibraryBundle,
+    pub media_files: HashMap<String, Vec<u8>>,
+}
+
+/// Moves library metadata between instances as a JSON document or a ZIP archive with media
+#[derive(Clone, Default)]
+pub struct LibraryService;
+
+impl LibraryService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Collects consoles, tags, games, media references and collections.
+    /// Checksums that haven't been computed yet are computed first.
+    pub async fn export_library(
+        &self,
+        pool: &PgPool,
+        game_service: &GameService,
+        media_service: &MediaService,
+    ) -> Result<LibraryBundle, ApiError> {
+        let consoles = game_service.get_all_consoles(pool).await?;
+
+        let tags = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT kind, name, slug FROM tags ORDER BY kind, slug"
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|(kind, name, slug)| LibraryTag { kind, name, slug })
+        .collect();
+
+        let mut games = Vec::new();
+        for console in &consoles {
+            for game in game_service.get_console_games(pool, &console.id).await? {
+                let rom_crc32 = match game_service.ensure_rom_crc32(pool, &game).await {
+                    Ok(crc) => Some(crc),
+                    Err(e) => {
+                        log::warn!("No CRC32 for {}: {}", game.rom_filename, e);
+                        game.rom_crc32.clone()
+                    }
+                };
+
+                let media = media_service
+                    .get_game_media(pool, &game.id)
+                    .await?
+                    .into_iter()
+                    .map(|m| LibraryMedia {
+                        kind: m.kind,
+                        content_hash: m.content_hash,
+                        file_extension: m.file_extension,
+                    })
+                    .collect();
+
+                games.push(LibraryGame {
+                    id: game.id,
+                    console_id: game.console_id.clone(),
+                    rom_filename: game.rom_filename.clone(),
+                    rom_size_bytes: game.rom_size_bytes,
+                    rom_crc32,
+                    metadata: game_metadata(&game),
+                    locked_fields: game.locked_fields.clone(),
+                    tags: game
+                        .tags
+                        .iter()
+                        .filter(|t| t.kind != "genre")
+                        .map(|t| LibraryTag {
+                            kind: t.kind.clone(),
+                            name: t.name.clone(),
+                            slug: t.slug.clone(),
+                        })
+                        .collect(),
+                    media,
+                });
+            }
+        }
+
+        let collection_games = sqlx::query_as::<_, (Uuid, Uuid)>(
+            "SELECT collection_id, game_id FROM collection_games ORDER BY collection_id, position"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let collections = sqlx::query_as::<_, (Uuid, String, String, Option<String>, bool)>(
+            "SELECT c.id, u.username, c.name, c.description, c.is_public
+             FROM collections c
+             JOIN users u ON u.id = c.user_id
+             ORDER BY u.username, c.name"
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|(id, owner, name, description, is_public)| LibraryCollection {
+            owner,
+            name,
+            description,
+            is_public,
+            game_ids: collection_games
+                .iter()
+                .filter(|(collection_id, _)| *collection_id == id)
+                .map(|(_, game_id)| *game_id)
+                .collect(),
+        })
+        .collect();
+
+        Ok(LibraryBundle {
+            format_version: LIBRARY_FORMAT_VERSION,
+            exported_at: chrono::Utc::now(),
+            consoles,
+            tags,
+            games,
+            collections,
+        })
+    }
+
+    /// Packs the bundle and the media files it references into a ZIP archive
+    pub fn write_archive(&self, bundle: &LibraryBundle, media_service: &MediaService) -> Result<Vec<u8>, ApiError> {
+        let zip_error = |e: zip::result::ZipError| ApiError::InternalServerError(format!("Failed to write archive: {}", e));
+        let io_error = |e: std::io::Error| ApiError::InternalServerError(format!("Failed to write archive: {}", e));
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        // Images are already compressed
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+        zip.start_file(LIBRARY_MANIFEST_FILENAME, deflated).map_err(zip_error)?;
+        serde_json::to_writer_pretty(&mut zip, bundle)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to write archive: {}", e)))?;
+
+        let mut written = HashSet::new();
+        for media in bundle.games.iter().flat_map(|g| &g.media) {
+            let filename = format!("{}.{}", media.content_hash, media.file_extension);
+            if !written.insert(filename.clone()) {
+                continue;
+            }
+
+            let data = match media_service
+                .get_media_file_path(false, &filename)
+                .and_then(|path| fs::read(path).map_err(|e| ApiError::NotFound(e.to_string())))
+            {
+                Ok(data) => data,
+                Err(e) => {
+                    log::warn!("Leaving media {} out of the library archive: {}", filename, e);
+                    continue;
+                }
+            };
+
+            zip.start_file(format!("{}/{}", MEDIA_DIR, filename), stored)
+                .map_err(zip_error)?;
+            zip.write_all(&data).map_err(io_error)?;
+        }
+
+        Ok(zip.finish().map_err(zip_error)?.into_inner())
+    }
+
+    /// Parses an uploaded bundle, either a bare JSON document or a ZIP archive
+    pub fn read_archive(&self, data: &[u8]) -> Result<LibraryArchive, ApiError> {
+        let mut media_files = HashMap::new();
+
+        let bundle: LibraryBundle = if data.starts_with(b"PK\x03\x04") {
+            let zip_error = |e: zip::result::ZipError| ApiError::BadRequest(format!("Invalid library archive: {}", e));
+            let mut zip = ZipArchive::new(Cursor::new(data)).map_err(zip_error)?;
+            let mut manifest = None;
+            let mut remaining = MAX_ARCHIVE_CONTENT_BYTES;
+
+            for index in 0..zip.len() {
+                let mut file = zip.by_index(index).map_err(zip_error)?;
+                let name = file.name().to_string();
+
+                if name == LIBRARY_MANIFEST_FILENAME {
+                    manifest = Some(read_entry(&mut file, &mut remaining)?);
+                } else if let Some(filename) = name.strip_prefix(&format!("{}/", MEDIA_DIR)) {
+                    if filename.is_empty() || filename.contains('/') {
+                        continue;
+                    }
+                    media_files.insert(filename.to_string(), read_entry(&mut file, &mut remaining)?);
+                }
+            }
+
+            let manifest = manifest.ok_or_else(|| {
+                ApiError::BadRequest(format!("Library archive has no {}", LIBRARY_MANIFEST_FILENAME))
+            })?;
+            serde_json::from_slice(&manifest)
+        } else {
+            serde_json::from_slice(data)
+        }
+        .map_err(|e| ApiError::BadRequest(format!("Invalid library bundle: {}", e)))?;
+
+        if bundle.format_version > LIBRARY_FORMAT_VERSION {
+            return Err(ApiError::BadRequest(format!(
+                "Library format version {} is newer than the supported version {}",
+                bundle.format_version, LIBRARY_FORMAT_VERSION
+            )));
+        }
+
+        Ok(LibraryArchive { bundle, media_files })
+    }
+
+    /// Applies a bundle to this library. Games are matched by console and ROM CRC32,
+    /// falling back to the file name for games exported without a checksum.
+    /// Missing consoles, tags and collections are created, locked fields are left alone.
+    pub async fn import_library(
+        &self,
+        pool: &PgPool,
+        game_service: &GameService,
+        media_service: &MediaService,
+        archive: &LibraryArchive,
+    ) -> Result<LibraryImportSummary, ApiError> {
+        let bundle = &archive.bundle;
+        let mut summary = LibraryImportSummary::default();
+
+        // Hashing ROMs can take a while, so match against existing games before locking anything
+        let consoles = game_service.get_all_consoles(pool).await?;
+        let mut games = Vec::new();
+        let mut by_checksum: HashMap<(String, String), Vec<usize>> = HashMap::new();
+        let mut by_filename: HashMap<(String, String), usize> = HashMap::new();
+        for console in &consoles {
+            for game in game_service.get_console_games(pool, &console.id).await? {
+                match game_service.ensure_rom_crc32(pool, &game).await {
+                    Ok(crc) => by_checksum
+                        .entry((game.console_id.clone(), crc))
+                        .or_default()
+                        .push(games.len()),
+                    Err(e) => log::warn!("No CRC32 for {}: {}", game.rom_filename, e),
+                }
+                by_filename.insert((game.console_id.clone(), game.rom_filename.clone()), games.len());
+                games.push(game);
+            }
+        }
+
+        // All or nothing, so a failure halfway doesn't leave a partly imported library
+        let mut tx = pool.begin().await?;
+
+        let console_ids: HashSet<&str> = consoles.iter().map(|c| c.id.as_str()).collect();
+
+        for console in bundle.consoles.iter().filter(|c| !console_ids.contains(c.id.as_str())) {
+            let request = CreateConsoleRequest {
+                id: console.id.clone(),
+                name: console.name.clone(),
+                manufacturer: console.manufacturer.clone(),
+                release_year: console.release_year,
+                icon_url: console.icon_url.clone(),
+                emulator_core: console.emulator_core.clone(),
+                supported_extensions: console.supported_extensions.clone(),
+                emulator_config: console.emulator_config.0.clone(),
+            };
+            request.validate()?;
+            ConsoleService::new().create_console(&mut tx, &request).await?;
+            summary.consoles_created += 1;
+        }
+
+        for tag in bundle.tags.iter().chain(bundle.games.iter().flat_map(|g| &g.tags)) {
+            if !TAG_KINDS.contains(&tag.kind.as_str()) {
+                log::warn!("Skipping tag {} with unknown kind {}", tag.slug, tag.kind);
+                continue;
+            }
+
+            let created: Option<Uuid> = sqlx::query_scalar(
+                "INSERT INTO tags (kind, name, slug)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (kind, slug) DO NOTHING
+                 RETURNING id"
+            )
+            .bind(&tag.kind)
+            .bind(&tag.name)
+            .bind(&tag.slug)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if created.is_some() {
+                summary.tags_created += 1;
+            }
+        }
+
+        let mut claimed = HashSet::new();
+        let mut game_ids = HashMap::new();
+        for entry in &bundle.games {
+            let candidates = match &entry.rom_crc32 {
+                Some(crc) => by_checksum
+                    .get(&(entry.console_id.clone(), crc.to_uppercase()))
+                    .cloned()
+                    .unwrap_or_default(),
+                None => by_filename
+                    .get(&(entry.console_id.clone(), entry.rom_filename.clone()))
+                    .map(|index| vec![*index])
+                    .unwrap_or_default(),
+            };
+            // The same dump can be in a library more than once, pair copies up by file name first
+            let Some(index) = candidates
+                .into_iter()
+                .filter(|index| !claimed.contains(index))
+                .min_by_key(|index| games[*index].rom_filename != entry.rom_filename)
+            else {
+                summary.unmatched.push(format!("{}/{}", entry.console_id, entry.rom_filename));
+                continue;
+            };
+            claimed.insert(index);
+            let game = &games[index];
+            summary.games_matched += 1;
+            game_ids.insert(entry.id, game.id);
+
+            let existing_media = media_service.get_game_media(pool, &game.id).await?;
+            for media in &entry.media {
+                if existing_media
+                    .iter()
+                    .any(|m| m.kind == media.kind && m.content_hash == media.content_hash)
+                {
+                    continue;
+                }
+
+                let filename = format!("{}.{}", media.content_hash, media.file_extension);
+                let data = match archive.media_files.get(&filename) {
+                    Some(data) => Some(data.clone()),
+                    // Instances sharing a media folder already have the file
+                    None => media_service
+                        .get_media_file_path(false, &filename)
+                        .ok()
+                        .and_then(|path| fs::read(path).ok()),
+                };
+                let Some(data) = data else {
+                    summary.media_missing += 1;
+                    continue;
+                };
+
+                match media_service.create_media(&mut tx, &game.id, &media.kind, data, None).await {
+                    Ok(_) => summary.media_imported += 1,
+                    Err(e) => {
+                        log::warn!("Skipping library media {}: {}", filename, e);
+                        summary.media_missing += 1;
+                    }
+                }
+            }
+
+            let mut updated = game_service
+                .apply_metadata(&mut tx, game, &entry.metadata, "library")
+                .await?;
+
+            let locked_fields: Vec<&str> = entry
+                .locked_fields
+                .iter()
+                .map(String::as_str)
+                .filter(|f| LOCKABLE_FIELDS.contains(f))
+                .collect();
+            let locked = sqlx::query(
+                "UPDATE games
+                 SET locked_fields = ARRAY(SELECT DISTINCT f FROM UNNEST(locked_fields || $2::text[]) f ORDER BY f)
+                 WHERE id = $1 AND NOT locked_fields @> $2::text[]"
+            )
+            .bind(game.id)
+            .bind(&locked_fields)
+            .execute(&mut *tx)
+            .await?;
+            updated |= locked.rows_affected() > 0;
+
+            for tag in &entry.tags {
+                let added = sqlx::query(
+                    "INSERT INTO game_tags (game_id, tag_id)
+                     SELECT $1, id FROM tags WHERE kind = $2 AND slug = $3
+                     ON CONFLICT DO NOTHING"
+                )
+                .bind(game.id)
+                .bind(&tag.kind)
+                .bind(&tag.slug)
+                .execute(&mut *tx)
+                .await?;
+                updated |= added.rows_affected() > 0;
+            }
+
+            if updated {
+                summary.games_updated += 1;
+            }
+        }
+
+        let users: HashMap<String, Uuid> = sqlx::query_as::<_, (String, Uuid)>("SELECT username, id FROM users")
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+        for collection in &bundle.collections {
+            let Some(user_id) = users.get(&collection.owner) else {
+                summary
+                    .skipped_collections
+                    .push(format!("{}/{}", collection.owner, collection.name));
+                continue;
+            };
+
+            let collection_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO collections (user_id, name, description, is_public)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (user_id, name) DO UPDATE
+                 SET description = EXCLUDED.description, is_public = EXCLUDED.is_public, updated_at = NOW()
+                 RETURNING id"
+            )
+            .bind(user_id)
+            .bind(&collection.name)
+            .bind(&collection.description)
+            .bind(collection.is_public)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            // Games already in the collection keep their place, new ones are appended
+            for game_id in collection.game_ids.iter().filter_map(|id| game_ids.get(id)) {
+                sqlx::query(
+                    "INSERT INTO collection_games (collection_id, game_id, position)
+                     VALUES ($1, $2, (SELECT COALESCE(MAX(position) + 1, 0) FROM collection_games WHERE collection_id = $1))
+                     ON CONFLICT DO NOTHING"
+                )
+                .bind(collection_id)
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            summary.collections_imported += 1;
+        }
+
+        tx.commit().await?;
+
+        log::info!(
+            "Imported library bundle: {} games matched, {} updated, {} unmatched",
+            summary.games_matched,
+            summary.games_updated,
+            summary.unmatched.len()
+        );
+
+        Ok(summary)
+    }
+}
+
+/// Reads an archive entry, counting it against the `remaining` decompressed bytes
+fn read_entry(file: impl Read, remaining: &mut u64) -> Result<Vec<u8>, ApiError> {
+    let mut contents = Vec::new();
+    file.take(*remaining + 1)
+        .read_to_end(&mut contents)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid library archive: {}", e)))?;
+
+    *remaining = remaining
+        .checked_sub(contents.len() as u64)
+        .ok_or_else(|| ApiError::BadRequest("Library archive exceeds maximum size".to_string()))?;
+
+    Ok(contents)
+}
+
+fn game_metadata(game: &Game) -> GameMetadata {
+    let genres = game.genres();
+
+    GameMetadata {
+        title: Some(game.title.clone()),
+        description: game.description.clone(),
+        release_year: game.release_year,
+        developer: game.developer.clone(),
+        publisher: game.publisher.clone(),
+        genres: Some(genres).filter(|g| !g.is_empty()),
+        players: game.players.clone(),
+        rating: game.rating,
+        region: game.region.clone(),
+        cover_url: game.cover_url.clone(),
+    }
+}
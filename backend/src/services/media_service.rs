@@ -0,0 +1,287 @@
+use crate::models::{GameMedia, GameMediaResponse, MediaThumbnail, MEDIA_KINDS, THUMBNAIL_SIZES};
+use crate::utils::ApiError;
+use image::{DynamicImage, ImageFormat};
+use sha2::{Digest, Sha256};
+use sqlx::{Acquire, PgPool, Postgres};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const MAX_MEDIA_SIZE_BYTES: usize = 20 * 1024 * 1024;
+
+const MEDIA_URL_PREFIX: &str = "/api/v1/media";
+const THUMBNAIL_DIR: &str = "thumbs";
+
+/// Thumbnail size used for `games.cover_url`
+const COVER_THUMBNAIL_SIZE: &str = "medium";
+
+#[derive(Clone)]
+pub struct MediaService {
+    media_storage_path: String,
+}
+
+struct StoredImage {
+    extension: &'static str,
+    mime_type: &'static str,
+    width: u32,
+    height: u32,
+}
+
+impl MediaService {
+    pub fn new(media_storage_path: String) -> Self {
+        Self { media_storage_path }
+    }
+
+    pub async fn get_game_media(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+    ) -> Result<Vec<GameMedia>, ApiError> {
+        let media = sqlx::query_as::<_, GameMedia>(
+            "SELECT * FROM game_media WHERE game_id = $1 ORDER BY kind, created_at"
+        )
+        .bind(game_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    pub async fn get_media(&self, pool: &PgPool, media_id: &Uuid) -> Result<GameMedia, ApiError> {
+        let media = sqlx::query_as::<_, GameMedia>("SELECT * FROM game_media WHERE id = $1")
+            .bind(media_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(media)
+    }
+
+    /// Stores an uploaded image under its content hash and generates its thumbnails.
+    /// Uploading the same image twice for a game returns the existing entry. Within a
+    /// transaction only the database row is rolled back, the stored files stay.
+    pub async fn create_media<'c>(
+        &self,
+        conn: impl Acquire<'c, Database = Postgres>,
+        game_id: &Uuid,
+        kind: &str,
+        data: Vec<u8>,
+        user_id: Option<&Uuid>,
+    ) -> Result<GameMedia, ApiError> {
+        if !MEDIA_KINDS.contains(&kind) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid media kind '{}', expected one of: {}",
+                kind,
+                MEDIA_KINDS.join(", ")
+            )));
+        }
+
+        if data.len() > MAX_MEDIA_SIZE_BYTES {
+            return Err(ApiError::BadRequest("Image exceeds maximum size".to_string()));
+        }
+
+        let content_hash = hex::encode(Sha256::digest(&data));
+        let size_bytes = data.len() as i64;
+
+        // Decoding and resizing is CPU bound, keep it off the async workers
+        let root = PathBuf::from(&self.media_storage_path);
+        let hash = content_hash.clone();
+        let stored = tokio::task::spawn_blocking(move || store_image(&root, &hash, &data))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))??;
+
+        let mut conn = conn.acquire().await?;
+        let media = sqlx::query_as::<_, GameMedia>(
+            "INSERT INTO game_media (game_id, kind, content_hash, file_extension, mime_type, width, height, size_bytes, uploaded_by)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (game_id, kind, content_hash) DO UPDATE SET uploaded_by = EXCLUDED.uploaded_by
+             RETURNING *"
+        )
+        .bind(game_id)
+        .bind(kind)
+        .bind(&content_hash)
+        .bind(stored.extension)
+        .bind(stored.mime_type)
+        .bind(stored.width as i32)
+        .bind(stored.height as i32)
+        .bind(size_bytes)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Deletes a media entry, removing its files once no other entry shares the same content.
+    /// A cover pointing at the removed image falls back to the newest remaining box art.
+    pub async fn delete_media(&self, pool: &PgPool, media: &GameMedia) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM game_media WHERE id = $1")
+            .bind(media.id)
+            .execute(pool)
+            .await?;
+
+        let fallback: Option<String> = sqlx::query_scalar(
+            "SELECT content_hash FROM game_media
+             WHERE game_id = $1 AND kind = 'box_art'
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(media.game_id)
+        .fetch_optional(pool)
+        .await?;
+
+        sqlx::query("UPDATE games SET cover_url = $2 WHERE id = $1 AND cover_url LIKE $3")
+            .bind(media.game_id)
+            .bind(fallback.map(|hash| self.cover_url(&hash)))
+            .bind(format!("%{}%", media.content_hash))
+            .execute(pool)
+            .await?;
+
+        let still_used: Option<bool> = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM game_media WHERE content_hash = $1)"
+        )
+        .bind(&media.content_hash)
+        .fetch_one(pool)
+        .await?;
+
+        if still_used != Some(true) {
+            let root = Path::new(&self.media_storage_path);
+            fs::remove_file(root.join(media_filename(&media.content_hash, &media.file_extension))).ok();
+
+            for (size, _) in THUMBNAIL_SIZES {
+                for extension in ["webp", "png"] {
+                    let thumbnail = thumbnail_filename(&media.content_hash, size, extension);
+                    fs::remove_file(root.join(THUMBNAIL_DIR).join(thumbnail)).ok();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_response(&self, media: GameMedia) -> GameMediaResponse {
+        let url = format!(
+            "{}/{}",
+            MEDIA_URL_PREFIX,
+            media_filename(&media.content_hash, &media.file_extension)
+        );
+
+        let thumbnails = THUMBNAIL_SIZES
+            .iter()
+            .map(|(size, _)| MediaThumbnail {
+                size: size.to_string(),
+                webp_url: self.thumbnail_url(&media.content_hash, size, "webp"),
+                png_url: self.thumbnail_url(&media.content_hash, size, "png"),
+            })
+            .collect();
+
+        GameMediaResponse {
+            media,
+            url,
+            thumbnails,
+        }
+    }
+
+    pub fn get_media_path(&self, media: &GameMedia) -> PathBuf {
+        Path::new(&self.media_storage_path).join(media_filename(&media.content_hash, &media.file_extension))
+    }
+
+    /// URL stored in `games.cover_url` for a box art image
+    pub fn cover_url(&self, content_hash: &str) -> String {
+        self.thumbnail_url(content_hash, COVER_THUMBNAIL_SIZE, "webp")
+    }
+
+    fn thumbnail_url(&self, content_hash: &str, size: &str, extension: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            MEDIA_URL_PREFIX,
+            THUMBNAIL_DIR,
+            thumbnail_filename(content_hash, size, extension)
+        )
+    }
+
+    /// Resolves a requested media filename, rejecting anything that is not a plain file name.
+    pub fn get_media_file_path(&self, thumbnail: bool, filename: &str) -> Result<PathBuf, ApiError> {
+        let valid = !filename.is_empty()
+            && !filename.starts_with('.')
+            && filename
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+
+        if !valid {
+            return Err(ApiError::NotFound("Media not found".to_string()));
+        }
+
+        let root = Path::new(&self.media_storage_path);
+        let path = if thumbnail {
+            root.join(THUMBNAIL_DIR).join(filename)
+        } else {
+            root.join(filename)
+        };
+
+        Ok(path)
+    }
+}
+
+fn media_filename(content_hash: &str, extension: &str) -> String {
+    format!("{}.{}", content_hash, extension)
+}
+
+fn thumbnail_filename(content_hash: &str, size: &str, extension: &str) -> String {
+    format!("{}_{}.{}", content_hash, size, extension)
+}
+
+/// Writes the original image and its WebP/PNG thumbnails under `root`.
+fn store_image(root: &Path, content_hash: &str, data: &[u8]) -> Result<StoredImage, ApiError> {
+    let format = image::guess_format(data)
+        .map_err(|_| ApiError::BadRequest("Unrecognised image format".to_string()))?;
+
+    let (extension, mime_type) = match format {
+        ImageFormat::Png => ("png", "image/png"),
+        ImageFormat::Jpeg => ("jpg", "image/jpeg"),
+        ImageFormat::WebP => ("webp", "image/webp"),
+        ImageFormat::Gif => ("gif", "image/gif"),
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Unsupported image format, expected PNG, JPEG, WebP or GIF".to_string(),
+            ))
+        }
+    };
+
+    let img = image::load_from_memory_with_format(data, format)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid image: {}", e)))?;
+
+    let thumbs_dir = root.join(THUMBNAIL_DIR);
+    fs::create_dir_all(&thumbs_dir)
+        .map_err(|e| ApiError::InternalServerError(format!("Failed to create media directory: {}", e)))?;
+
+    fs::write(root.join(media_filename(content_hash, extension)), data)
+        .map_err(|e| ApiError::InternalServerError(format!("Failed to write media file: {}", e)))?;
+
+    for (size, max_dimension) in THUMBNAIL_SIZES {
+        // Never upscale, small pixel-art title screens are served at their native size
+        let thumbnail = if img.width() > *max_dimension || img.height() > *max_dimension {
+            img.thumbnail(*max_dimension, *max_dimension)
+        } else {
+            img.clone()
+        };
+        let thumbnail = DynamicImage::ImageRgba8(thumbnail.to_rgba8());
+
+        for (extension, format) in [("webp", ImageFormat::WebP), ("png", ImageFormat::Png)] {
+            let mut bytes = Cursor::new(Vec::new());
+            thumbnail
+                .write_to(&mut bytes, format)
+                .map_err(|e| ApiError::InternalServerError(format!("Failed to encode thumbnail: {}", e)))?;
+
+            let path = thumbs_dir.join(thumbnail_filename(content_hash, size, extension));
+            fs::write(&path, bytes.into_inner())
+                .map_err(|e| ApiError::InternalServerError(format!("Failed to write thumbnail: {}", e)))?;
+        }
+    }
+
+    Ok(StoredImage {
+        extension,
+        mime_type,
+        width: img.width(),
+        height: img.height(),
+    })
+}
@@ -0,0 +1,390 @@
+use crate::models::{Game, GameMetadata, MetadataEntry, MetadataRecord};
+use crate::services::{normalize_title, split_genres, MetadataProvider};
+use crate::utils::ApiError;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// Metadata provider backed by the `metadata_entries` table, filled from local
+/// libretro-database `.dat` files or JSON bundles. Never touches the network.
+#[derive(Clone, Default)]
+pub struct LocalMetadataProvider;
+
+impl LocalMetadataProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Imports a clrmamepro-format `.dat` file for one console. Returns the number of entries stored.
+    pub async fn import_dat(
+        &self,
+        pool: &PgPool,
+        console_id: &str,
+        dat: &str,
+        source: &str,
+    ) -> Result<usize, ApiError> {
+        let records = parse_dat(dat)?;
+        self.import_records(pool, Some(console_id), &records, source).await
+    }
+
+    /// Stores records, merging them with existing entries that share a CRC, serial or title.
+    /// Records without a `console_id` fall back to `default_console`.
+    pub async fn import_records(
+        &self,
+        pool: &PgPool,
+        default_console: Option<&str>,
+        records: &[MetadataRecord],
+        source: &str,
+    ) -> Result<usize, ApiError> {
+        let mut imported = 0;
+        let mut tx = pool.begin().await?;
+
+        for record in records {
+            let Some(console_id) = record.console_id.as_deref().or(default_console) else {
+                return Err(ApiError::ValidationError(
+                    "Metadata record without console_id, pass ?console=<id>".to_string(),
+                ));
+            };
+
+            let crc32 = record
+                .crc32
+                .as_deref()
+                .map(|c| c.trim().to_uppercase())
+                .filter(|c| c.len() == 8 && c.chars().all(|ch| ch.is_ascii_hexdigit()));
+            let serial = record
+                .serial
+                .as_deref()
+                .map(|s| s.trim().to_uppercase())
+                .filter(|s| !s.is_empty());
+            let normalized_title = record
+                .title
+                .as_deref()
+                .map(normalize_title)
+                .filter(|t| !t.is_empty());
+
+            let match_key = match (&crc32, &serial, &normalized_title) {
+                (Some(crc), _, _) => format!("crc:{}", crc),
+                (None, Some(serial), _) => format!("serial:{}", serial),
+                (None, None, Some(title)) => format!("title:{}", title),
+                (None, None, None) => continue,
+            };
+
+            // Genres stay a plain string here and become tags when applied to a game
+            let genre = record
+                .genre
+                .as_deref()
+                .map(|g| g.trim().chars().take(50).collect::<String>())
+                .filter(|g| !g.is_empty());
+
+            let record = GameMetadata {
+                title: record.title.clone(),
+                description: record.description.clone(),
+                release_year: record.release_year,
+                developer: record.developer.clone(),
+                publisher: record.publisher.clone(),
+                players: record.players.clone(),
+                region: record.region.clone(),
+                ..Default::default()
+            }
+            .clamp_lengths();
+
+            sqlx::query(
+                "INSERT INTO metadata_entries
+                    (console_id, match_key, crc32, serial, title, normalized_title, description,
+                     developer, publisher, genre, release_year, players, region, source)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                 ON CONFLICT (console_id, match_key) DO UPDATE SET
+                    serial = COALESCE(EXCLUDED.serial, metadata_entries.serial),
+                    title = COALESCE(EXCLUDED.title, metadata_entries.title),
+                    normalized_title = COALESCE(EXCLUDED.normalized_title, metadata_entries.normalized_title),
+                    description = COALESCE(EXCLUDED.description, metadata_entries.description),
+                    developer = COALESCE(EXCLUDED.developer, metadata_entries.developer),
+                    publisher = COALESCE(EXCLUDED.publisher, metadata_entries.publisher),
+                    genre = COALESCE(EXCLUDED.genre, metadata_entries.genre),
+                    release_year = COALESCE(EXCLUDED.release_year, metadata_entries.release_year),
+                    players = COALESCE(EXCLUDED.players, metadata_entries.players),
+                    region = COALESCE(EXCLUDED.region, metadata_entries.region),
+                    source = EXCLUDED.source,
+                    updated_at = NOW()"
+            )
+            .bind(console_id)
+            .bind(&match_key)
+            .bind(&crc32)
+            .bind(&serial)
+            .bind(&record.title)
+            .bind(&normalized_title)
+            .bind(&record.description)
+            .bind(&record.developer)
+            .bind(&record.publisher)
+            .bind(&genre)
+            .bind(record.release_year)
+            .bind(&record.players)
+            .bind(&record.region)
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+
+            imported += 1;
+        }
+
+        tx.commit().await?;
+
+        Ok(imported)
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for LocalMetadataProvider {
+    fn name(&self) -> &str {
+        "metadata_db"
+    }
+
+    async fn lookup(
+        &self,
+        pool: &PgPool,
+        game: &Game,
+        crc32: Option<&str>,
+    ) -> Result<Option<GameMetadata>, ApiError> {
+        // Checksum matches win over title matches
+        let entry = sqlx::query_as::<_, MetadataEntry>(
+            "SELECT * FROM metadata_entries
+             WHERE console_id = $1 AND (crc32 = $2 OR normalized_title = $3)
+             ORDER BY COALESCE(crc32 = $2, false) DESC, updated_at DESC
+             LIMIT 1"
+        )
+        .bind(&game.console_id)
+        .bind(crc32)
+        .bind(normalize_title(&game.title))
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(entry.map(|e| GameMetadata {
+            title: e.title,
+            description: e.description,
+            release_year: e.release_year,
+            developer: e.developer,
+            publisher: e.publisher,
+            genres: e.genre.as_deref().map(split_genres),
+            players: e.players,
+            region: e.region,
+            ..Default::default()
+        }))
+    }
+}
+
+#[derive(Debug)]
+enum DatValue {
+    Text(String),
+    Group(Vec<(String, DatValue)>),
+}
+
+fn dat_text<'a>(fields: &'a [(String, DatValue)], key: &str) -> Option<&'a str> {
+    fields.iter().find_map(|(k, v)| match v {
+        DatValue::Text(t) if k == key && !t.trim().is_empty() => Some(t.trim()),
+        _ => None,
+    })
+}
+
+/// Parses clrmamepro `.dat` files as shipped in libretro-database `dat/` and `metadat/`:
+///
+/// ```text
+/// game (
+///     name "Super Mario World (USA)"
+///     developer "Nintendo"
+///     releaseyear "1990"
+///     rom ( name "Super Mario World (USA).sfc" size 524288 crc B19ED489 serial "SNS-MW-USA" )
+/// )
+/// ```
+fn parse_dat(dat: &str) -> Result<Vec<MetadataRecord>, ApiError> {
+    let tokens = tokenize_dat(dat)?;
+    let mut position = 0;
+    let entries = parse_dat_group(&tokens, &mut position)?;
+
+    let records = entries
+        .iter()
+        .filter(|(key, _)| key == "game")
+        .filter_map(|(_, value)| match value {
+            DatValue::Group(fields) => Some(fields),
+            DatValue::Text(_) => None,
+        })
+        .map(|fields| {
+            let rom = fields.iter().find_map(|(k, v)| match v {
+                DatValue::Group(rom) if k == "rom" => Some(rom.as_slice()),
+                _ => None,
+            });
+
+            MetadataRecord {
+                console_id: None,
+                crc32: rom.and_then(|r| dat_text(r, "crc")).map(str::to_string),
+                serial: dat_text(fields, "serial")
+                    .or_else(|| rom.and_then(|r| dat_text(r, "serial")))
+                    .map(str::to_string),
+                title: dat_text(fields, "name").map(str::to_string),
+                description: None,
+                developer: dat_text(fields, "developer").map(str::to_string),
+                publisher: dat_text(fields, "publisher").map(str::to_string),
+                genre: dat_text(fields, "genre").map(str::to_string),
+                release_year: dat_text(fields, "releaseyear").and_then(|y| y.parse().ok()),
+                players: dat_text(fields, "users").map(str::to_string),
+                region: dat_text(fields, "region").map(str::to_string),
+            }
+        })
+        .collect();
+
+    Ok(records)
+}
+
+#[derive(Debug, PartialEq)]
+enum DatToken {
+    Open,
+    Close,
+    Word(String),
+}
+
+fn tokenize_dat(dat: &str) -> Result<Vec<DatToken>, ApiError> {
+    let mut tokens = Vec::new();
+    let mut chars = dat.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(DatToken::Open),
+            ')' => tokens.push(DatToken::Close),
+            '"' => {
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => {
+                            if let Some(escaped) = chars.next() {
+                                word.push(escaped);
+                            }
+                        }
+                        Some(ch) => word.push(ch),
+                        None => {
+                            return Err(ApiError::BadRequest("Unterminated string in .dat file".to_string()))
+                        }
+                    }
+                }
+                tokens.push(DatToken::Word(word));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == '(' || next == ')' || next == '"' {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(DatToken::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Reads `key value` / `key ( ... )` pairs until the matching `)` or end of input
+fn parse_dat_group(tokens: &[DatToken], position: &mut usize) -> Result<Vec<(String, DatValue)>, ApiError> {
+    let mut fields = Vec::new();
+
+    while *position < tokens.len() {
+        let key = match &tokens[*position] {
+            DatToken::Close => {
+                *position += 1;
+                return Ok(fields);
+            }
+            DatToken::Word(word) => word.clone(),
+            DatToken::Open => {
+                return Err(ApiError::BadRequest("Unexpected '(' in .dat file".to_string()))
+            }
+        };
+        *position += 1;
+
+        let value = match tokens.get(*position) {
+            Some(DatToken::Open) => {
+                *position += 1;
+                DatValue::Group(parse_dat_group(tokens, position)?)
+            }
+            Some(DatToken::Word(word)) => {
+                *position += 1;
+                DatValue::Text(word.clone())
+            }
+            Some(DatToken::Close) | None => DatValue::Text(String::new()),
+        };
+
+        fields.push((key, value));
+    }
+
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNES_DAT: &str = r#"clrmamepro (
+	name "Nintendo - Super Nintendo Entertainment System"
+	version "2024.01.01"
+)
+
+game (
+	name "Super Mario World (USA)"
+	description "Super Mario World (USA)"
+	developer "Nintendo EAD"
+	publisher "Nintendo"
+	releaseyear "1990"
+	users "2"
+	region "USA"
+	rom ( name "Super Mario World (USA).sfc" size 524288 crc B19ED489 md5 CDD3C8C37322978CA8669B34BC89C804 serial "SNS-MW-USA" )
+)
+
+game (
+	name "Street Fighter II \"Turbo\" (Japan)"
+	releaseyear "unknown"
+	rom ( name "Street Fighter II Turbo (Japan).sfc" size 2097152 crc 2F4D6AE8 )
+)
+"#;
+
+    #[test]
+    fn parses_game_entries() {
+        let records = parse_dat(SNES_DAT).unwrap();
+        // The clrmamepro header is not a game
+        assert_eq!(records.len(), 2);
+
+        let smw = &records[0];
+        assert_eq!(smw.console_id, None);
+        assert_eq!(smw.title.as_deref(), Some("Super Mario World (USA)"));
+        assert_eq!(smw.crc32.as_deref(), Some("B19ED489"));
+        assert_eq!(smw.serial.as_deref(), Some("SNS-MW-USA"));
+        assert_eq!(smw.developer.as_deref(), Some("Nintendo EAD"));
+        assert_eq!(smw.publisher.as_deref(), Some("Nintendo"));
+        assert_eq!(smw.release_year, Some(1990));
+        assert_eq!(smw.players.as_deref(), Some("2"));
+        assert_eq!(smw.region.as_deref(), Some("USA"));
+
+        let sf2 = &records[1];
+        assert_eq!(sf2.title.as_deref(), Some("Street Fighter II \"Turbo\" (Japan)"));
+        assert_eq!(sf2.crc32.as_deref(), Some("2F4D6AE8"));
+        assert_eq!(sf2.serial, None);
+        assert_eq!(sf2.release_year, None);
+    }
+
+    #[test]
+    fn rejects_malformed_dat() {
+        assert!(parse_dat("game ( name \"Unterminated )").is_err());
+        assert!(parse_dat("game ( ( name \"Nested\" ) )").is_err());
+        // A missing closing parenthesis still yields what was read
+        assert_eq!(parse_dat("game ( name \"Truncated\"").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn normalizes_titles_for_matching() {
+        assert_eq!(
+            normalize_title("Legend of Zelda, The - A Link to the Past (USA)"),
+            normalize_title("The Legend of Zelda: A Link to the Past")
+        );
+        assert_eq!(normalize_title("Sonic The Hedgehog (USA, Europe) [!]"), "sonichedgehog");
+        assert_eq!(normalize_title("(Proto)"), "");
+    }
+}
@@ -1,7 +1,51 @@
 pub mod auth_service;
+pub mod bios_service;
+pub mod cheat_service;
+pub mod collection_service;
+pub mod console_service;
+pub mod document_service;
+pub mod duplicate_service;
+pub mod emulatorjs_service;
 pub mod game_service;
+pub mod gamelist_service;
+pub mod input_service;
+pub mod launchbox_service;
+pub mod library_service;
+pub mod media_service;
+pub mod metadata_service;
+pub mod patch_service;
+pub mod retroarch_service;
+pub mod review_service;
 pub mod save_service;
+pub mod settings_service;
+pub mod shader_service;
+pub mod smart_collection_service;
+pub mod tag_service;
+pub mod title_service;
+pub mod visibility_service;
 
 pub use auth_service::*;
+pub use bios_service::*;
+pub use cheat_service::*;
+pub use collection_service::*;
+pub use console_service::*;
+pub use document_service::*;
+pub use duplicate_service::*;
+pub use emulatorjs_service::*;
 pub use game_service::*;
+pub use gamelist_service::*;
+pub use input_service::*;
+pub use launchbox_service::*;
+pub use library_service::*;
+pub use media_service::*;
+pub use metadata_service::*;
+pub use patch_service::*;
+pub use retroarch_service::*;
+pub use review_service::*;
 pub use save_service::*;
+pub use settings_service::*;
+pub use shader_service::*;
+pub use smart_collection_service::*;
+pub use tag_service::*;
+pub use title_service::*;
+pub use visibility_service::*;
@@ -0,0 +1,788 @@
+use crate::models::{Game, GamePatch, RomPatch, RomPatchUpload, UpdateRomPatchRequest};
+use crate::utils::ApiError;
+use sqlx::PgPool;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const MAX_PATCH_SIZE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Largest output a BPS/UPS patch may declare, so a bad header can't trigger a huge allocation
+const MAX_PATCHED_ROM_BYTES: u64 = 1024 * 1024 * 1024;
+
+const CACHE_DIR: &str = "cache";
+
+/// Containers whose bytes aren't the ROM itself, so patches can't be applied to them
+const UNPATCHABLE_ROM_EXTENSIONS: [&str; 6] = ["zip", "7z", "rar", "gz", "chd", "cso"];
+
+/// Stores IPS/BPS/UPS patches and builds (and caches) the patched ROM images
+#[derive(Clone)]
+pub struct PatchService {
+    patch_storage_path: String,
+}
+
+impl PatchService {
+    pub fn new(patch_storage_path: String) -> Self {
+        Self { patch_storage_path }
+    }
+
+    pub async fn get_patches(&self, pool: &PgPool, game_id: &Uuid) -> Result<Vec<RomPatch>, ApiError> {
+        let patches = sqlx::query_as::<_, RomPatch>(
+            "SELECT * FROM rom_patches WHERE game_id = $1 ORDER BY name"
+        )
+        .bind(game_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(patches)
+    }
+
+    pub async fn get_patch(&self, pool: &PgPool, game_id: &Uuid, patch_id: &Uuid) -> Result<RomPatch, ApiError> {
+        let patch = sqlx::query_as::<_, RomPatch>(
+            "SELECT * FROM rom_patches WHERE id = $1 AND game_id = $2"
+        )
+        .bind(patch_id)
+        .bind(game_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(patch)
+    }
+
+    /// Stores a patch for `game` after applying it once to the base ROM, which rejects patches
+    /// made for a different dump and leaves the patched image in the cache.
+    pub async fn create_patch(
+        &self,
+        pool: &PgPool,
+        game: &Game,
+        rom_path: &str,
+        base_crc32: &str,
+        upload: RomPatchUpload,
+        user_id: &Uuid,
+    ) -> Result<RomPatch, ApiError> {
+        let RomPatchUpload { request, data } = upload;
+        let name = request.name.trim();
+        if name.is_empty() || name.len() > 255 {
+            return Err(ApiError::ValidationError("Patch name must be 1-255 characters".to_string()));
+        }
+        if data.len() > MAX_PATCH_SIZE_BYTES {
+            return Err(ApiError::BadRequest("Patch exceeds maximum size".to_string()));
+        }
+        check_patchable(&game.rom_filename)?;
+        self.ensure_unique_name(pool, &game.id, name, None).await?;
+
+        let info = read_patch_info(&data)?;
+        check_source_crc(&info, base_crc32, &game.rom_filename)?;
+
+        let id = Uuid::new_v4();
+        let filename = format!("{}/{}.{}", game.id, id, info.format);
+        let patch_path = Path::new(&self.patch_storage_path).join(&filename);
+        let cache_path = self.cache_path(&id, base_crc32, &game.rom_filename);
+        let written = [patch_path.clone(), cache_path.clone()];
+        let patch_crc32 = format!("{:08X}", info.patch_crc32);
+        let file_size = data.len() as i64;
+        let rom_path = PathBuf::from(rom_path);
+        let format = info.format;
+
+        let target_crc32 = tokio::task::spawn_blocking(move || -> Result<u32, ApiError> {
+            let source = fs::read(&rom_path)
+                .map_err(|e| ApiError::NotFound(format!("ROM file not readable: {}", e)))?;
+            let target = apply_patch(&source, &data)?;
+            write_file(&patch_path, &data)?;
+            write_file(&cache_path, &target)?;
+            Ok(crc32fast::hash(&target))
+        })
+        .await
+        .map_err(|e| ApiError::InternalServerError(e.to_string()))??;
+
+        let patch = sqlx::query_as::<_, RomPatch>(
+            "INSERT INTO rom_patches
+                (id, game_id, name, description, format, filename, file_size,
+                 patch_crc32, source_crc32, target_crc32, created_by)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             RETURNING *"
+        )
+        .bind(id)
+        .bind(game.id)
+        .bind(name)
+        .bind(request.description.as_deref().map(str::trim).filter(|d| !d.is_empty()))
+        .bind(format)
+        .bind(&filename)
+        .bind(file_size)
+        .bind(&patch_crc32)
+        .bind(info.source_crc32.map(|crc| format!("{:08X}", crc)))
+        .bind(format!("{:08X}", target_crc32))
+        .bind(user_id)
+        .fetch_one(pool)
+        .await;
+
+        // Don't leave the files behind when the insert fails, e.g. on a concurrent duplicate name
+        if patch.is_err() {
+            for path in &written {
+                fs::remove_file(path).ok();
+            }
+        }
+
+        patch.map_err(ApiError::from)
+    }
+
+    pub async fn update_patch(
+        &self,
+        pool: &PgPool,
+        patch: &RomPatch,
+        request: &UpdateRomPatchRequest,
+    ) -> Result<RomPatch, ApiError> {
+        let name = request.name.as_deref().map(str::trim);
+        if let Some(name) = name {
+            if name.is_empty() {
+                return Err(ApiError::ValidationError("Patch name must not be empty".to_string()));
+            }
+            self.ensure_unique_name(pool, &patch.game_id, name, Some(&patch.id)).await?;
+        }
+
+        let updated = sqlx::query_as::<_, RomPatch>(
+            "UPDATE rom_patches SET
+                name = COALESCE($2, name),
+                description = COALESCE($3, description)
+             WHERE id = $1
+             RETURNING *"
+        )
+        .bind(patch.id)
+        .bind(name)
+        .bind(request.description.as_deref().map(str::trim))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(updated)
+    }
+
+    /// Deletes the patch, its file and any cached patched images
+    pub async fn delete_patch(&self, pool: &PgPool, patch: &RomPatch) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM rom_patches WHERE id = $1")
+            .bind(patch.id)
+            .execute(pool)
+            .await?;
+
+        fs::remove_file(Path::new(&self.patch_storage_path).join(&patch.filename)).ok();
+
+        let prefix = format!("{}_", patch.id);
+        if let Ok(entries) = fs::read_dir(Path::new(&self.patch_storage_path).join(CACHE_DIR)) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                    fs::remove_file(entry.path()).ok();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the path of the base ROM with `patch` applied, building it on first use.
+    /// Cached images are keyed by the base ROM's CRC, so replacing the ROM rebuilds them.
+    pub async fn patched_rom_path(
+        &self,
+        game: &Game,
+        patch: &RomPatch,
+        rom_path: &str,
+        base_crc32: &str,
+    ) -> Result<PathBuf, ApiError> {
+        check_patchable(&game.rom_filename)?;
+        if let Some(expected) = patch.source_crc32.as_deref().filter(|crc| *crc != base_crc32) {
+            return Err(ApiError::BadRequest(format!(
+                "Patch '{}' expects a base ROM with CRC32 {}, but the ROM now has {}",
+                patch.name, expected, base_crc32
+            )));
+        }
+
+        let cache_path = self.cache_path(&patch.id, base_crc32, &game.rom_filename);
+        if cache_path.exists() {
+            return Ok(cache_path);
+        }
+
+        let rom_path = PathBuf::from(rom_path);
+        let patch_path = Path::new(&self.patch_storage_path).join(&patch.filename);
+        let output = cache_path.clone();
+        tokio::task::spawn_blocking(move || -> Result<(), ApiError> {
+            let source = fs::read(&rom_path)
+                .map_err(|e| ApiError::NotFound(format!("ROM file not readable: {}", e)))?;
+            let data = fs::read(&patch_path)
+                .map_err(|e| ApiError::NotFound(format!("Patch file not readable: {}", e)))?;
+            write_file(&output, &apply_patch(&source, &data)?)
+        })
+        .await
+        .map_err(|e| ApiError::InternalServerError(e.to_string()))??;
+
+        Ok(cache_path)
+    }
+
+    async fn ensure_unique_name(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        name: &str,
+        exclude: Option<&Uuid>,
+    ) -> Result<(), ApiError> {
+        let exists: Option<bool> = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM rom_patches
+                           WHERE game_id = $1 AND name = $2 AND ($3::UUID IS NULL OR id <> $3))"
+        )
+        .bind(game_id)
+        .bind(name)
+        .bind(exclude)
+        .fetch_one(pool)
+        .await?;
+
+        if exists == Some(true) {
+            return Err(ApiError::BadRequest(format!(
+                "A patch named '{}' already exists for this game",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn cache_path(&self, patch_id: &Uuid, base_crc32: &str, rom_filename: &str) -> PathBuf {
+        let extension = Path::new(rom_filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("bin");
+
+        Path::new(&self.patch_storage_path)
+            .join(CACHE_DIR)
+            .join(format!("{}_{}.{}", patch_id, base_crc32, extension))
+    }
+}
+
+/// Fills `Game::patches` for a batch of games with a single query
+pub async fn load_game_patches(pool: &PgPool, games: &mut [Game]) -> Result<(), ApiError> {
+    if games.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = games.iter().map(|g| g.id).collect();
+    let patches = sqlx::query_as::<_, GamePatch>(
+        "SELECT game_id, id, name, format FROM rom_patches WHERE game_id = ANY($1) ORDER BY name"
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    for game in games.iter_mut() {
+        game.patches = patches.iter().filter(|p| p.game_id == game.id).cloned().collect();
+    }
+
+    Ok(())
+}
+
+fn check_patchable(rom_filename: &str) -> Result<(), ApiError> {
+    let extension = Path::new(rom_filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    if UNPATCHABLE_ROM_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(ApiError::BadRequest(format!(
+            "Patches can't be applied to .{} files, the ROM must be stored uncompressed",
+            extension
+        )));
+    }
+
+    Ok(())
+}
+
+fn check_source_crc(info: &PatchInfo, base_crc32: &str, rom_filename: &str) -> Result<(), ApiError> {
+    match info.source_crc32.map(|crc| format!("{:08X}", crc)) {
+        Some(expected) if expected != base_crc32 => Err(ApiError::ValidationError(format!(
+            "{} patch expects a base ROM with CRC32 {}, but {} has {}",
+            info.format.to_uppercase(),
+            expected,
+            rom_filename,
+            base_crc32
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Writes through a temporary file so concurrent readers never see a partial image
+fn write_file(path: &Path, data: &[u8]) -> Result<(), ApiError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to create patch directory: {}", e)))?;
+    }
+
+    let temp = path.with_file_name(format!(".{}.tmp", Uuid::new_v4()));
+    fs::write(&temp, data)
+        .and_then(|_| fs::rename(&temp, path))
+        .map_err(|e| {
+            fs::remove_file(&temp).ok();
+            ApiError::InternalServerError(format!("Failed to write patch file: {}", e))
+        })
+}
+
+/// Format and checksums read from a patch header and footer
+struct PatchInfo {
+    format: &'static str,
+    /// CRC32 identifying the patch. BPS/UPS use their footer CRC, since hashing a file that
+    /// ends in its own CRC always gives the same value.
+    patch_crc32: u32,
+    source_crc32: Option<u32>,
+    target_crc32: Option<u32>,
+}
+
+/// Identifies the patch format and, for BPS/UPS, checks the patch's own CRC
+fn read_patch_info(data: &[u8]) -> Result<PatchInfo, ApiError> {
+    if data.starts_with(b"PATCH") {
+        return Ok(PatchInfo {
+            format: "ips",
+            patch_crc32: crc32fast::hash(data),
+            source_crc32: None,
+            target_crc32: None,
+        });
+    }
+
+    let format = if data.starts_with(b"BPS1") {
+        "bps"
+    } else if data.starts_with(b"UPS1") {
+        "ups"
+    } else {
+        return Err(ApiError::ValidationError(
+            "Unrecognised patch format, expected an IPS, BPS or UPS file".to_string(),
+        ));
+    };
+
+    // Footer: source CRC, target CRC, then the CRC of everything before it
+    if data.len() < 16 {
+        return Err(corrupt(format));
+    }
+    let footer = &data[data.len() - 12..];
+    let crc_at = |i: usize| u32::from_le_bytes([footer[i], footer[i + 1], footer[i + 2], footer[i + 3]]);
+
+    if crc32fast::hash(&data[..data.len() - 4]) != crc_at(8) {
+        return Err(corrupt(format));
+    }
+
+    Ok(PatchInfo {
+        format,
+        patch_crc32: crc_at(8),
+        source_crc32: Some(crc_at(0)),
+        target_crc32: Some(crc_at(4)),
+    })
+}
+
+/// Applies an IPS, BPS or UPS patch. BPS and UPS results are verified against their target CRC.
+fn apply_patch(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let info = read_patch_info(patch)?;
+    if let Some(expected) = info.source_crc32 {
+        let actual = crc32fast::hash(source);
+        if actual != expected {
+            return Err(ApiError::BadRequest(format!(
+                "{} patch expects a base ROM with CRC32 {:08X}, got {:08X}",
+                info.format.to_uppercase(),
+                expected,
+                actual
+            )));
+        }
+    }
+
+    let target = match info.format {
+        "ips" => apply_ips(source, patch)?,
+        "bps" => apply_bps(source, patch)?,
+        _ => apply_ups(source, patch)?,
+    };
+
+    if let Some(expected) = info.target_crc32 {
+        if crc32fast::hash(&target) != expected {
+            return Err(ApiError::BadRequest(format!(
+                "Patched ROM failed the {} target checksum",
+                info.format.to_uppercase()
+            )));
+        }
+    }
+
+    Ok(target)
+}
+
+fn corrupt(format: &str) -> ApiError {
+    ApiError::ValidationError(format!("Corrupt {} patch", format.to_uppercase()))
+}
+
+/// Bounds-checked cursor over the patch body
+struct PatchReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    format: &'static str,
+}
+
+impl<'a> PatchReader<'a> {
+    /// `data` excludes any footer, so reading past the body is an error
+    fn new(data: &'a [u8], format: &'static str, magic_len: usize) -> Self {
+        Self {
+            data,
+            position: magic_len,
+            format,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.position)
+    }
+
+    fn byte(&mut self) -> Result<u8, ApiError> {
+        let byte = *self.data.get(self.position).ok_or_else(|| corrupt(self.format))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], ApiError> {
+        let end = self.position.checked_add(count).ok_or_else(|| corrupt(self.format))?;
+        let bytes = self.data.get(self.position..end).ok_or_else(|| corrupt(self.format))?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn be(&mut self, count: usize) -> Result<usize, ApiError> {
+        Ok(self.bytes(count)?.iter().fold(0, |value, b| value << 8 | *b as usize))
+    }
+
+    /// BPS/UPS variable-length integer
+    fn number(&mut self) -> Result<u64, ApiError> {
+        let mut value: u64 = 0;
+        let mut shift: u64 = 1;
+
+        loop {
+            let byte = self.byte()?;
+            value = (byte as u64 & 0x7f)
+                .checked_mul(shift)
+                .and_then(|v| v.checked_add(value))
+                .ok_or_else(|| corrupt(self.format))?;
+            if byte & 0x80 != 0 {
+                return Ok(value);
+            }
+            shift = shift.checked_mul(128).ok_or_else(|| corrupt(self.format))?;
+            value = value.checked_add(shift).ok_or_else(|| corrupt(self.format))?;
+        }
+    }
+
+    fn size(&mut self) -> Result<usize, ApiError> {
+        usize::try_from(self.number()?).map_err(|_| corrupt(self.format))
+    }
+}
+
+fn check_sizes(format: &str, source: &[u8], source_size: usize, target_size: usize) -> Result<(), ApiError> {
+    if source.len() != source_size {
+        return Err(ApiError::BadRequest(format!(
+            "{} patch expects a {} byte ROM, got {} bytes",
+            format.to_uppercase(),
+            source_size,
+            source.len()
+        )));
+    }
+    if target_size as u64 > MAX_PATCHED_ROM_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "{} patch output exceeds the maximum ROM size",
+            format.to_uppercase()
+        )));
+    }
+
+    Ok(())
+}
+
+/// IPS: `PATCH`, then records of a 24-bit offset and 16-bit length (0 means an RLE run),
+/// ending with `EOF` and an optional 24-bit truncation size
+fn apply_ips(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let mut reader = PatchReader::new(patch, "ips", 5);
+    let mut target = source.to_vec();
+
+    loop {
+        if patch.get(reader.position..reader.position + 3) == Some(b"EOF".as_slice()) {
+            reader.bytes(3)?;
+            if reader.remaining() >= 3 {
+                target.truncate(reader.be(3)?);
+            }
+            return Ok(target);
+        }
+
+        let offset = reader.be(3)?;
+        let length = reader.be(2)?;
+
+        if length == 0 {
+            let run = reader.be(2)?;
+            let value = reader.byte()?;
+            if target.len() < offset + run {
+                target.resize(offset + run, 0);
+            }
+            target[offset..offset + run].fill(value);
+        } else {
+            let data = reader.bytes(length)?;
+            if target.len() < offset + length {
+                target.resize(offset + length, 0);
+            }
+            target[offset..offset + length].copy_from_slice(data);
+        }
+    }
+}
+
+/// UPS: sizes, then runs of (relative offset, XOR bytes up to a zero terminator)
+fn apply_ups(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let mut reader = PatchReader::new(&patch[..patch.len() - 12], "ups", 4);
+    let source_size = reader.size()?;
+    let target_size = reader.size()?;
+    check_sizes("ups", source, source_size, target_size)?;
+
+    let mut target = vec![0u8; target_size];
+    let shared = source.len().min(target_size);
+    target[..shared].copy_from_slice(&source[..shared]);
+
+    let mut position: usize = 0;
+    while !reader.at_end() {
+        position = position.checked_add(reader.size()?).ok_or_else(|| corrupt("ups"))?;
+
+        loop {
+            let xor = reader.byte()?;
+            if xor == 0 {
+                break;
+            }
+            if let Some(byte) = target.get_mut(position) {
+                *byte ^= xor;
+            }
+            position = position.checked_add(1).ok_or_else(|| corrupt("ups"))?;
+        }
+        position = position.checked_add(1).ok_or_else(|| corrupt("ups"))?;
+    }
+
+    Ok(target)
+}
+
+/// BPS: sizes and metadata, then source/target read and copy actions
+fn apply_bps(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let mut reader = PatchReader::new(&patch[..patch.len() - 12], "bps", 4);
+    let source_size = reader.size()?;
+    let target_size = reader.size()?;
+    let metadata_size = reader.size()?;
+    reader.bytes(metadata_size)?;
+    check_sizes("bps", source, source_size, target_size)?;
+
+    let mut target = Vec::with_capacity(target_size);
+    let mut source_offset: i64 = 0;
+    let mut target_offset: i64 = 0;
+
+    while !reader.at_end() {
+        let action = reader.number()?;
+        let length = usize::try_from((action >> 2) + 1).map_err(|_| corrupt("bps"))?;
+        if target.len() + length > target_size {
+            return Err(corrupt("bps"));
+        }
+
+        match action & 3 {
+            // SourceRead: copy from the same position in the source
+            0 => {
+                let start = target.len();
+                let data = source.get(start..start + length).ok_or_else(|| corrupt("bps"))?;
+                target.extend_from_slice(data);
+            }
+            // TargetRead: literal bytes from the patch
+            1 => target.extend_from_slice(reader.bytes(length)?),
+            // SourceCopy: copy from a relative position in the source
+            2 => {
+                source_offset += relative_offset(reader.number()?);
+                let start = usize::try_from(source_offset).map_err(|_| corrupt("bps"))?;
+                let data = source.get(start..start + length).ok_or_else(|| corrupt("bps"))?;
+                target.extend_from_slice(data);
+                source_offset += length as i64;
+            }
+            // TargetCopy: repeat already written output, byte by byte since ranges may overlap
+            _ => {
+                target_offset += relative_offset(reader.number()?);
+                let start = usize::try_from(target_offset).map_err(|_| corrupt("bps"))?;
+                if start >= target.len() {
+                    return Err(corrupt("bps"));
+                }
+                for i in 0..length {
+                    target.push(target[start + i]);
+                }
+                target_offset += length as i64;
+            }
+        }
+    }
+
+    if target.len() != target_size {
+        return Err(corrupt("bps"));
+    }
+
+    Ok(target)
+}
+
+fn relative_offset(value: u64) -> i64 {
+    let magnitude = (value >> 1) as i64;
+    if value & 1 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// BPS/UPS variable-length integer, the inverse of `PatchReader::number`
+    fn push_number(out: &mut Vec<u8>, mut value: u64) {
+        loop {
+            let low = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(0x80 | low);
+                return;
+            }
+            out.push(low);
+            value -= 1;
+        }
+    }
+
+    fn with_footer(mut patch: Vec<u8>, source: &[u8], target: &[u8]) -> Vec<u8> {
+        patch.extend_from_slice(&crc32fast::hash(source).to_le_bytes());
+        patch.extend_from_slice(&crc32fast::hash(target).to_le_bytes());
+        patch.extend_from_slice(&crc32fast::hash(&patch).to_le_bytes());
+        patch
+    }
+
+    fn bps_patch(source: &[u8], target: &[u8]) -> Vec<u8> {
+        let mut patch = b"BPS1".to_vec();
+        push_number(&mut patch, source.len() as u64);
+        push_number(&mut patch, target.len() as u64);
+        push_number(&mut patch, 0);
+        // SourceRead "Hello "
+        push_number(&mut patch, 5 << 2);
+        // TargetRead "Rust"
+        push_number(&mut patch, (3 << 2) | 1);
+        patch.extend_from_slice(b"Rust");
+        // SourceCopy "!" from source offset 11
+        push_number(&mut patch, 2);
+        push_number(&mut patch, 11 << 1);
+        // TargetCopy "Rust" from target offset 6
+        push_number(&mut patch, (3 << 2) | 3);
+        push_number(&mut patch, 6 << 1);
+        with_footer(patch, source, target)
+    }
+
+    fn ups_patch(source: &[u8], target: &[u8]) -> Vec<u8> {
+        let mut patch = b"UPS1".to_vec();
+        push_number(&mut patch, source.len() as u64);
+        push_number(&mut patch, target.len() as u64);
+        // XOR bytes 1-2, then skip one byte past the terminator and XOR byte 5
+        push_number(&mut patch, 1);
+        patch.extend_from_slice(&[0x01 ^ 0x11, 0x02 ^ 0x22, 0]);
+        push_number(&mut patch, 1);
+        patch.extend_from_slice(&[0x05 ^ 0x55, 0]);
+        with_footer(patch, source, target)
+    }
+
+    #[test]
+    fn applies_ips_records_rle_and_truncation() {
+        let source = [0u8; 8];
+        let mut patch = b"PATCH".to_vec();
+        // Plain record: 2 bytes at offset 2
+        patch.extend_from_slice(&[0x00, 0x00, 0x02, 0x00, 0x02, 0xAA, 0xBB]);
+        // RLE record: 4 x 0xCC at offset 6, growing the ROM to 10 bytes
+        patch.extend_from_slice(&[0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x04, 0xCC]);
+        patch.extend_from_slice(b"EOF");
+        let target = apply_patch(&source, &patch).unwrap();
+        assert_eq!(target, [0, 0, 0xAA, 0xBB, 0, 0, 0xCC, 0xCC, 0xCC, 0xCC]);
+
+        // Truncation extension: a 24-bit size after EOF
+        patch.extend_from_slice(&[0x00, 0x00, 0x09]);
+        let target = apply_patch(&source, &patch).unwrap();
+        assert_eq!(target, [0, 0, 0xAA, 0xBB, 0, 0, 0xCC, 0xCC, 0xCC]);
+    }
+
+    #[test]
+    fn rejects_truncated_ips_record() {
+        let patch = [b"PATCH".as_slice(), &[0x00, 0x00, 0x02, 0x00, 0x04, 0xAA]].concat();
+        assert!(apply_patch(&[0u8; 8], &patch).is_err());
+    }
+
+    #[test]
+    fn applies_bps_actions() {
+        let source = b"Hello World!";
+        let target = b"Hello Rust!Rust";
+        let patch = bps_patch(source, target);
+
+        let info = read_patch_info(&patch).unwrap();
+        assert_eq!(info.format, "bps");
+        assert_eq!(info.source_crc32, Some(crc32fast::hash(source)));
+        assert_eq!(info.target_crc32, Some(crc32fast::hash(target)));
+        assert_eq!(apply_patch(source, &patch).unwrap(), target);
+    }
+
+    #[test]
+    fn applies_ups_xor_runs() {
+        let source = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05];
+        let target = [0x00, 0x11, 0x22, 0x03, 0x04, 0x55, 0x00];
+        let patch = ups_patch(&source, &target);
+
+        assert_eq!(read_patch_info(&patch).unwrap().format, "ups");
+        assert_eq!(apply_patch(&source, &patch).unwrap(), target);
+    }
+
+    #[test]
+    fn rejects_ups_offsets_past_the_address_space() {
+        let source = [0u8; 4];
+        let mut patch = b"UPS1".to_vec();
+        push_number(&mut patch, 4);
+        push_number(&mut patch, 4);
+        // Skips to the last address, whose terminator then steps past it
+        push_number(&mut patch, usize::MAX as u64);
+        patch.push(0);
+        let patch = with_footer(patch, &source, &source);
+
+        assert!(matches!(
+            apply_patch(&source, &patch),
+            Err(ApiError::ValidationError(message)) if message == "Corrupt UPS patch"
+        ));
+    }
+
+    #[test]
+    fn reads_patch_formats() {
+        let ips = b"PATCHEOF";
+        let info = read_patch_info(ips).unwrap();
+        assert_eq!(info.format, "ips");
+        assert_eq!(info.patch_crc32, crc32fast::hash(ips));
+        assert_eq!(info.source_crc32, None);
+
+        assert!(read_patch_info(b"NOT A PATCH").is_err());
+        assert!(read_patch_info(b"BPS1").is_err());
+    }
+
+    #[test]
+    fn rejects_corrupt_footer() {
+        let mut patch = bps_patch(b"Hello World!", b"Hello Rust!Rust");
+        let last = patch.len() - 1;
+        patch[last] ^= 0xFF;
+        assert!(read_patch_info(&patch).is_err());
+
+        let mut patch = ups_patch(&[0; 6], &[0; 7]);
+        patch[5] ^= 0xFF;
+        assert!(read_patch_info(&patch).is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_source_crc() {
+        let patch = bps_patch(b"Hello World!", b"Hello Rust!Rust");
+        assert!(matches!(
+            apply_patch(b"Hello Earth!", &patch),
+            Err(ApiError::BadRequest(_))
+        ));
+
+        let info = read_patch_info(&patch).unwrap();
+        let wrong = format!("{:08X}", crc32fast::hash(b"Hello Earth!"));
+        assert!(check_source_crc(&info, &wrong, "game.sfc").is_err());
+        let right = format!("{:08X}", crc32fast::hash(b"Hello World!"));
+        assert!(check_source_crc(&info, &right, "game.sfc").is_ok());
+    }
+}
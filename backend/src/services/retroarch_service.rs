@@ -0,0 +1,119 @@
+use crate::models::{
+    Console, RetroArchExportParams, RetroArchPlaylist, RetroArchPlaylistExport, RetroArchPlaylistItem,
+};
+use crate::services::GameService;
+use crate::utils::ApiError;
+use sqlx::PgPool;
+
+const DETECT: &str = "DETECT";
+
+/// libretro core and database for each `Console::emulator_core`,
+/// as (emulator_core, core library, core display name, libretro database name)
+const LIBRETRO_CORES: &[(&str, &str, &str, &str)] = &[
+    ("nes", "fceumm_libretro", "Nintendo - NES / Famicom (FCEUmm)", "Nintendo - Nintendo Entertainment System"),
+    ("snes", "snes9x_libretro", "Nintendo - SNES / SFC (Snes9x - Current)", "Nintendo - Super Nintendo Entertainment System"),
+    ("gb", "gambatte_libretro", "Nintendo - Game Boy / Color (Gambatte)", "Nintendo - Game Boy"),
+    ("gbc", "gambatte_libretro", "Nintendo - Game Boy / Color (Gambatte)", "Nintendo - Game Boy Color"),
+    ("gba", "mgba_libretro", "Nintendo - Game Boy Advance (mGBA)", "Nintendo - Game Boy Advance"),
+    ("segaMD", "genesis_plus_gx_libretro", "Sega - MS/GG/MD/CD (Genesis Plus GX)", "Sega - Mega Drive - Genesis"),
+    ("n64", "mupen64plus_next_libretro", "Nintendo - Nintendo 64 (Mupen64Plus-Next)", "Nintendo - Nintendo 64"),
+    ("psx", "pcsx_rearmed_libretro", "Sony - PlayStation (PCSX ReARMed)", "Sony - PlayStation"),
+    ("dreamcast", "flycast_libretro", "Sega - Dreamcast/NAOMI (Flycast)", "Sega - Dreamcast"),
+    ("ps2", "pcsx2_libretro", "Sony - PlayStation 2 (LRPS2)", "Sony - PlayStation 2"),
+];
+
+/// Builds RetroArch playlists from the game library
+#[derive(Clone)]
+pub struct RetroArchService {
+    rom_root: String,
+}
+
+impl RetroArchService {
+    pub fn new(rom_root: String) -> Self {
+        Self { rom_root }
+    }
+
+    pub async fn build_playlist(
+        &self,
+        pool: &PgPool,
+        console: &Console,
+        game_service: &GameService,
+        params: &RetroArchExportParams,
+    ) -> Result<RetroArchPlaylistExport, ApiError> {
+        let core = LIBRETRO_CORES
+            .iter()
+            .find(|(emulator_core, ..)| *emulator_core == console.emulator_core);
+
+        let db_name = core
+            .map(|(_, _, _, db)| db.to_string())
+            .unwrap_or_else(|| console.name.clone());
+        let playlist_name = format!("{}.lpl", db_name);
+
+        let (core_path, core_name) = match (core, &params.core_dir) {
+            (Some((_, library, name, _)), Some(core_dir)) => {
+                let extension = params.core_extension.as_deref().unwrap_or(".so");
+                let library = format!("{}{}", library, extension);
+                (join_path(core_dir, &[&library]), name.to_string())
+            }
+            _ => (DETECT.to_string(), DETECT.to_string()),
+        };
+
+        let rom_root = params.rom_root.as_deref().unwrap_or(&self.rom_root);
+        let games = game_service.get_console_games(pool, &console.id).await?;
+
+        let mut items = Vec::with_capacity(games.len());
+        for game in &games {
+            // A missing ROM file should not break the whole playlist
+            let crc32 = match game_service.ensure_rom_crc32(pool, game).await {
+                Ok(crc) => format!("{}|crc", crc),
+                Err(e) => {
+                    log::warn!("No CRC32 for {}: {}", game.rom_filename, e);
+                    DETECT.to_string()
+                }
+            };
+
+            items.push(RetroArchPlaylistItem {
+                path: join_path(rom_root, &[&console.id, &game.rom_filename]),
+                label: game.title.clone(),
+                core_path: core_path.clone(),
+                core_name: core_name.clone(),
+                crc32,
+                db_name: playlist_name.clone(),
+            });
+        }
+
+        let (default_core_path, default_core_name) = if core_path == DETECT {
+            (String::new(), String::new())
+        } else {
+            (core_path.clone(), core_name.clone())
+        };
+
+        Ok(RetroArchPlaylistExport {
+            console_id: console.id.clone(),
+            filename: playlist_name,
+            playlist: RetroArchPlaylist {
+                version: "1.5".to_string(),
+                default_core_path,
+                default_core_name,
+                label_display_mode: 0,
+                right_thumbnail_mode: 0,
+                left_thumbnail_mode: 0,
+                sort_mode: 0,
+                items,
+            },
+        })
+    }
+}
+
+/// Joins path segments using the separator of `root`, so Windows ROM roots keep backslashes
+fn join_path(root: &str, segments: &[&str]) -> String {
+    let separator = if root.contains('\\') && !root.contains('/') { '\\' } else { '/' };
+    let mut path = root.trim_end_matches(['/', '\\']).to_string();
+
+    for segment in segments {
+        path.push(separator);
+        path.push_str(segment);
+    }
+
+    path
+}
@@ -0,0 +1,303 @@
+use crate::models::{
+    GameReview, GameReviewWithUser, ProfileUser, StatusCount, UpdatePlayStatusRequest, UpsertReviewRequest,
+    UserActivity, UserGameStatus, UserProfile, PLAY_STATUSES, RATING_SCALES,
+};
+use crate::services::game_visible_to;
+use crate::utils::ApiError;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+const PROFILE_ACTIVITY_LIMIT: i64 = 50;
+
+/// Ratings, reviews, play status and the profile activity feed built from status changes
+#[derive(Clone, Default)]
+pub struct ReviewService;
+
+impl ReviewService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn get_game_reviews(&self, pool: &PgPool, game_id: &Uuid) -> Result<Vec<GameReviewWithUser>, ApiError> {
+        let reviews = sqlx::query_as::<_, GameReviewWithUser>(
+            "SELECT r.*, u.username FROM game_reviews r
+             JOIN users u ON u.id = r.user_id
+             WHERE r.game_id = $1
+             ORDER BY r.updated_at DESC"
+        )
+        .bind(game_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(reviews)
+    }
+
+    /// Creates or replaces the user's review and refreshes the game's aggregate rating
+    pub async fn upsert_review(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        game_id: &Uuid,
+        request: &UpsertReviewRequest,
+    ) -> Result<GameReview, ApiError> {
+        let scale = request.scale.unwrap_or(10);
+        if !RATING_SCALES.contains(&scale) {
+            return Err(ApiError::ValidationError("Rating scale must be 5 or 10".to_string()));
+        }
+
+        let rating = match request.rating {
+            Some(rating) if rating > scale => {
+                return Err(ApiError::ValidationError(format!(
+                    "Rating must be between 1 and {}",
+                    scale
+                )))
+            }
+            Some(rating) => Some(rating * (10 / scale)),
+            None => None,
+        };
+        let review = request
+            .review
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty());
+
+        if rating.is_none() && review.is_none() {
+            return Err(ApiError::ValidationError("A rating or review is required".to_string()));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let saved = sqlx::query_as::<_, GameReview>(
+            "INSERT INTO game_reviews (user_id, game_id, rating, review)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id, game_id) DO UPDATE SET
+                rating = EXCLUDED.rating,
+                review = EXCLUDED.review,
+                updated_at = NOW()
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(rating)
+        .bind(review)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::refresh_rating(&mut tx, game_id).await?;
+        tx.commit().await?;
+
+        Ok(saved)
+    }
+
+    pub async fn delete_review(&self, pool: &PgPool, user_id: &Uuid, game_id: &Uuid) -> Result<(), ApiError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM game_reviews WHERE user_id = $1 AND game_id = $2")
+            .bind(user_id)
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::refresh_rating(&mut tx, game_id).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn refresh_rating(tx: &mut Transaction<'_, Postgres>, game_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query(
+            "UPDATE games SET
+                user_rating = (SELECT AVG(rating)::REAL FROM game_reviews WHERE game_id = $1),
+                user_rating_count = (SELECT COUNT(rating) FROM game_reviews WHERE game_id = $1)
+             WHERE id = $1"
+        )
+        .bind(game_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_status(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        game_id: &Uuid,
+    ) -> Result<Option<UserGameStatus>, ApiError> {
+        let status = sqlx::query_as::<_, UserGameStatus>(
+            "SELECT * FROM user_game_status WHERE user_id = $1 AND game_id = $2"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(status)
+    }
+
+    /// Sets the play status, recording an activity entry when it changes
+    pub async fn set_status(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        game_id: &Uuid,
+        request: &UpdatePlayStatusRequest,
+    ) -> Result<UserGameStatus, ApiError> {
+        let status = request.status.as_str();
+        if !PLAY_STATUSES.contains(&status) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid status '{}', expected one of {}",
+                status,
+                PLAY_STATUSES.join(", ")
+            )));
+        }
+        if let (Some(started), Some(finished)) = (request.started_on, request.finished_on) {
+            if finished < started {
+                return Err(ApiError::ValidationError("finished_on is before started_on".to_string()));
+            }
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let old_status: Option<String> = sqlx::query_scalar(
+            "SELECT status FROM user_game_status WHERE user_id = $1 AND game_id = $2 FOR UPDATE"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let saved = sqlx::query_as::<_, UserGameStatus>(
+            "INSERT INTO user_game_status (user_id, game_id, status, started_on, finished_on)
+             VALUES ($1, $2, $3,
+                     COALESCE($4, CASE WHEN $3 = 'playing' THEN CURRENT_DATE END),
+                     COALESCE($5, CASE WHEN $3 IN ('beaten', 'completed', 'abandoned') THEN CURRENT_DATE END))
+             ON CONFLICT (user_id, game_id) DO UPDATE SET
+                status = EXCLUDED.status,
+                started_on = COALESCE($4, user_game_status.started_on, EXCLUDED.started_on),
+                finished_on = CASE
+                    WHEN EXCLUDED.status IN ('backlog', 'playing') THEN $5
+                    ELSE COALESCE($5, user_game_status.finished_on, EXCLUDED.finished_on)
+                END,
+                updated_at = NOW()
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(status)
+        .bind(request.started_on)
+        .bind(request.finished_on)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if old_status.as_deref() != Some(status) {
+            Self::record_activity(&mut tx, user_id, game_id, old_status.as_deref(), Some(status)).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(saved)
+    }
+
+    pub async fn clear_status(&self, pool: &PgPool, user_id: &Uuid, game_id: &Uuid) -> Result<(), ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let old_status: Option<String> = sqlx::query_scalar(
+            "DELETE FROM user_game_status WHERE user_id = $1 AND game_id = $2 RETURNING status"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if old_status.is_some() {
+            Self::record_activity(&mut tx, user_id, game_id, old_status.as_deref(), None).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn record_activity(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        game_id: &Uuid,
+        old_status: Option<&str>,
+        new_status: Option<&str>,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO user_activity (user_id, game_id, old_status, new_status) VALUES ($1, $2, $3, $4)"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(old_status)
+        .bind(new_status)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// `user_id`'s public profile as seen by `viewer_id`: status counts, reviews and recent
+    /// activity only cover games the viewer can see
+    pub async fn get_profile(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        viewer_id: &Uuid,
+    ) -> Result<UserProfile, ApiError> {
+        let user = sqlx::query_as::<_, ProfileUser>(
+            "SELECT id, username, avatar_url, created_at FROM users WHERE id = $1"
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        let status_counts = sqlx::query_as::<_, StatusCount>(&format!(
+            "SELECT s.status, COUNT(*) AS count FROM user_game_status s
+             JOIN games g ON g.id = s.game_id
+             WHERE s.user_id = $1 AND {}
+             GROUP BY s.status
+             ORDER BY s.status",
+            game_visible_to("$2")
+        ))
+        .bind(user_id)
+        .bind(viewer_id)
+        .fetch_all(pool)
+        .await?;
+
+        let review_count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM game_reviews r
+             JOIN games g ON g.id = r.game_id
+             WHERE r.user_id = $1 AND {}",
+            game_visible_to("$2")
+        ))
+        .bind(user_id)
+        .bind(viewer_id)
+        .fetch_one(pool)
+        .await?;
+
+        let activity = sqlx::query_as::<_, UserActivity>(&format!(
+            "SELECT a.id, a.user_id, a.game_id, g.title AS game_title, a.old_status, a.new_status, a.created_at
+             FROM user_activity a
+             JOIN games g ON g.id = a.game_id
+             WHERE a.user_id = $1 AND {}
+             ORDER BY a.created_at DESC
+             LIMIT $2",
+            game_visible_to("$3")
+        ))
+        .bind(user_id)
+        .bind(PROFILE_ACTIVITY_LIMIT)
+        .bind(viewer_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(UserProfile {
+            user,
+            status_counts,
+            review_count,
+            activity,
+        })
+    }
+}
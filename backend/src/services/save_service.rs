@@ -1,4 +1,4 @@
-use crate::models::SaveState;
+use crate::models::{NewSaveState, SaveState};
 use crate::utils::ApiError;
 use sqlx::PgPool;
 use std::fs;
@@ -20,12 +20,16 @@ impl SaveService {
         pool: &PgPool,
         user_id: &Uuid,
         game_id: &Uuid,
+        patch_id: Option<&Uuid>,
     ) -> Result<Vec<SaveState>, ApiError> {
         let saves = sqlx::query_as::<_, SaveState>(
-            "SELECT * FROM save_states WHERE user_id = $1 AND game_id = $2 ORDER BY slot"
+            "SELECT * FROM save_states
+             WHERE user_id = $1 AND game_id = $2 AND patch_id IS NOT DISTINCT FROM $3
+             ORDER BY slot"
         )
         .bind(user_id)
         .bind(game_id)
+        .bind(patch_id)
         .fetch_all(pool)
         .await?;
 
@@ -47,18 +51,45 @@ impl SaveService {
         Ok(save)
     }
 
+    /// Fetches a save state, failing with `Forbidden` unless it belongs to `user_id`
+    pub async fn get_owned_save_state(
+        &self,
+        pool: &PgPool,
+        save_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<SaveState, ApiError> {
+        let save = self.get_save_state(pool, save_id).await?;
+
+        if save.user_id != *user_id {
+            return Err(ApiError::Forbidden(
+                "Not authorized to access this save state".to_string(),
+            ));
+        }
+
+        Ok(save)
+    }
+
     pub async fn create_save_state(
         &self,
         pool: &PgPool,
         user_id: &Uuid,
-        game_id: &Uuid,
-        slot: i32,
-        save_data: Vec<u8>,
-        screenshot: Option<Vec<u8>>,
-        description: Option<String>,
+        save: NewSaveState,
     ) -> Result<SaveState, ApiError> {
-        // Generate unique filename for save data
-        let save_filename = format!("{}_{}_slot{}.sav", user_id, game_id, slot);
+        let NewSaveState {
+            game_id,
+            patch_id,
+            slot,
+            save_data,
+            screenshot,
+            description,
+        } = save;
+
+        // Generate unique filename for save data, patched variants get their own files
+        let file_stem = match patch_id {
+            Some(patch_id) => format!("{}_{}_{}_slot{}", user_id, game_id, patch_id, slot),
+            None => format!("{}_{}_slot{}", user_id, game_id, slot),
+        };
+        let save_filename = format!("{}.sav", file_stem);
         let save_path = Path::new(&self.save_storage_path).join(&save_filename);
 
         // Ensure storage directory exists
@@ -73,7 +104,7 @@ impl SaveService {
 
         // Handle screenshot if provided
         let screenshot_filename = if let Some(screenshot_data) = screenshot {
-            let screenshot_filename = format!("{}_{}_slot{}.png", user_id, game_id, slot);
+            let screenshot_filename = format!("{}.png", file_stem);
             let screenshot_path = Path::new(&self.save_storage_path).join(&screenshot_filename);
 
             fs::write(&screenshot_path, screenshot_data)
@@ -85,21 +116,26 @@ impl SaveService {
         };
 
         // Delete existing save in this slot if it exists
-        sqlx::query("DELETE FROM save_states WHERE user_id = $1 AND game_id = $2 AND slot = $3")
-            .bind(user_id)
-            .bind(game_id)
-            .bind(slot)
-            .execute(pool)
-            .await?;
+        sqlx::query(
+            "DELETE FROM save_states
+             WHERE user_id = $1 AND game_id = $2 AND patch_id IS NOT DISTINCT FROM $3 AND slot = $4"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(patch_id)
+        .bind(slot)
+        .execute(pool)
+        .await?;
 
         // Insert new save state into database
         let save = sqlx::query_as::<_, SaveState>(
-            "INSERT INTO save_states (user_id, game_id, slot, save_data_filename, screenshot_filename, description)
-             VALUES ($1, $2, $3, $4, $5, $6)
+            "INSERT INTO save_states (user_id, game_id, patch_id, slot, save_data_filename, screenshot_filename, description)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
              RETURNING *"
         )
         .bind(user_id)
         .bind(game_id)
+        .bind(patch_id)
         .bind(slot)
         .bind(&save_filename)
         .bind(&screenshot_filename)
@@ -147,6 +183,23 @@ impl SaveService {
         Ok(())
     }
 
+    /// Removes the files of every save made with a patch. The rows go with the patch itself.
+    pub async fn delete_patch_save_files(&self, pool: &PgPool, patch_id: &Uuid) -> Result<(), ApiError> {
+        let saves = sqlx::query_as::<_, SaveState>("SELECT * FROM save_states WHERE patch_id = $1")
+            .bind(patch_id)
+            .fetch_all(pool)
+            .await?;
+
+        for save in saves {
+            fs::remove_file(Path::new(&self.save_storage_path).join(&save.save_data_filename)).ok();
+            if let Some(screenshot_filename) = &save.screenshot_filename {
+                fs::remove_file(Path::new(&self.save_storage_path).join(screenshot_filename)).ok();
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn get_save_file_path(&self, filename: &str) -> String {
         Path::new(&self.save_storage_path)
             .join(filename)
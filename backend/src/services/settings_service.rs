@@ -0,0 +1,296 @@
+use crate::models::{
+    ConsoleSettingsOverride, EffectiveSettings, Game, GameEmulatorConfig, GameSettingsOverride, SettingsOverride,
+    UpdateSettingsRequest, UserSettings,
+};
+use crate::services::{check_core_options, ShaderService};
+use crate::utils::ApiError;
+use sqlx::types::Json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Global settings and their per-console and per-game overrides
+#[derive(Clone, Default)]
+pub struct SettingsService;
+
+impl SettingsService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The user's global settings, created with the defaults if they have none yet
+    pub async fn get_settings(&self, pool: &PgPool, user_id: &Uuid) -> Result<UserSettings, ApiError> {
+        let settings = sqlx::query_as::<_, UserSettings>(
+            "INSERT INTO user_settings (user_id) VALUES ($1)
+             ON CONFLICT (user_id) DO UPDATE SET user_id = EXCLUDED.user_id
+             RETURNING *"
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    pub async fn update_settings(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        request: &UpdateSettingsRequest,
+    ) -> Result<UserSettings, ApiError> {
+        if let Some(core_options) = &request.core_options {
+            check_core_options(core_options)?;
+        }
+        self.get_settings(pool, user_id).await?;
+
+        let settings = sqlx::query_as::<_, UserSettings>(
+            "UPDATE user_settings SET
+                theme = COALESCE($2, theme),
+                crt_effect = COALESCE($3, crt_effect),
+                scanline_intensity = COALESCE($4, scanline_intensity),
+                audio_volume = COALESCE($5, audio_volume),
+                shader = COALESCE($6, shader),
+                core_options = COALESCE($7, core_options),
+                updated_at = NOW()
+             WHERE user_id = $1
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(request.theme.as_deref().map(str::trim))
+        .bind(request.crt_effect)
+        .bind(request.scanline_intensity)
+        .bind(request.audio_volume)
+        .bind(request.shader.as_deref().map(str::trim))
+        .bind(request.core_options.as_ref().map(Json))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    pub async fn get_console_overrides(&self, pool: &PgPool, user_id: &Uuid) -> Result<Vec<ConsoleSettingsOverride>, ApiError> {
+        let overrides = sqlx::query_as::<_, ConsoleSettingsOverride>(
+            "SELECT * FROM user_console_settings WHERE user_id = $1 ORDER BY console_id"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(overrides)
+    }
+
+    pub async fn get_console_override(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        console_id: &str,
+    ) -> Result<Option<ConsoleSettingsOverride>, ApiError> {
+        let settings_override = sqlx::query_as::<_, ConsoleSettingsOverride>(
+            "SELECT * FROM user_console_settings WHERE user_id = $1 AND console_id = $2"
+        )
+        .bind(user_id)
+        .bind(console_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(settings_override)
+    }
+
+    pub async fn set_console_override(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        console_id: &str,
+        settings: &SettingsOverride,
+    ) -> Result<ConsoleSettingsOverride, ApiError> {
+        check_core_options(&settings.core_options)?;
+
+        let settings_override = sqlx::query_as::<_, ConsoleSettingsOverride>(
+            "INSERT INTO user_console_settings (user_id, console_id, settings)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, console_id) DO UPDATE SET
+                settings = EXCLUDED.settings,
+                updated_at = NOW()
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(console_id)
+        .bind(Json(settings))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(settings_override)
+    }
+
+    pub async fn delete_console_override(&self, pool: &PgPool, user_id: &Uuid, console_id: &str) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM user_console_settings WHERE user_id = $1 AND console_id = $2")
+            .bind(user_id)
+            .bind(console_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_game_override(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        game_id: &Uuid,
+    ) -> Result<Option<GameSettingsOverride>, ApiError> {
+        let settings_override = sqlx::query_as::<_, GameSettingsOverride>(
+            "SELECT * FROM user_game_settings WHERE user_id = $1 AND game_id = $2"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(settings_override)
+    }
+
+    pub async fn set_game_override(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        game_id: &Uuid,
+        settings: &SettingsOverride,
+    ) -> Result<GameSettingsOverride, ApiError> {
+        check_core_options(&settings.core_options)?;
+
+        let settings_override = sqlx::query_as::<_, GameSettingsOverride>(
+            "INSERT INTO user_game_settings (user_id, game_id, settings)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, game_id) DO UPDATE SET
+                settings = EXCLUDED.settings,
+                updated_at = NOW()
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(Json(settings))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(settings_override)
+    }
+
+    pub async fn delete_game_override(&self, pool: &PgPool, user_id: &Uuid, game_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM user_game_settings WHERE user_id = $1 AND game_id = $2")
+            .bind(user_id)
+            .bind(game_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Loads the user's layers for the game and resolves them over its emulator configuration.
+    /// A shader naming an uploaded preset comes with the preset and the user's parameter values,
+    /// unless the preset isn't meant for the game's console.
+    pub async fn resolve_game_settings(
+        &self,
+        pool: &PgPool,
+        shader_service: &ShaderService,
+        game: &Game,
+        emulator: &GameEmulatorConfig,
+        user_id: &Uuid,
+    ) -> Result<EffectiveSettings, ApiError> {
+        let settings = self.get_settings(pool, user_id).await?;
+        let console_overrides = self.get_console_override(pool, user_id, &game.console_id).await?;
+        let game_overrides = self.get_game_override(pool, user_id, &game.id).await?;
+
+        let mut effective = resolve_settings(
+            emulator,
+            &settings,
+            console_overrides.map(|o| o.settings.0),
+            game_overrides.map(|o| o.settings.0),
+        );
+
+        if let Some(shader) = &effective.shader {
+            if let Some(preset) = shader_service.find_preset_by_name(pool, shader).await? {
+                if preset.suits(&game.console_id) {
+                    effective.shader_preset = Some(shader_service.resolve_preset(pool, preset, user_id).await?);
+                } else {
+                    effective.shader = None;
+                }
+            }
+        }
+
+        Ok(effective)
+    }
+}
+
+/// Layers the user's global settings, console override and game override, in that order,
+/// over the shader and core options of the game's emulator configuration
+pub fn resolve_settings(
+    emulator: &GameEmulatorConfig,
+    settings: &UserSettings,
+    console_overrides: Option<SettingsOverride>,
+    game_overrides: Option<SettingsOverride>,
+) -> EffectiveSettings {
+    let mut effective = EffectiveSettings {
+        game_id: emulator.game_id,
+        console_id: emulator.console_id.clone(),
+        theme: settings.theme.clone(),
+        crt_effect: settings.crt_effect,
+        scanline_intensity: settings.scanline_intensity,
+        shader: emulator.shader.clone(),
+        shader_preset: None,
+        audio_volume: settings.audio_volume,
+        core_options: emulator.core_options.clone(),
+        sources: ["theme", "crt_effect", "scanline_intensity", "audio_volume"]
+            .into_iter()
+            .map(|field| (field.to_string(), "global"))
+            .chain(std::iter::once(("shader".to_string(), "emulator")))
+            .chain(emulator.core_options.keys().map(|name| (format!("core_options.{}", name), "emulator")))
+            .collect(),
+        console_overrides: None,
+        game_overrides: None,
+    };
+
+    if let Some(shader) = &settings.shader {
+        effective.shader = Some(shader.clone());
+        effective.sources.insert("shader".to_string(), "global");
+    }
+    for (name, value) in settings.core_options.iter() {
+        effective.core_options.insert(name.clone(), value.clone());
+        effective.sources.insert(format!("core_options.{}", name), "global");
+    }
+
+    if let Some(layer) = &console_overrides {
+        apply_override(&mut effective, layer, "console");
+    }
+    if let Some(layer) = &game_overrides {
+        apply_override(&mut effective, layer, "game");
+    }
+
+    // An empty shader is how a layer turns off the one below it
+    effective.shader = effective.shader.filter(|s| !s.is_empty());
+    effective.console_overrides = console_overrides;
+    effective.game_overrides = game_overrides;
+    effective
+}
+
+fn apply_override(effective: &mut EffectiveSettings, layer: &SettingsOverride, source: &'static str) {
+    let sources = &mut effective.sources;
+    if let Some(crt_effect) = layer.crt_effect {
+        effective.crt_effect = crt_effect;
+        sources.insert("crt_effect".to_string(), source);
+    }
+    if let Some(scanline_intensity) = layer.scanline_intensity {
+        effective.scanline_intensity = scanline_intensity;
+        sources.insert("scanline_intensity".to_string(), source);
+    }
+    if let Some(shader) = &layer.shader {
+        effective.shader = Some(shader.clone());
+        sources.insert("shader".to_string(), source);
+    }
+    if let Some(audio_volume) = layer.audio_volume {
+        effective.audio_volume = audio_volume;
+        sources.insert("audio_volume".to_string(), source);
+    }
+    for (name, value) in &layer.core_options {
+        effective.core_options.insert(name.clone(), value.clone());
+        sources.insert(format!("core_options.{}", name), source);
+    }
+}
@@ -0,0 +1,1062 @@
+use crate::models::{
+    CreateShaderPresetRequest, ResolvedShaderPreset, ShaderParameter, ShaderPass, ShaderPreset, ShaderTexture,
+    UpdateShaderPresetRequest, UserShaderParameters, SHADER_FORMATS,
+};
+use crate::utils::ApiError;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use zip::ZipArchive;
+
+/// Whole upload, archives included once extracted
+pub const MAX_SHADER_UPLOAD_BYTES: usize = 50 * 1024 * 1024;
+
+/// RetroArch's own limit on passes per preset
+const MAX_SHADER_PASSES: usize = 64;
+
+/// Nesting allowed for `#include` in pass sources
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Largest pass source once its includes are expanded
+const MAX_PASS_SOURCE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Per-pass preset keys, written with the pass index appended (`filter_linear0`)
+const PASS_OPTIONS: [&str; 13] = [
+    "alias",
+    "filter_linear",
+    "wrap_mode",
+    "mipmap_input",
+    "float_framebuffer",
+    "srgb_framebuffer",
+    "frame_count_mod",
+    "scale_type",
+    "scale_type_x",
+    "scale_type_y",
+    "scale",
+    "scale_x",
+    "scale_y",
+];
+
+/// Per-texture preset keys, written after the texture's name (`MASK_linear`)
+const TEXTURE_OPTIONS: [&str; 3] = ["linear", "wrap_mode", "mipmap"];
+
+const SCALE_TYPES: [&str; 3] = ["source", "viewport", "absolute"];
+
+const WRAP_MODES: [&str; 4] = ["clamp_to_border", "clamp_to_edge", "repeat", "mirrored_repeat"];
+
+/// Stores RetroArch shader presets with their passes, and the parameter values users pick for them
+#[derive(Clone)]
+pub struct ShaderService {
+    shader_storage_path: String,
+}
+
+/// What a preset loads, read from the preset file and the sources of its passes
+struct ParsedPreset {
+    passes: Vec<ShaderPass>,
+    textures: BTreeMap<String, ShaderTexture>,
+    parameters: Vec<ShaderParameter>,
+    preset_values: BTreeMap<String, f64>,
+    /// The preset file and everything it references, by path in the preset's folder
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl ShaderService {
+    pub fn new(shader_storage_path: String) -> Self {
+        Self { shader_storage_path }
+    }
+
+    pub async fn get_presets(&self, pool: &PgPool, console_id: Option<&str>) -> Result<Vec<ShaderPreset>, ApiError> {
+        let presets = sqlx::query_as::<_, ShaderPreset>(
+            "SELECT p.*, ARRAY(SELECT console_id FROM shader_preset_consoles
+                               WHERE preset_id = p.id ORDER BY console_id) AS consoles
+             FROM shader_presets p
+             WHERE $1::text IS NULL
+                OR NOT EXISTS (SELECT 1 FROM shader_preset_consoles c WHERE c.preset_id = p.id)
+                OR EXISTS (SELECT 1 FROM shader_preset_consoles c WHERE c.preset_id = p.id AND c.console_id = $1)
+             ORDER BY p.name"
+        )
+        .bind(console_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(presets.into_iter().map(ShaderPreset::with_urls).collect())
+    }
+
+    pub async fn get_preset(&self, pool: &PgPool, preset_id: &Uuid) -> Result<ShaderPreset, ApiError> {
+        let preset = sqlx::query_as::<_, ShaderPreset>(
+            "SELECT p.*, ARRAY(SELECT console_id FROM shader_preset_consoles
+                               WHERE preset_id = p.id ORDER BY console_id) AS consoles
+             FROM shader_presets p
+             WHERE p.id = $1"
+        )
+        .bind(preset_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(preset.with_urls())
+    }
+
+    /// The uploaded preset a settings `shader` value refers to, `None` for EmulatorJS's own shaders
+    pub async fn find_preset_by_name(&self, pool: &PgPool, name: &str) -> Result<Option<ShaderPreset>, ApiError> {
+        let preset_id: Option<Uuid> = sqlx::query_scalar("SELECT id FROM shader_presets WHERE name = $1")
+            .bind(name)
+            .fetch_optional(pool)
+            .await?;
+
+        match preset_id {
+            Some(preset_id) => Ok(Some(self.get_preset(pool, &preset_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Rejects a shader setting for a console when it names an uploaded preset meant for others
+    pub async fn check_shader_setting(&self, pool: &PgPool, shader: &str, console_id: &str) -> Result<(), ApiError> {
+        if let Some(preset) = self.find_preset_by_name(pool, shader).await? {
+            if !preset.suits(console_id) {
+                return Err(ApiError::ValidationError(format!(
+                    "Shader preset '{}' isn't marked as compatible with {}",
+                    preset.name, console_id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds the preset among the uploaded files, which may be ZIP archives, checks it and
+    /// everything it references, and stores those files under the preset's folder.
+    /// Files the preset doesn't use are left out.
+    pub async fn create_preset(
+        &self,
+        pool: &PgPool,
+        request: &CreateShaderPresetRequest,
+        uploads: Vec<(String, Vec<u8>)>,
+        user_id: &Uuid,
+    ) -> Result<ShaderPreset, ApiError> {
+        let uploaded = collect_uploaded_files(uploads)?;
+
+        let preset_path = match request.preset.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            Some(wanted) => {
+                let wanted = normalize_path("", wanted)
+                    .ok_or_else(|| ApiError::ValidationError(format!("Invalid preset path '{}'", wanted)))?;
+                find_file(&uploaded, &wanted)
+                    .filter(|(path, _)| preset_format(path).is_some())
+                    .map(|(path, _)| path.to_string())
+                    .ok_or_else(|| ApiError::ValidationError(format!("Upload has no preset '{}'", wanted)))?
+            }
+            None => {
+                let presets: Vec<&String> = uploaded.keys().filter(|p| preset_format(p).is_some()).collect();
+                match presets.as_slice() {
+                    [path] => path.to_string(),
+                    [] => return Err(ApiError::ValidationError("Upload contains no .glslp or .slangp preset".to_string())),
+                    _ => {
+                        return Err(ApiError::ValidationError(format!(
+                            "Upload contains {} presets, pick one with the preset field",
+                            presets.len()
+                        )))
+                    }
+                }
+            }
+        };
+        let format = preset_format(&preset_path).unwrap_or_default();
+
+        let name = match request.name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => Path::new(&preset_path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().chars().take(100).collect())
+                .unwrap_or_else(|| preset_path.clone()),
+        };
+        self.ensure_unique_name(pool, &name, None).await?;
+        check_consoles(pool, &request.consoles).await?;
+
+        let parsed = parse_preset(&preset_path, format, &uploaded)?;
+        let total_size: usize = parsed.files.values().map(Vec::len).sum();
+
+        let id = Uuid::new_v4();
+        let folder = PathBuf::from(&self.shader_storage_path).join(id.to_string());
+        let files = parsed.files;
+        let paths: Vec<String> = files.keys().cloned().collect();
+        tokio::task::spawn_blocking(move || store_files(&folder, &files))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))??;
+
+        let result = async {
+            let mut tx = pool.begin().await?;
+            sqlx::query(
+                "INSERT INTO shader_presets
+                    (id, name, description, format, preset_path, passes, textures, parameters,
+                     preset_values, files, total_size, uploaded_by)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"
+            )
+            .bind(id)
+            .bind(&name)
+            .bind(request.description.as_deref().map(str::trim).filter(|d| !d.is_empty()))
+            .bind(format)
+            .bind(&preset_path)
+            .bind(Json(&parsed.passes))
+            .bind(Json(&parsed.textures))
+            .bind(Json(&parsed.parameters))
+            .bind(Json(&parsed.preset_values))
+            .bind(&paths)
+            .bind(total_size as i64)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+            set_consoles(&mut tx, &id, &request.consoles).await?;
+            tx.commit().await?;
+
+            self.get_preset(pool, &id).await
+        }
+        .await;
+
+        if result.is_err() {
+            let _ = fs::remove_dir_all(self.preset_folder(&id));
+        }
+        result
+    }
+
+    pub async fn update_preset(
+        &self,
+        pool: &PgPool,
+        preset: &ShaderPreset,
+        request: &UpdateShaderPresetRequest,
+    ) -> Result<ShaderPreset, ApiError> {
+        let name = request.name.as_deref().map(str::trim);
+        if let Some(name) = name {
+            if name.is_empty() {
+                return Err(ApiError::ValidationError("Preset name must not be empty".to_string()));
+            }
+            self.ensure_unique_name(pool, name, Some(&preset.id)).await?;
+        }
+        if let Some(consoles) = &request.consoles {
+            check_consoles(pool, consoles).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE shader_presets SET
+                name = COALESCE($2, name),
+                description = CASE WHEN $3::text IS NULL THEN description ELSE NULLIF($3, '') END,
+                updated_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(preset.id)
+        .bind(name)
+        .bind(request.description.as_deref().map(str::trim))
+        .execute(&mut *tx)
+        .await?;
+        if let Some(consoles) = &request.consoles {
+            sqlx::query("DELETE FROM shader_preset_consoles WHERE preset_id = $1")
+                .bind(preset.id)
+                .execute(&mut *tx)
+                .await?;
+            set_consoles(&mut tx, &preset.id, consoles).await?;
+        }
+        tx.commit().await?;
+
+        self.get_preset(pool, &preset.id).await
+    }
+
+    /// Deletes the preset, its folder and the parameter values users picked for it
+    pub async fn delete_preset(&self, pool: &PgPool, preset: &ShaderPreset) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM shader_presets WHERE id = $1")
+            .bind(preset.id)
+            .execute(pool)
+            .await?;
+
+        let folder = self.preset_folder(&preset.id);
+        if folder.exists() {
+            fs::remove_dir_all(&folder)
+                .map_err(|e| ApiError::InternalServerError(format!("Failed to delete shader files: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Path of one of the preset's stored files, `None` when the preset has no such file
+    pub fn file_path(&self, preset: &ShaderPreset, path: &str) -> Option<PathBuf> {
+        let path = normalize_path("", path)?;
+        preset
+            .files
+            .contains(&path)
+            .then(|| self.preset_folder(&preset.id).join(path))
+    }
+
+    pub async fn get_user_parameters(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        preset_id: &Uuid,
+    ) -> Result<BTreeMap<String, f64>, ApiError> {
+        let parameters = sqlx::query_as::<_, UserShaderParameters>(
+            "SELECT * FROM user_shader_parameters WHERE user_id = $1 AND preset_id = $2"
+        )
+        .bind(user_id)
+        .bind(preset_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(parameters.map(|p| p.parameters.0).unwrap_or_default())
+    }
+
+    /// Stores the user's values, which must be parameters of the preset and within their range
+    pub async fn set_user_parameters(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        preset: &ShaderPreset,
+        values: &BTreeMap<String, f64>,
+    ) -> Result<(), ApiError> {
+        for (name, value) in values {
+            let parameter = preset
+                .parameters
+                .iter()
+                .find(|p| p.name == *name)
+                .ok_or_else(|| ApiError::ValidationError(format!("Preset has no parameter '{}'", name)))?;
+            if !(parameter.minimum..=parameter.maximum).contains(value) {
+                return Err(ApiError::ValidationError(format!(
+                    "Parameter '{}' must be between {} and {}",
+                    name, parameter.minimum, parameter.maximum
+                )));
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO user_shader_parameters (user_id, preset_id, parameters)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, preset_id) DO UPDATE SET
+                parameters = EXCLUDED.parameters,
+                updated_at = NOW()"
+        )
+        .bind(user_id)
+        .bind(preset.id)
+        .bind(Json(values))
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_user_parameters(&self, pool: &PgPool, user_id: &Uuid, preset_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM user_shader_parameters WHERE user_id = $1 AND preset_id = $2")
+            .bind(user_id)
+            .bind(preset_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The preset with the value each parameter takes for the user
+    pub async fn resolve_preset(
+        &self,
+        pool: &PgPool,
+        preset: ShaderPreset,
+        user_id: &Uuid,
+    ) -> Result<ResolvedShaderPreset, ApiError> {
+        let user_values = self.get_user_parameters(pool, user_id, &preset.id).await?;
+
+        let values = preset
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let value = user_values
+                    .get(&parameter.name)
+                    .or_else(|| preset.preset_values.get(&parameter.name))
+                    .copied()
+                    .unwrap_or(parameter.default);
+                (parameter.name.clone(), value)
+            })
+            .collect();
+
+        Ok(ResolvedShaderPreset {
+            preset,
+            values,
+            user_values,
+        })
+    }
+
+    fn preset_folder(&self, preset_id: &Uuid) -> PathBuf {
+        Path::new(&self.shader_storage_path).join(preset_id.to_string())
+    }
+
+    async fn ensure_unique_name(&self, pool: &PgPool, name: &str, except: Option<&Uuid>) -> Result<(), ApiError> {
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM shader_presets WHERE name = $1 AND ($2::uuid IS NULL OR id <> $2))"
+        )
+        .bind(name)
+        .bind(except)
+        .fetch_one(pool)
+        .await?;
+
+        if taken {
+            return Err(ApiError::ValidationError(format!("A shader preset named '{}' already exists", name)));
+        }
+
+        Ok(())
+    }
+}
+
+async fn check_consoles(pool: &PgPool, consoles: &[String]) -> Result<(), ApiError> {
+    for console_id in consoles {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM consoles WHERE id = $1)")
+            .bind(console_id)
+            .fetch_one(pool)
+            .await?;
+        if !exists {
+            return Err(ApiError::ValidationError(format!("Unknown console '{}'", console_id)));
+        }
+    }
+
+    Ok(())
+}
+
+async fn set_consoles(tx: &mut Transaction<'_, Postgres>, preset_id: &Uuid, consoles: &[String]) -> Result<(), ApiError> {
+    sqlx::query(
+        "INSERT INTO shader_preset_consoles (preset_id, console_id)
+         SELECT $1, UNNEST($2::text[])
+         ON CONFLICT DO NOTHING"
+    )
+    .bind(preset_id)
+    .bind(consoles)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// `glslp` or `slangp` for preset files
+fn preset_format(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+    SHADER_FORMATS.iter().find(|f| **f == extension).copied()
+}
+
+/// Resolves `reference` against the folder `base`, rejecting absolute paths and paths that
+/// leave the upload's root
+fn normalize_path(base: &str, reference: &str) -> Option<String> {
+    let reference = reference.replace('\\', "/");
+    if reference.starts_with('/') || reference.contains(':') {
+        return None;
+    }
+
+    let mut segments: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in reference.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+fn parent_folder(path: &str) -> &str {
+    path.rsplit_once('/').map(|(folder, _)| folder).unwrap_or("")
+}
+
+/// Looks a referenced file up by path, or by name alone when the files were uploaded without
+/// their folders and only one has that name
+fn find_file<'a>(files: &'a HashMap<String, Vec<u8>>, path: &str) -> Option<(&'a str, &'a [u8])> {
+    if let Some((path, data)) = files.get_key_value(path) {
+        return Some((path.as_str(), data.as_slice()));
+    }
+
+    let name = path.rsplit('/').next()?;
+    let mut matches = files
+        .iter()
+        .filter(|(candidate, _)| candidate.rsplit('/').next() == Some(name));
+    match (matches.next(), matches.next()) {
+        (Some((path, data)), None) => Some((path.as_str(), data.as_slice())),
+        _ => None,
+    }
+}
+
+/// Uploaded files by path, with ZIP archives replaced by their entries
+fn collect_uploaded_files(uploads: Vec<(String, Vec<u8>)>) -> Result<HashMap<String, Vec<u8>>, ApiError> {
+    let mut files = HashMap::new();
+    let mut total = 0;
+
+    for (filename, data) in uploads {
+        if data.starts_with(b"PK\x03\x04") {
+            let zip_error = |e: zip::result::ZipError| ApiError::BadRequest(format!("Invalid archive '{}': {}", filename, e));
+            let mut zip = ZipArchive::new(Cursor::new(data)).map_err(zip_error)?;
+
+            for index in 0..zip.len() {
+                let mut entry = zip.by_index(index).map_err(zip_error)?;
+                let Some(path) = normalize_path("", entry.name()) else {
+                    continue;
+                };
+                if entry.is_dir() || path.starts_with("__MACOSX/") {
+                    continue;
+                }
+                // Entry sizes come from the archive itself, so count what actually decompresses
+                let mut contents = Vec::new();
+                (&mut entry)
+                    .take((MAX_SHADER_UPLOAD_BYTES - total) as u64 + 1)
+                    .read_to_end(&mut contents)
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid archive '{}': {}", filename, e)))?;
+                total += contents.len();
+                if total > MAX_SHADER_UPLOAD_BYTES {
+                    return Err(ApiError::BadRequest("Shader upload exceeds maximum size".to_string()));
+                }
+                files.insert(path, contents);
+            }
+        } else {
+            total += data.len();
+            if total > MAX_SHADER_UPLOAD_BYTES {
+                return Err(ApiError::BadRequest("Shader upload exceeds maximum size".to_string()));
+            }
+            // Clients may send paths relative to the preset's folder, but not outside of it
+            let path = normalize_path("", &filename)
+                .or_else(|| normalize_path("", filename.rsplit(['/', '\\']).next().unwrap_or_default()))
+                .ok_or_else(|| ApiError::BadRequest(format!("Invalid file name '{}'", filename)))?;
+            files.insert(path, data);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Reads the preset and every pass, include and texture it references
+fn parse_preset(
+    preset_path: &str,
+    format: &str,
+    uploaded: &HashMap<String, Vec<u8>>,
+) -> Result<ParsedPreset, ApiError> {
+    let preset_data = &uploaded[preset_path];
+    let config = parse_preset_config(preset_path, source_text(preset_path, preset_data)?)?;
+    let folder = parent_folder(preset_path);
+    let pass_extension = if format == "glslp" { "glsl" } else { "slang" };
+
+    let mut files = BTreeMap::from([(preset_path.to_string(), preset_data.clone())]);
+    let mut parameters: Vec<ShaderParameter> = Vec::new();
+
+    let count: usize = config
+        .get("shaders")
+        .and_then(|v| v.parse().ok())
+        .filter(|count| (1..=MAX_SHADER_PASSES).contains(count))
+        .ok_or_else(|| {
+            ApiError::ValidationError(format!("Preset must set shaders to a pass count between 1 and {}", MAX_SHADER_PASSES))
+        })?;
+
+    let mut passes = Vec::with_capacity(count);
+    for index in 0..count {
+        let reference = config
+            .get(&format!("shader{}", index))
+            .ok_or_else(|| ApiError::ValidationError(format!("Preset has no shader{}", index)))?;
+        let path = reference_path(folder, reference)?;
+        if !path.to_lowercase().ends_with(&format!(".{}", pass_extension)) {
+            return Err(ApiError::ValidationError(format!(
+                "Pass {} is '{}', .{} presets need .{} passes",
+                index, reference, format, pass_extension
+            )));
+        }
+
+        let mut source = String::new();
+        read_source(&path, uploaded, &mut files, &mut parameters, &mut source, &mut HashSet::new(), 0)?;
+        check_stages(&path, format, &source)?;
+
+        let options = PASS_OPTIONS
+            .iter()
+            .filter_map(|key| {
+                config
+                    .get(&format!("{}{}", key, index))
+                    .map(|value| check_option(key, value).map(|_| (key.to_string(), value.clone())))
+            })
+            .collect::<Result<_, _>>()?;
+        passes.push(ShaderPass { shader: path, options });
+    }
+
+    let mut textures = BTreeMap::new();
+    for name in list_value(config.get("textures")) {
+        let reference = config
+            .get(name)
+            .ok_or_else(|| ApiError::ValidationError(format!("Preset has no path for texture '{}'", name)))?;
+        let path = reference_path(folder, reference)?;
+        let data = stored_file(&path, uploaded, &mut files)?;
+        if image::guess_format(data).is_err() {
+            return Err(ApiError::ValidationError(format!("Texture '{}' is not an image", path)));
+        }
+
+        let options = TEXTURE_OPTIONS
+            .iter()
+            .filter_map(|key| {
+                config
+                    .get(&format!("{}_{}", name, key))
+                    .map(|value| check_option(key, value).map(|_| (key.to_string(), value.clone())))
+            })
+            .collect::<Result<_, _>>()?;
+        textures.insert(name.to_string(), ShaderTexture { path, options });
+    }
+
+    // Values for parameters no pass declares have no effect, RetroArch ignores them too
+    let mut preset_values = BTreeMap::new();
+    for name in list_value(config.get("parameters")) {
+        if !parameters.iter().any(|p| p.name == name) {
+            continue;
+        }
+        let value = config
+            .get(name)
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| ApiError::ValidationError(format!("Preset has no valid value for parameter '{}'", name)))?;
+        preset_values.insert(name.to_string(), value);
+    }
+
+    Ok(ParsedPreset {
+        passes,
+        textures,
+        parameters,
+        preset_values,
+        files,
+    })
+}
+
+/// Reads RetroArch's `key = value` config format, with optionally quoted values
+fn parse_preset_config(path: &str, text: &str) -> Result<HashMap<String, String>, ApiError> {
+    let mut config = HashMap::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with("#reference") {
+            return Err(ApiError::ValidationError(format!(
+                "'{}' references another preset, upload the preset it references instead",
+                path
+            )));
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            ApiError::ValidationError(format!("'{}' line {}: expected key = value", path, number + 1))
+        })?;
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        config.insert(key.trim().to_string(), value.to_string());
+    }
+
+    Ok(config)
+}
+
+/// Entries of a `;` separated list such as `textures` and `parameters`
+fn list_value(value: Option<&String>) -> impl Iterator<Item = &str> {
+    value
+        .map(|v| v.split(';'))
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+fn reference_path(folder: &str, reference: &str) -> Result<String, ApiError> {
+    normalize_path(folder, reference).ok_or_else(|| {
+        ApiError::ValidationError(format!("'{}' is outside of the preset's folder", reference))
+    })
+}
+
+/// Finds a referenced file in the upload and keeps it for storage under the referenced path
+fn stored_file<'a>(
+    path: &str,
+    uploaded: &HashMap<String, Vec<u8>>,
+    files: &'a mut BTreeMap<String, Vec<u8>>,
+) -> Result<&'a [u8], ApiError> {
+    if !files.contains_key(path) {
+        let (_, data) = find_file(uploaded, path)
+            .ok_or_else(|| ApiError::ValidationError(format!("Upload is missing '{}'", path)))?;
+        files.insert(path.to_string(), data.to_vec());
+    }
+
+    Ok(&files[path])
+}
+
+/// Appends the source at `path` with its includes expanded to `source`, collecting the
+/// parameters it declares. A file already in `included` is skipped, as an include guard would.
+fn read_source(
+    path: &str,
+    uploaded: &HashMap<String, Vec<u8>>,
+    files: &mut BTreeMap<String, Vec<u8>>,
+    parameters: &mut Vec<ShaderParameter>,
+    source: &mut String,
+    included: &mut HashSet<String>,
+    depth: usize,
+) -> Result<(), ApiError> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(ApiError::ValidationError(format!("Includes nest too deep at '{}'", path)));
+    }
+    if !included.insert(path.to_string()) {
+        return Ok(());
+    }
+    let text = source_text(path, stored_file(path, uploaded, files)?)?.to_string();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(include) = trimmed.strip_prefix("#include") {
+            let reference = include.trim().trim_matches('"');
+            let include_path = reference_path(parent_folder(path), reference)?;
+            read_source(&include_path, uploaded, files, parameters, source, included, depth + 1)?;
+            continue;
+        }
+        if let Some(declaration) = trimmed.strip_prefix("#pragma parameter") {
+            let parameter = parse_parameter(declaration).ok_or_else(|| {
+                ApiError::ValidationError(format!("'{}' has an invalid parameter: {}", path, trimmed))
+            })?;
+            // Passes often share parameters, the first declaration wins as in RetroArch
+            if !parameters.iter().any(|p| p.name == parameter.name) {
+                parameters.push(parameter);
+            }
+        }
+        source.push_str(line);
+        source.push('\n');
+        if source.len() > MAX_PASS_SOURCE_BYTES {
+            return Err(ApiError::ValidationError(format!(
+                "'{}' exceeds {} bytes with its includes expanded",
+                path, MAX_PASS_SOURCE_BYTES
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// `NAME "Description" default minimum maximum [step]`
+fn parse_parameter(declaration: &str) -> Option<ShaderParameter> {
+    let (name, rest) = declaration.trim().split_once(char::is_whitespace)?;
+    let rest = rest.trim().strip_prefix('"')?;
+    let (description, numbers) = rest.split_once('"')?;
+    let numbers: Vec<f64> = numbers
+        .split_whitespace()
+        .map(|n| n.parse().ok().filter(|n: &f64| n.is_finite()))
+        .collect::<Option<_>>()?;
+
+    let (default, minimum, maximum, step) = match numbers.as_slice() {
+        [default, minimum, maximum] => (*default, *minimum, *maximum, (maximum - minimum) * 0.1),
+        [default, minimum, maximum, step] => (*default, *minimum, *maximum, *step),
+        _ => return None,
+    };
+    if minimum > maximum {
+        return None;
+    }
+
+    Some(ShaderParameter {
+        name: name.to_string(),
+        description: description.trim().to_string(),
+        default,
+        minimum,
+        maximum,
+        step,
+    })
+}
+
+/// GLSL passes hold both stages behind `#if defined(VERTEX)`/`#ifdef FRAGMENT` directives,
+/// slang passes mark them with `#pragma stage`
+fn check_stages(path: &str, format: &str, source: &str) -> Result<(), ApiError> {
+    let has_stage = |stage: &str| {
+        source.lines().any(|line| {
+            if format == "glslp" {
+                tests_define(line, &stage.to_uppercase())
+            } else {
+                line.trim()
+                    .strip_prefix("#pragma stage")
+                    .is_some_and(|s| s.trim() == stage)
+            }
+        })
+    };
+
+    for stage in ["vertex", "fragment"] {
+        if !has_stage(stage) {
+            return Err(ApiError::ValidationError(format!("Pass '{}' has no {} stage", path, stage)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `line` is an `#ifdef`, `#if` or `#elif` directive testing that `define` is defined
+fn tests_define(line: &str, define: &str) -> bool {
+    let Some(directive) = line.trim().strip_prefix('#').map(str::trim_start) else {
+        return false;
+    };
+    if let Some(name) = directive.strip_prefix("ifdef") {
+        return name.trim() == define;
+    }
+
+    let Some(condition) = directive.strip_prefix("if").or_else(|| directive.strip_prefix("elif")) else {
+        return false;
+    };
+    let words: Vec<&str> = condition
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+        .collect();
+    words.windows(2).any(|pair| pair == ["defined", define])
+}
+
+fn check_option(key: &str, value: &str) -> Result<(), ApiError> {
+    let valid = match key {
+        "alias" => !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        "scale_type" | "scale_type_x" | "scale_type_y" => SCALE_TYPES.contains(&value),
+        "wrap_mode" => WRAP_MODES.contains(&value),
+        "scale" | "scale_x" | "scale_y" => value.parse::<f64>().is_ok_and(|v| v.is_finite() && v > 0.0),
+        "frame_count_mod" => value.parse::<u32>().is_ok(),
+        _ => matches!(value, "true" | "false" | "1" | "0"),
+    };
+
+    if !valid {
+        return Err(ApiError::ValidationError(format!("Invalid value '{}' for {}", value, key)));
+    }
+
+    Ok(())
+}
+
+fn source_text<'a>(path: &str, data: &'a [u8]) -> Result<&'a str, ApiError> {
+    std::str::from_utf8(data)
+        .ok()
+        .filter(|text| !text.contains('\0'))
+        .ok_or_else(|| ApiError::ValidationError(format!("'{}' is not a text file", path)))
+}
+
+fn store_files(folder: &Path, files: &BTreeMap<String, Vec<u8>>) -> Result<(), ApiError> {
+    let result = files.iter().try_for_each(|(path, data)| {
+        let target = folder.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, data)
+    });
+
+    result.map_err(|e| {
+        let _ = fs::remove_dir_all(folder);
+        ApiError::InternalServerError(format!("Failed to store shader files: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    const CRT_PASS: &str = "#pragma parameter MASK_STRENGTH \"Mask Strength\" 0.3 0.0 1.0 0.05
+#pragma parameter SCANLINES \"Scanlines\" 1.0 0.0 1.0
+#if defined(VERTEX)
+void main() {}
+#elif defined(FRAGMENT)
+#include \"include/common.h\"
+void main() {}
+#endif
+";
+
+    fn upload(files: &[(&str, &[u8])]) -> HashMap<String, Vec<u8>> {
+        files
+            .iter()
+            .map(|(path, data)| (path.to_string(), data.to_vec()))
+            .collect()
+    }
+
+    fn crt_upload(preset: &str) -> HashMap<String, Vec<u8>> {
+        upload(&[
+            ("crt/crt.glslp", preset.as_bytes()),
+            ("crt/shaders/crt.glsl", CRT_PASS.as_bytes()),
+            ("crt/shaders/include/common.h", b"#pragma parameter GAMMA \"Gamma\" 2.2 1.0 3.0 0.1\n"),
+            ("crt/shaders/blur.glsl", b"#if defined(VERTEX)\n#elif defined(FRAGMENT)\n#endif\n"),
+            ("crt/textures/mask.png", PNG_SIGNATURE),
+        ])
+    }
+
+    fn validation_error(result: Result<ParsedPreset, ApiError>) -> String {
+        match result {
+            Err(ApiError::ValidationError(message)) => message,
+            Err(e) => panic!("expected a validation error, got {:?}", e),
+            Ok(_) => panic!("expected the preset to be rejected"),
+        }
+    }
+
+    #[test]
+    fn parses_glsl_preset() {
+        let preset = r#"# CRT with a blur pass
+shaders = 2
+shader0 = shaders/crt.glsl
+filter_linear0 = false
+scale_type0 = "source"
+scale0 = 2.0
+shader1 = "./shaders/blur.glsl"
+alias1 = BLUR_PASS
+
+textures = "mask"
+mask = textures/mask.png
+mask_linear = true
+"#;
+        let parsed = parse_preset("crt/crt.glslp", "glslp", &crt_upload(preset)).unwrap();
+
+        assert_eq!(parsed.passes.len(), 2);
+        assert_eq!(parsed.passes[0].shader, "crt/shaders/crt.glsl");
+        assert_eq!(parsed.passes[0].options["filter_linear"], "false");
+        assert_eq!(parsed.passes[0].options["scale_type"], "source");
+        assert_eq!(parsed.passes[0].options["scale"], "2.0");
+        assert_eq!(parsed.passes[1].shader, "crt/shaders/blur.glsl");
+        assert_eq!(parsed.passes[1].options["alias"], "BLUR_PASS");
+        assert_eq!(parsed.textures["mask"].path, "crt/textures/mask.png");
+        assert_eq!(parsed.textures["mask"].options["linear"], "true");
+
+        // Included files are stored alongside the passes that use them
+        assert_eq!(
+            parsed.files.keys().collect::<Vec<_>>(),
+            [
+                "crt/crt.glslp",
+                "crt/shaders/blur.glsl",
+                "crt/shaders/crt.glsl",
+                "crt/shaders/include/common.h",
+                "crt/textures/mask.png",
+            ]
+        );
+
+        let names: Vec<&str> = parsed.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["MASK_STRENGTH", "SCANLINES", "GAMMA"]);
+        let scanlines = &parsed.parameters[1];
+        assert_eq!(scanlines.description, "Scanlines");
+        assert_eq!((scanlines.default, scanlines.minimum, scanlines.maximum), (1.0, 0.0, 1.0));
+        assert!((scanlines.step - 0.1).abs() < 1e-9);
+        assert!(parsed.preset_values.is_empty());
+    }
+
+    #[test]
+    fn parses_slang_preset() {
+        let pass = "#version 450\n#pragma parameter WARP \"Warp\" 0.1 0.0 0.5 0.01\n\
+                    #pragma stage vertex\nvoid main() {}\n#pragma stage fragment\nvoid main() {}\n";
+        let uploaded = upload(&[("warp.slangp", b"shaders = 1\nshader0 = warp.slang\n"), ("warp.slang", pass.as_bytes())]);
+        let parsed = parse_preset("warp.slangp", "slangp", &uploaded).unwrap();
+
+        assert_eq!(parsed.passes[0].shader, "warp.slang");
+        assert_eq!(parsed.parameters[0].name, "WARP");
+        assert_eq!(parsed.parameters[0].step, 0.01);
+    }
+
+    #[test]
+    fn reads_parameter_overrides() {
+        let preset = "shaders = 1\nshader0 = shaders/crt.glsl\n\
+                      parameters = \"MASK_STRENGTH;GAMMA;UNKNOWN\"\n\
+                      MASK_STRENGTH = 0.75\nGAMMA = \"2.4\"\nUNKNOWN = 5\n";
+        let parsed = parse_preset("crt/crt.glslp", "glslp", &crt_upload(preset)).unwrap();
+
+        // Values for parameters no pass declares are dropped
+        assert_eq!(
+            parsed.preset_values,
+            BTreeMap::from([("GAMMA".to_string(), 2.4), ("MASK_STRENGTH".to_string(), 0.75)])
+        );
+
+        let preset = "shaders = 1\nshader0 = shaders/crt.glsl\nparameters = MASK_STRENGTH\nMASK_STRENGTH = strong\n";
+        let message = validation_error(parse_preset("crt/crt.glslp", "glslp", &crt_upload(preset)));
+        assert!(message.contains("MASK_STRENGTH"), "{}", message);
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_preset_folder() {
+        for reference in ["../../crt.glsl", "shaders/../../../crt.glsl", "/etc/crt.glsl", "C:\\crt.glsl"] {
+            let preset = format!("shaders = 1\nshader0 = {}\n", reference);
+            let message = validation_error(parse_preset("crt/crt.glslp", "glslp", &crt_upload(&preset)));
+            assert!(message.contains("outside of the preset's folder"), "{}: {}", reference, message);
+        }
+
+        let preset = "shaders = 1\nshader0 = shaders/crt.glsl\ntextures = mask\nmask = ../../mask.png\n";
+        let message = validation_error(parse_preset("crt/crt.glslp", "glslp", &crt_upload(preset)));
+        assert!(message.contains("outside of the preset's folder"), "{}", message);
+
+        let uploaded = upload(&[("a.glslp", b"shaders = 1\nshader0 = ../a.glsl\n")]);
+        let message = validation_error(parse_preset("a.glslp", "glslp", &uploaded));
+        assert!(message.contains("outside of the preset's folder"), "{}", message);
+
+        let escaping = "#include \"../../../secret.h\"\n#if defined(VERTEX)\n#elif defined(FRAGMENT)\n#endif\n";
+        let uploaded = upload(&[("a.glslp", b"shaders = 1\nshader0 = a.glsl\n"), ("a.glsl", escaping.as_bytes())]);
+        let message = validation_error(parse_preset("a.glslp", "glslp", &uploaded));
+        assert!(message.contains("outside of the preset's folder"), "{}", message);
+    }
+
+    #[test]
+    fn rejects_invalid_presets() {
+        let cases = [
+            ("shaders = 0\n", "pass count"),
+            ("shaders = 2\nshader0 = shaders/crt.glsl\n", "no shader1"),
+            ("shaders = 1\nshader0 = shaders/crt.slang\n", "need .glsl passes"),
+            ("shaders = 1\nshader0 = shaders/missing.glsl\n", "missing"),
+            ("shaders = 1\nshader0 = shaders/crt.glsl\nscale_type0 = huge\n", "Invalid value"),
+            ("shaders = 1\nshader0 = shaders/crt.glsl\ntextures = mask\nmask = shaders/crt.glsl\n", "not an image"),
+            ("#reference \"other.glslp\"\n", "references another preset"),
+            ("shaders 1\n", "expected key = value"),
+        ];
+
+        for (preset, expected) in cases {
+            let message = validation_error(parse_preset("crt/crt.glslp", "glslp", &crt_upload(preset)));
+            assert!(message.contains(expected), "{:?}: {}", preset, message);
+        }
+    }
+
+    #[test]
+    fn expands_each_include_once() {
+        // Every level includes the next one twice, which would double the source per level
+        let mut files = vec![
+            ("a.glslp".to_string(), b"shaders = 1\nshader0 = a.glsl\n".to_vec()),
+            (
+                "a.glsl".to_string(),
+                b"#include \"level0.h\"\n#ifdef VERTEX\n#endif\n#ifdef FRAGMENT\n#endif\n".to_vec(),
+            ),
+        ];
+        for level in 0..MAX_INCLUDE_DEPTH - 1 {
+            let include = format!("#include \"level{}.h\"\n", level + 1);
+            files.push((format!("level{}.h", level), include.repeat(2).into_bytes()));
+        }
+        files.push((format!("level{}.h", MAX_INCLUDE_DEPTH - 1), vec![b'x'; 1024]));
+        let uploaded: HashMap<String, Vec<u8>> = files.into_iter().collect();
+        let parsed = parse_preset("a.glslp", "glslp", &uploaded).unwrap();
+        assert_eq!(parsed.files.len(), MAX_INCLUDE_DEPTH + 2);
+
+        let padding = "// padding\n";
+        let big = padding.repeat(MAX_PASS_SOURCE_BYTES / padding.len() / 2 + 1);
+        let pass = "#include \"a.h\"\n#include \"b.h\"\n#ifdef VERTEX\n#endif\n#ifdef FRAGMENT\n#endif\n";
+        let uploaded = upload(&[
+            ("a.glslp", b"shaders = 1\nshader0 = a.glsl\n"),
+            ("a.glsl", pass.as_bytes()),
+            ("a.h", big.as_bytes()),
+            ("b.h", big.as_bytes()),
+        ]);
+        let message = validation_error(parse_preset("a.glslp", "glslp", &uploaded));
+        assert!(message.contains("with its includes expanded"), "{}", message);
+    }
+
+    #[test]
+    fn finds_glsl_stages_by_directive() {
+        for (pass, valid) in [
+            ("#ifdef VERTEX\n#endif\n#ifdef FRAGMENT\n#endif\n", true),
+            ("#if defined VERTEX\n#elif defined( FRAGMENT )\n#endif\n", true),
+            ("# if defined(VERTEX) || defined(GL_ES)\n#endif\n#if defined(FRAGMENT)\n#endif\n", true),
+            ("// VERTEX and FRAGMENT stages\nvoid main() {}\n", false),
+            ("#ifdef VERTEX_COLOR\n#endif\n#ifdef FRAGMENT\n#endif\n", false),
+            ("#ifndef VERTEX\n#endif\n#ifdef FRAGMENT\n#endif\n", false),
+        ] {
+            assert_eq!(check_stages("a.glsl", "glslp", pass).is_ok(), valid, "{:?}", pass);
+        }
+    }
+
+    #[test]
+    fn parses_parameter_declarations() {
+        let parameter = parse_parameter(" BLOOM \"Bloom Amount\" 0.5 0.0 2.0 0.25").unwrap();
+        assert_eq!(parameter.name, "BLOOM");
+        assert_eq!(parameter.description, "Bloom Amount");
+        assert_eq!((parameter.default, parameter.minimum, parameter.maximum, parameter.step), (0.5, 0.0, 2.0, 0.25));
+
+        assert!(parse_parameter("BLOOM \"Bloom\" 0.5 2.0 0.0").is_none());
+        assert!(parse_parameter("BLOOM \"Bloom\" 0.5 0.0").is_none());
+        assert!(parse_parameter("BLOOM Bloom 0.5 0.0 2.0").is_none());
+        assert!(parse_parameter("BLOOM \"Bloom\" 0.5 0.0 inf").is_none());
+    }
+}
@@ -0,0 +1,659 @@
+use crate::models::{
+    CreateSmartCollectionRequest, Rule, RuleOp, SmartCollection, UpdateSmartCollectionRequest,
+};
+use crate::utils::ApiError;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::Value as JsonValue;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+const MAX_RULE_DEPTH: usize = 8;
+const MAX_RULE_CONDITIONS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Text,
+    Integer,
+    Number,
+    Timestamp,
+    Boolean,
+}
+
+/// Fields rules may refer to, as (name, kind, SQL expression over `games g`).
+/// `{user}` is bound to the user the collection is evaluated for.
+const RULE_FIELDS: &[(&str, FieldKind, &str)] = &[
+    ("title", FieldKind::Text, "g.title"),
+    ("console_id", FieldKind::Text, "g.console_id"),
+    // Tag names joined with ", ", meant for `contains`
+    (
+        "genre",
+        FieldKind::Text,
+        "(SELECT string_agg(t.name, ', ' ORDER BY t.name) FROM game_tags gt JOIN tags t ON t.id = gt.tag_id WHERE gt.game_id = g.id AND t.kind = 'genre')",
+    ),
+    (
+        "tags",
+        FieldKind::Text,
+        "(SELECT string_agg(t.name, ', ' ORDER BY t.name) FROM game_tags gt JOIN tags t ON t.id = gt.tag_id WHERE gt.game_id = g.id)",
+    ),
+    ("developer", FieldKind::Text, "g.developer"),
+    ("publisher", FieldKind::Text, "g.publisher"),
+    ("region", FieldKind::Text, "g.region"),
+    ("players", FieldKind::Text, "g.players"),
+    ("release_year", FieldKind::Integer, "g.release_year"),
+    ("rom_size_bytes", FieldKind::Integer, "g.rom_size_bytes"),
+    ("rating", FieldKind::Number, "g.rating"),
+    ("user_rating", FieldKind::Number, "g.user_rating"),
+    ("created_at", FieldKind::Timestamp, "g.created_at"),
+    (
+        "favorite",
+        FieldKind::Boolean,
+        "EXISTS (SELECT 1 FROM user_favorites f WHERE f.game_id = g.id AND f.user_id = {user})",
+    ),
+    (
+        "play_count",
+        FieldKind::Integer,
+        "COALESCE((SELECT s.play_count FROM user_game_stats s WHERE s.game_id = g.id AND s.user_id = {user}), 0)",
+    ),
+    (
+        "total_playtime_seconds",
+        FieldKind::Integer,
+        "COALESCE((SELECT s.total_playtime_seconds FROM user_game_stats s WHERE s.game_id = g.id AND s.user_id = {user}), 0)",
+    ),
+    (
+        "first_played",
+        FieldKind::Timestamp,
+        "(SELECT s.first_played FROM user_game_stats s WHERE s.game_id = g.id AND s.user_id = {user})",
+    ),
+    (
+        "last_played",
+        FieldKind::Timestamp,
+        "(SELECT s.last_played FROM user_game_stats s WHERE s.game_id = g.id AND s.user_id = {user})",
+    ),
+    (
+        "save_count",
+        FieldKind::Integer,
+        "(SELECT COUNT(*) FROM save_states ss WHERE ss.game_id = g.id AND ss.user_id = {user})",
+    ),
+    (
+        "my_rating",
+        FieldKind::Integer,
+        "(SELECT r.rating FROM game_reviews r WHERE r.game_id = g.id AND r.user_id = {user})",
+    ),
+    (
+        "play_status",
+        FieldKind::Text,
+        "(SELECT ps.status FROM user_game_status ps WHERE ps.game_id = g.id AND ps.user_id = {user})",
+    ),
+    // Overwriting a slot replaces the row, so the newest created_at is the last save
+    (
+        "last_saved",
+        FieldKind::Timestamp,
+        "(SELECT MAX(ss.created_at) FROM save_states ss WHERE ss.game_id = g.id AND ss.user_id = {user})",
+    ),
+];
+
+const DATE_UNITS: &[&str] = &["day", "week", "month", "year"];
+
+enum RuleValue {
+    Text(String),
+    Integer(i64),
+    Number(f64),
+    Timestamp(DateTime<Utc>),
+    Boolean(bool),
+    TextList(Vec<String>),
+    IntegerList(Vec<i64>),
+    NumberList(Vec<f64>),
+}
+
+#[derive(Clone, Default)]
+pub struct SmartCollectionService;
+
+impl SmartCollectionService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Lists the user's own smart collections, or public smart collections of other users
+    pub async fn get_smart_collections(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        public: bool,
+    ) -> Result<Vec<SmartCollection>, ApiError> {
+        let condition = if public {
+            "is_public AND user_id <> $1"
+        } else {
+            "user_id = $1"
+        };
+
+        let collections = sqlx::query_as::<_, SmartCollection>(&format!(
+            "SELECT * FROM smart_collections WHERE {} ORDER BY name",
+            condition
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(collections)
+    }
+
+    /// Returns a smart collection the user owns or that is public
+    pub async fn get_visible_smart_collection(
+        &self,
+        pool: &PgPool,
+        collection_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<SmartCollection, ApiError> {
+        let collection = sqlx::query_as::<_, SmartCollection>("SELECT * FROM smart_collections WHERE id = $1")
+            .bind(collection_id)
+            .fetch_one(pool)
+            .await?;
+
+        if collection.user_id != *user_id && !collection.is_public {
+            return Err(ApiError::NotFound("Smart collection not found".to_string()));
+        }
+
+        Ok(collection)
+    }
+
+    /// Returns a smart collection the user owns
+    pub async fn get_owned_smart_collection(
+        &self,
+        pool: &PgPool,
+        collection_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<SmartCollection, ApiError> {
+        let collection = self
+            .get_visible_smart_collection(pool, collection_id, user_id)
+            .await?;
+
+        if collection.user_id != *user_id {
+            return Err(ApiError::Forbidden("Not authorized to modify this smart collection".to_string()));
+        }
+
+        Ok(collection)
+    }
+
+    pub async fn create_smart_collection(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        request: &CreateSmartCollectionRequest,
+    ) -> Result<SmartCollection, ApiError> {
+        validate_rule(&request.rules)?;
+        self.ensure_unique_name(pool, user_id, &request.name, None).await?;
+
+        let collection = sqlx::query_as::<_, SmartCollection>(
+            "INSERT INTO smart_collections (user_id, name, description, rules, sort, is_public)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(request.name.trim())
+        .bind(&request.description)
+        .bind(Json(&request.rules))
+        .bind(request.sort.map(Json))
+        .bind(request.is_public)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    pub async fn update_smart_collection(
+        &self,
+        pool: &PgPool,
+        collection: &SmartCollection,
+        request: &UpdateSmartCollectionRequest,
+    ) -> Result<SmartCollection, ApiError> {
+        if let Some(rules) = &request.rules {
+            validate_rule(rules)?;
+        }
+        if let Some(name) = &request.name {
+            self.ensure_unique_name(pool, &collection.user_id, name, Some(&collection.id))
+                .await?;
+        }
+
+        let collection = sqlx::query_as::<_, SmartCollection>(
+            "UPDATE smart_collections
+             SET name = COALESCE($2, name),
+                 description = COALESCE($3, description),
+                 rules = COALESCE($4, rules),
+                 sort = COALESCE($5, sort),
+                 is_public = COALESCE($6, is_public),
+                 updated_at = NOW()
+             WHERE id = $1
+             RETURNING *"
+        )
+        .bind(collection.id)
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(&request.description)
+        .bind(request.rules.as_ref().map(Json))
+        .bind(request.sort.map(Json))
+        .bind(request.is_public)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    async fn ensure_unique_name(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        name: &str,
+        except: Option<&Uuid>,
+    ) -> Result<(), ApiError> {
+        let exists: Option<bool> = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM smart_collections WHERE user_id = $1 AND name = $2 AND id IS DISTINCT FROM $3)"
+        )
+        .bind(user_id)
+        .bind(name.trim())
+        .bind(except)
+        .fetch_one(pool)
+        .await?;
+
+        if exists == Some(true) {
+            return Err(ApiError::BadRequest("A smart collection with this name already exists".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_smart_collection(&self, pool: &PgPool, collection_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM smart_collections WHERE id = $1")
+            .bind(collection_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Checks that a rule only uses known fields, supported operators and well-typed values
+pub fn validate_rule(rule: &Rule) -> Result<(), ApiError> {
+    let mut builder = QueryBuilder::<Postgres>::new("");
+    push_rule(&mut builder, rule, &Uuid::nil())
+}
+
+/// Appends `AND (<rule>)` to a query over `games g`. Field names and operators come from
+/// fixed tables and every value is bound, so user input never reaches the SQL text.
+pub fn push_rule(builder: &mut QueryBuilder<'_, Postgres>, rule: &Rule, user_id: &Uuid) -> Result<(), ApiError> {
+    let mut conditions = 0;
+
+    builder.push(" AND (");
+    push_rule_node(builder, rule, user_id, 1, &mut conditions)?;
+    builder.push(")");
+
+    Ok(())
+}
+
+fn push_rule_node(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    rule: &Rule,
+    user_id: &Uuid,
+    depth: usize,
+    conditions: &mut usize,
+) -> Result<(), ApiError> {
+    if depth > MAX_RULE_DEPTH {
+        return Err(ApiError::ValidationError(format!(
+            "Rules may be nested at most {} levels deep",
+            MAX_RULE_DEPTH
+        )));
+    }
+
+    match rule {
+        Rule::All { all } => push_rule_group(builder, all, " AND ", "TRUE", user_id, depth, conditions),
+        Rule::Any { any } => push_rule_group(builder, any, " OR ", "FALSE", user_id, depth, conditions),
+        Rule::Not { not } => {
+            builder.push("NOT (");
+            push_rule_node(builder, not, user_id, depth + 1, conditions)?;
+            builder.push(")");
+            Ok(())
+        }
+        Rule::Condition { field, op, value } => {
+            count_condition(conditions)?;
+            push_condition(builder, field, *op, value, user_id)
+        }
+    }
+}
+
+fn count_condition(conditions: &mut usize) -> Result<(), ApiError> {
+    *conditions += 1;
+    if *conditions > MAX_RULE_CONDITIONS {
+        return Err(ApiError::ValidationError(format!(
+            "Rules may contain at most {} conditions",
+            MAX_RULE_CONDITIONS
+        )));
+    }
+    Ok(())
+}
+
+fn push_rule_group(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    rules: &[Rule],
+    separator: &str,
+    empty: &str,
+    user_id: &Uuid,
+    depth: usize,
+    conditions: &mut usize,
+) -> Result<(), ApiError> {
+    if rules.is_empty() {
+        // An empty group stands for TRUE or FALSE, so it counts like a condition
+        count_condition(conditions)?;
+        builder.push(empty);
+        return Ok(());
+    }
+
+    for (i, rule) in rules.iter().enumerate() {
+        if i > 0 {
+            builder.push(separator);
+        }
+        builder.push("(");
+        push_rule_node(builder, rule, user_id, depth + 1, conditions)?;
+        builder.push(")");
+    }
+
+    Ok(())
+}
+
+fn push_condition(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    field: &str,
+    op: RuleOp,
+    value: &JsonValue,
+    user_id: &Uuid,
+) -> Result<(), ApiError> {
+    let (_, kind, sql) = RULE_FIELDS
+        .iter()
+        .find(|(name, ..)| *name == field)
+        .ok_or_else(|| ApiError::ValidationError(format!("Unknown rule field '{}'", field)))?;
+    let kind = *kind;
+
+    let comparison = match op {
+        RuleOp::Eq => Some(" = "),
+        RuleOp::Ne => Some(" IS DISTINCT FROM "),
+        RuleOp::Lt => Some(" < "),
+        RuleOp::Lte => Some(" <= "),
+        RuleOp::Gt => Some(" > "),
+        RuleOp::Gte => Some(" >= "),
+        _ => None,
+    };
+
+    match (op, kind) {
+        (RuleOp::IsNull, _) | (RuleOp::NotNull, _) => {
+            push_field(builder, sql, user_id);
+            builder.push(if op == RuleOp::IsNull { " IS NULL" } else { " IS NOT NULL" });
+        }
+        // Text comparisons are case-insensitive
+        (RuleOp::Eq, FieldKind::Text) | (RuleOp::Ne, FieldKind::Text) => {
+            let value = rule_value(field, kind, value)?;
+            builder.push("LOWER(");
+            push_field(builder, sql, user_id);
+            builder.push(")").push(comparison.unwrap_or(" = ")).push("LOWER(");
+            push_value(builder, value);
+            builder.push(")");
+        }
+        (RuleOp::Eq, _) | (RuleOp::Ne, _) => {
+            let value = rule_value(field, kind, value)?;
+            push_field(builder, sql, user_id);
+            builder.push(comparison.unwrap_or(" = "));
+            push_value(builder, value);
+        }
+        (RuleOp::Lt | RuleOp::Lte | RuleOp::Gt | RuleOp::Gte, FieldKind::Integer | FieldKind::Number | FieldKind::Timestamp) => {
+            let value = rule_value(field, kind, value)?;
+            push_field(builder, sql, user_id);
+            builder.push(comparison.unwrap_or(" = "));
+            push_value(builder, value);
+        }
+        (RuleOp::In, FieldKind::Text | FieldKind::Integer | FieldKind::Number) => {
+            let values = value.as_array().ok_or_else(|| {
+                ApiError::ValidationError(format!("Rule on '{}' with 'in' expects an array", field))
+            })?;
+            for value in values {
+                rule_value(field, kind, value)?;
+            }
+            let value = match kind {
+                FieldKind::Text => {
+                    RuleValue::TextList(values.iter().filter_map(|v| v.as_str().map(str::to_lowercase)).collect())
+                }
+                FieldKind::Integer => RuleValue::IntegerList(values.iter().filter_map(JsonValue::as_i64).collect()),
+                _ => RuleValue::NumberList(values.iter().filter_map(JsonValue::as_f64).collect()),
+            };
+
+            if kind == FieldKind::Text {
+                builder.push("LOWER(");
+                push_field(builder, sql, user_id);
+                builder.push(")");
+            } else {
+                push_field(builder, sql, user_id);
+            }
+            builder.push(" = ANY(");
+            push_value(builder, value);
+            builder.push(")");
+        }
+        (RuleOp::Contains | RuleOp::StartsWith, FieldKind::Text) => {
+            rule_value(field, kind, value)?;
+            let text = value.as_str().unwrap_or_default();
+            let pattern = if op == RuleOp::Contains {
+                format!("%{}%", escape_like(text))
+            } else {
+                format!("{}%", escape_like(text))
+            };
+            push_field(builder, sql, user_id);
+            builder.push(" ILIKE ").push_bind(pattern);
+        }
+        (RuleOp::WithinDays, FieldKind::Timestamp) => {
+            let days = value
+                .as_i64()
+                .filter(|d| (0..=36_500).contains(d))
+                .ok_or_else(|| {
+                    ApiError::ValidationError(format!(
+                        "Rule on '{}' with 'within_days' expects a number of days",
+                        field
+                    ))
+                })?;
+            push_field(builder, sql, user_id);
+            builder
+                .push(" >= NOW() - make_interval(days => ")
+                .push_bind(days as i32)
+                .push(")");
+        }
+        (RuleOp::SinceStartOf, FieldKind::Timestamp) => {
+            let unit = value
+                .as_str()
+                .filter(|u| DATE_UNITS.contains(u))
+                .ok_or_else(|| {
+                    ApiError::ValidationError(format!(
+                        "Rule on '{}' with 'since_start_of' expects one of {}",
+                        field,
+                        DATE_UNITS.join(", ")
+                    ))
+                })?;
+            push_field(builder, sql, user_id);
+            builder
+                .push(" >= date_trunc(")
+                .push_bind(unit.to_string())
+                .push(", NOW())");
+        }
+        _ => {
+            return Err(ApiError::ValidationError(format!(
+                "Operator {:?} is not supported for field '{}'",
+                op, field
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+/// Pushes a field expression, binding the user id wherever it is referenced
+fn push_field(builder: &mut QueryBuilder<'_, Postgres>, sql: &str, user_id: &Uuid) {
+    let mut parts = sql.split("{user}");
+    if let Some(first) = parts.next() {
+        builder.push(first);
+    }
+    for part in parts {
+        builder.push_bind(*user_id).push(part);
+    }
+}
+
+fn push_value(builder: &mut QueryBuilder<'_, Postgres>, value: RuleValue) {
+    match value {
+        RuleValue::Text(v) => builder.push_bind(v),
+        RuleValue::Integer(v) => builder.push_bind(v),
+        RuleValue::Number(v) => builder.push_bind(v),
+        RuleValue::Timestamp(v) => builder.push_bind(v),
+        RuleValue::Boolean(v) => builder.push_bind(v),
+        RuleValue::TextList(v) => builder.push_bind(v),
+        RuleValue::IntegerList(v) => builder.push_bind(v),
+        RuleValue::NumberList(v) => builder.push_bind(v),
+    };
+}
+
+/// Converts a JSON value to the field's type. Timestamps accept RFC 3339 or `YYYY-MM-DD`.
+fn rule_value(field: &str, kind: FieldKind, value: &JsonValue) -> Result<RuleValue, ApiError> {
+    let converted = match kind {
+        FieldKind::Text => value.as_str().map(|v| RuleValue::Text(v.to_string())),
+        FieldKind::Integer => value.as_i64().map(RuleValue::Integer),
+        FieldKind::Number => value.as_f64().map(RuleValue::Number),
+        FieldKind::Boolean => value.as_bool().map(RuleValue::Boolean),
+        FieldKind::Timestamp => value.as_str().and_then(|v| {
+            DateTime::parse_from_rfc3339(v)
+                .map(|t| t.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(|t| t.and_utc())
+                })
+                .map(RuleValue::Timestamp)
+        }),
+    };
+
+    converted.ok_or_else(|| {
+        let expected = match kind {
+            FieldKind::Text => "a string",
+            FieldKind::Integer => "an integer",
+            FieldKind::Number => "a number",
+            FieldKind::Boolean => "true or false",
+            FieldKind::Timestamp => "an RFC 3339 timestamp or YYYY-MM-DD date",
+        };
+        ApiError::ValidationError(format!("Rule on '{}' expects {}", field, expected))
+    })
+}
+
+/// Escapes `%`, `_` and `\` for use in a LIKE pattern with the default `\` escape character
+pub fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn compile(rule: JsonValue) -> Result<String, ApiError> {
+        let rule: Rule = serde_json::from_value(rule).unwrap();
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT g.* FROM games g WHERE TRUE");
+        push_rule(&mut builder, &rule, &Uuid::nil())?;
+        Ok(builder.sql().to_string())
+    }
+
+    fn validation_error(rule: JsonValue) -> String {
+        match compile(rule) {
+            Err(ApiError::ValidationError(message)) => message,
+            Err(e) => panic!("expected a validation error, got {:?}", e),
+            Ok(sql) => panic!("expected the rule to be rejected, got {}", sql),
+        }
+    }
+
+    #[test]
+    fn compiles_rules_with_bound_values() {
+        let sql = compile(json!({ "all": [
+            { "field": "title", "op": "contains", "value": "Mario'; DROP TABLE games; --" },
+            { "any": [
+                { "field": "release_year", "op": "gte", "value": 1990 },
+                { "not": { "field": "favorite", "op": "eq", "value": true } },
+            ] },
+            { "field": "region", "op": "in", "value": ["USA", "Europe"] },
+            { "field": "developer", "op": "is_null" },
+        ] }))
+        .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT g.* FROM games g WHERE TRUE AND (\
+             (g.title ILIKE $1) AND \
+             ((g.release_year >= $2) OR (NOT (EXISTS (SELECT 1 FROM user_favorites f \
+             WHERE f.game_id = g.id AND f.user_id = $3) = $4))) AND \
+             (LOWER(g.region) = ANY($5)) AND \
+             (g.developer IS NULL))"
+        );
+    }
+
+    #[test]
+    fn only_accepts_known_fields_and_operators() {
+        let message = validation_error(json!({ "field": "g.title; --", "op": "eq", "value": "x" }));
+        assert!(message.contains("Unknown rule field"), "{}", message);
+
+        for (field, op, value) in [
+            ("release_year", "contains", json!("19")),
+            ("title", "lt", json!("M")),
+            ("favorite", "in", json!([true])),
+            ("title", "within_days", json!(7)),
+            ("release_year", "since_start_of", json!("year")),
+        ] {
+            let message = validation_error(json!({ "field": field, "op": op, "value": value }));
+            assert!(message.contains("is not supported"), "{} {}: {}", field, op, message);
+        }
+
+        for (field, op, value) in [
+            ("release_year", "eq", json!("1990")),
+            ("rating", "gt", json!(null)),
+            ("title", "in", json!("Mario")),
+            ("region", "in", json!(["USA", 1])),
+            ("created_at", "lt", json!("last week")),
+            ("last_played", "within_days", json!(-1)),
+            ("last_played", "since_start_of", json!("century")),
+        ] {
+            let message = validation_error(json!({ "field": field, "op": op, "value": value }));
+            assert!(message.contains(field), "{} {}: {}", field, op, message);
+        }
+
+        assert!(serde_json::from_value::<Rule>(json!({ "field": "title", "op": "like", "value": "%" })).is_err());
+    }
+
+    #[test]
+    fn escapes_like_wildcards() {
+        assert_eq!(escape_like("100%_done"), "100\\%\\_done");
+        assert_eq!(escape_like("C:\\Games"), "C:\\\\Games");
+        assert_eq!(escape_like("Zelda"), "Zelda");
+    }
+
+    #[test]
+    fn limits_rule_depth_and_size() {
+        let mut rule = json!({ "field": "title", "op": "eq", "value": "Zelda" });
+        for _ in 0..MAX_RULE_DEPTH - 1 {
+            rule = json!({ "not": rule });
+        }
+        assert!(compile(rule.clone()).is_ok());
+        let message = validation_error(json!({ "all": [rule] }));
+        assert!(message.contains("nested"), "{}", message);
+
+        let condition = json!({ "field": "release_year", "op": "gt", "value": 1990 });
+        let conditions = vec![condition; MAX_RULE_CONDITIONS];
+        assert!(compile(json!({ "any": conditions.clone() })).is_ok());
+        let message = validation_error(json!({ "any": [{ "all": conditions }, { "all": [] }] }));
+        assert!(message.contains("at most"), "{}", message);
+
+        // Empty groups add no condition but still make the query bigger
+        let message = validation_error(json!({ "all": vec![json!({ "all": [] }); MAX_RULE_CONDITIONS + 1] }));
+        assert!(message.contains("at most"), "{}", message);
+    }
+}
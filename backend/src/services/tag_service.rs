@@ -0,0 +1,313 @@
+use crate::models::{CreateTagRequest, Game, GameTag, Tag, TagSummary, UpdateTagRequest, TAG_KINDS};
+use crate::utils::ApiError;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Manages the tag taxonomy and the tags attached to games
+#[derive(Clone, Default)]
+pub struct TagService;
+
+impl TagService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn get_tags(&self, pool: &PgPool, kind: Option<&str>) -> Result<Vec<TagSummary>, ApiError> {
+        if let Some(kind) = kind {
+            validate_kind(kind)?;
+        }
+
+        let tags = sqlx::query_as::<_, TagSummary>(
+            "SELECT t.*, (SELECT COUNT(*) FROM game_tags gt WHERE gt.tag_id = t.id) AS game_count
+             FROM tags t
+             WHERE $1::VARCHAR IS NULL OR t.kind = $1
+             ORDER BY t.kind, t.name"
+        )
+        .bind(kind)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    pub async fn get_tag(&self, pool: &PgPool, tag_id: &Uuid) -> Result<Tag, ApiError> {
+        let tag = sqlx::query_as::<_, Tag>("SELECT * FROM tags WHERE id = $1")
+            .bind(tag_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(tag)
+    }
+
+    pub async fn create_tag(
+        &self,
+        pool: &PgPool,
+        request: &CreateTagRequest,
+        user_id: &Uuid,
+    ) -> Result<Tag, ApiError> {
+        validate_kind(&request.kind)?;
+        let name = request.name.trim();
+        let slug = tag_slug(name)?;
+
+        let tag = sqlx::query_as::<_, Tag>(
+            "INSERT INTO tags (kind, name, slug, created_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (kind, slug) DO NOTHING
+             RETURNING *"
+        )
+        .bind(&request.kind)
+        .bind(name)
+        .bind(&slug)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        tag.ok_or_else(|| ApiError::BadRequest(format!("A {} tag named '{}' already exists", request.kind, name)))
+    }
+
+    pub async fn update_tag(&self, pool: &PgPool, tag: &Tag, request: &UpdateTagRequest) -> Result<Tag, ApiError> {
+        let kind = request.kind.as_deref().unwrap_or(&tag.kind);
+        validate_kind(kind)?;
+        let name = request.name.as_deref().map(str::trim).unwrap_or(&tag.name);
+        let slug = tag_slug(name)?;
+
+        let exists: Option<bool> = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM tags WHERE kind = $1 AND slug = $2 AND id <> $3)"
+        )
+        .bind(kind)
+        .bind(&slug)
+        .bind(tag.id)
+        .fetch_one(pool)
+        .await?;
+
+        if exists == Some(true) {
+            return Err(ApiError::BadRequest(format!(
+                "A {} tag named '{}' already exists, merge the tags instead",
+                kind, name
+            )));
+        }
+
+        let tag = sqlx::query_as::<_, Tag>(
+            "UPDATE tags SET kind = $2, name = $3, slug = $4 WHERE id = $1 RETURNING *"
+        )
+        .bind(tag.id)
+        .bind(kind)
+        .bind(name)
+        .bind(&slug)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(tag)
+    }
+
+    pub async fn delete_tag(&self, pool: &PgPool, tag_id: &Uuid) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM tags WHERE id = $1")
+            .bind(tag_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Moves every game of `source` onto `target` and deletes `source`
+    pub async fn merge_tags(&self, pool: &PgPool, source: &Tag, target: &Tag) -> Result<Tag, ApiError> {
+        if source.id == target.id {
+            return Err(ApiError::BadRequest("Cannot merge a tag into itself".to_string()));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO game_tags (game_id, tag_id, added_by, created_at)
+             SELECT game_id, $2, added_by, created_at FROM game_tags WHERE tag_id = $1
+             ON CONFLICT (game_id, tag_id) DO NOTHING"
+        )
+        .bind(source.id)
+        .bind(target.id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM tags WHERE id = $1")
+            .bind(source.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(target.clone())
+    }
+
+    pub async fn add_game_tag(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        tag_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO game_tags (game_id, tag_id, added_by) VALUES ($1, $2, $3)
+             ON CONFLICT (game_id, tag_id) DO NOTHING"
+        )
+        .bind(game_id)
+        .bind(tag_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes a tag from a game. With `added_by`, only removes it if that user added it.
+    /// Returns whether the tag was removed.
+    pub async fn remove_game_tag(
+        &self,
+        pool: &PgPool,
+        game_id: &Uuid,
+        tag_id: &Uuid,
+        added_by: Option<&Uuid>,
+    ) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            "DELETE FROM game_tags
+             WHERE game_id = $1 AND tag_id = $2 AND ($3::UUID IS NULL OR added_by = $3)"
+        )
+        .bind(game_id)
+        .bind(tag_id)
+        .bind(added_by)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Fills `Game::tags` for a batch of games with a single query
+pub async fn load_game_tags(pool: &PgPool, games: &mut [Game]) -> Result<(), ApiError> {
+    if games.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = games.iter().map(|g| g.id).collect();
+    let tags = sqlx::query_as::<_, GameTag>(
+        "SELECT gt.game_id, t.id, t.kind, t.name, t.slug
+         FROM game_tags gt
+         JOIN tags t ON t.id = gt.tag_id
+         WHERE gt.game_id = ANY($1)
+         ORDER BY t.kind, t.name"
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    for game in games.iter_mut() {
+        game.tags = tags.iter().filter(|t| t.game_id == game.id).cloned().collect();
+    }
+
+    Ok(())
+}
+
+/// Replaces the game's genre tags, creating missing genres
+pub async fn replace_genre_tags(
+    tx: &mut Transaction<'_, Postgres>,
+    game_id: &Uuid,
+    genres: &[String],
+    user_id: Option<&Uuid>,
+) -> Result<(), ApiError> {
+    let mut tag_ids = Vec::with_capacity(genres.len());
+
+    for genre in genres {
+        let Ok(slug) = tag_slug(genre) else { continue };
+
+        // The no-op update makes RETURNING yield existing rows too
+        let tag_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO tags (kind, name, slug, created_by) VALUES ('genre', $1, $2, $3)
+             ON CONFLICT (kind, slug) DO UPDATE SET slug = EXCLUDED.slug
+             RETURNING id"
+        )
+        .bind(genre)
+        .bind(&slug)
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        tag_ids.push(tag_id);
+    }
+
+    sqlx::query(
+        "DELETE FROM game_tags
+         WHERE game_id = $1
+           AND tag_id IN (SELECT id FROM tags WHERE kind = 'genre')
+           AND tag_id <> ALL($2)"
+    )
+    .bind(game_id)
+    .bind(&tag_ids)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO game_tags (game_id, tag_id, added_by)
+         SELECT $1, UNNEST($2::UUID[]), $3
+         ON CONFLICT (game_id, tag_id) DO NOTHING"
+    )
+    .bind(game_id)
+    .bind(&tag_ids)
+    .bind(user_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Splits an imported genre string such as "Action, Platform" or "Shooter / Run and Gun"
+pub fn split_genres(genre: &str) -> Vec<String> {
+    normalize_tag_names(genre.split([',', ';', '/']).map(str::to_string).collect())
+}
+
+/// Trims names, drops empty ones and removes duplicates that share a slug
+pub fn normalize_tag_names(names: Vec<String>) -> Vec<String> {
+    let mut seen = Vec::new();
+    let mut normalized = Vec::new();
+
+    for name in names {
+        let name = name.trim().to_string();
+        let Ok(slug) = tag_slug(&name) else { continue };
+        if !seen.contains(&slug) {
+            seen.push(slug);
+            normalized.push(name);
+        }
+    }
+
+    normalized
+}
+
+/// URL-safe tag identifier, e.g. "Shoot'em up" -> "shoot-em-up".
+/// Must stay in line with the slug expression in migration 014.
+pub fn tag_slug(name: &str) -> Result<String, ApiError> {
+    let mut slug = String::new();
+
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        return Err(ApiError::ValidationError(format!("Tag name '{}' has no letters or digits", name)));
+    }
+
+    Ok(slug)
+}
+
+fn validate_kind(kind: &str) -> Result<(), ApiError> {
+    if !TAG_KINDS.contains(&kind) {
+        return Err(ApiError::ValidationError(format!(
+            "Invalid tag kind '{}', expected one of {}",
+            kind,
+            TAG_KINDS.join(", ")
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::models::{Game, GameTitle, GameVariant};
+use crate::services::{game_visible_to, normalize_title, strip_title_tags};
+use crate::utils::ApiError;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Groups the regional releases and revisions of a game under one title
+#[derive(Clone, Default)]
+pub struct TitleService;
+
+impl TitleService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Puts every game without a title under the title matching its normalised name,
+    /// creating titles as needed. Returns the number of games grouped.
+    pub async fn assign_titles(&self, pool: &PgPool) -> Result<u64, ApiError> {
+        let games = sqlx::query_as::<_, Game>("SELECT * FROM games WHERE title_id IS NULL ORDER BY created_at")
+            .fetch_all(pool)
+            .await?;
+
+        let mut groups: BTreeMap<(String, String), Vec<&Game>> = BTreeMap::new();
+        for game in &games {
+            let key = normalize_title(&game.title);
+            if !key.is_empty() {
+                groups.entry((game.console_id.clone(), key)).or_default().push(game);
+            }
+        }
+
+        let mut grouped = 0;
+        let mut tx = pool.begin().await?;
+
+        for ((console_id, key), games) in groups {
+            // An existing title keeps its name, new ones are named after the oldest release
+            let title_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO game_titles (console_id, name, normalized_name)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (console_id, normalized_name) DO UPDATE SET name = game_titles.name
+                 RETURNING id"
+            )
+            .bind(&console_id)
+            .bind(title_name(&games[0].title))
+            .bind(&key)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            let ids: Vec<Uuid> = games.iter().map(|g| g.id).collect();
+            grouped += sqlx::query("UPDATE games SET title_id = $1 WHERE id = ANY($2)")
+                .bind(title_id)
+                .bind(&ids)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        tx.commit().await?;
+
+        Ok(grouped)
+    }
+
+    pub async fn get_title(&self, pool: &PgPool, title_id: &Uuid) -> Result<GameTitle, ApiError> {
+        let title = sqlx::query_as::<_, GameTitle>("SELECT * FROM game_titles WHERE id = $1")
+            .bind(title_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Title not found".to_string()))?;
+
+        Ok(title)
+    }
+
+    /// Moves a game to another title of its console, or to a new title of its own when
+    /// `title_id` is `None`. Titles left without games are removed.
+    pub async fn set_game_title(
+        &self,
+        pool: &PgPool,
+        game: &Game,
+        title_id: Option<&Uuid>,
+    ) -> Result<GameTitle, ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let title = match title_id {
+            Some(title_id) => {
+                let title = self.get_title(pool, title_id).await?;
+                if title.console_id != game.console_id {
+                    return Err(ApiError::BadRequest(format!(
+                        "{} is a {} title, not {}",
+                        title.name, title.console_id, game.console_id
+                    )));
+                }
+                title
+            }
+            // Without a grouping key, so later regrouping leaves it alone
+            None => sqlx::query_as::<_, GameTitle>(
+                "INSERT INTO game_titles (console_id, name) VALUES ($1, $2) RETURNING *"
+            )
+            .bind(&game.console_id)
+            .bind(title_name(&game.title))
+            .fetch_one(&mut *tx)
+            .await?,
+        };
+
+        sqlx::query("UPDATE games SET title_id = $2 WHERE id = $1")
+            .bind(game.id)
+            .bind(title.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM game_titles t WHERE NOT EXISTS (SELECT 1 FROM games g WHERE g.title_id = t.id)")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(title)
+    }
+
+    pub async fn get_preferred_regions(&self, pool: &PgPool, user_id: &Uuid) -> Result<Vec<String>, ApiError> {
+        let regions: Option<Vec<String>> =
+            sqlx::query_scalar("SELECT preferred_regions FROM user_settings WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(regions.unwrap_or_default())
+    }
+
+    pub async fn set_preferred_regions(
+        &self,
+        pool: &PgPool,
+        user_id: &Uuid,
+        regions: &[String],
+    ) -> Result<Vec<String>, ApiError> {
+        let mut cleaned: Vec<String> = Vec::new();
+        for region in regions.iter().map(|r| r.trim()) {
+            if region.is_empty() || region.len() > 50 {
+                return Err(ApiError::ValidationError(format!("Invalid region '{}'", region)));
+            }
+            if !cleaned.iter().any(|r| r.eq_ignore_ascii_case(region)) {
+                cleaned.push(region.to_string());
+            }
+        }
+
+        let regions: Vec<String> = sqlx::query_scalar(
+            "INSERT INTO user_settings (user_id, preferred_regions) VALUES ($1, $2)
+             ON CONFLICT (user_id) DO UPDATE SET preferred_regions = EXCLUDED.preferred_regions, updated_at = NOW()
+             RETURNING preferred_regions"
+        )
+        .bind(user_id)
+        .bind(&cleaned)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(regions)
+    }
+}
+
+/// Fills each game's other releases the user can see
+pub async fn load_game_variants(pool: &PgPool, games: &mut [Game], user_id: &Uuid) -> Result<(), ApiError> {
+    let title_ids: Vec<Uuid> = games.iter().filter_map(|g| g.title_id).collect();
+    if title_ids.is_empty() {
+        return Ok(());
+    }
+
+    let variants = sqlx::query_as::<_, GameVariant>(&format!(
+        "SELECT g.title_id, g.id, g.title, g.region, g.rom_filename FROM games g
+         WHERE g.title_id = ANY($1) AND {}
+         ORDER BY g.region NULLS LAST, g.title",
+        game_visible_to("$2")
+    ))
+    .bind(&title_ids)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    for game in games.iter_mut() {
+        game.variants = variants
+            .iter()
+            .filter(|v| Some(v.title_id) == game.title_id && v.id != game.id)
+            .cloned()
+            .collect();
+    }
+
+    Ok(())
+}
+
+/// Display name of a title: the release title without its region and revision tags
+fn title_name(title: &str) -> String {
+    let name = strip_title_tags(title);
+    if name.is_empty() {
+        title.to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_regional_releases_and_revisions() {
+        let key = normalize_title("Sonic The Hedgehog (USA, Europe)");
+        for release in [
+            "Sonic The Hedgehog (Japan, Korea)",
+            "Sonic the Hedgehog (USA, Europe) (Rev 1)",
+            "Sonic The Hedgehog [b1]",
+            "Sonic  The  Hedgehog (Virtual Console)",
+        ] {
+            assert_eq!(normalize_title(release), key, "{}", release);
+        }
+        assert_ne!(normalize_title("Sonic The Hedgehog 2 (World)"), key);
+    }
+
+    #[test]
+    fn names_titles_without_tags() {
+        assert_eq!(strip_title_tags("Super Mario World (USA) [!]"), "Super Mario World");
+        assert_eq!(strip_title_tags("Street Fighter II (Japan) (Rev A)"), "Street Fighter II");
+        assert_eq!(strip_title_tags("Castlevania (Europe) (Beta [a])"), "Castlevania");
+        assert_eq!(title_name("Super Mario World (USA)"), "Super Mario World");
+        // Nothing is left once tags are removed, so the full name is kept
+        assert_eq!(title_name("(Prototype)"), "(Prototype)");
+    }
+}
@@ -0,0 +1,232 @@
+use crate::models::{
+    CreateUserGroupRequest, GameVisibility, UpdateVisibilityRequest, UserGroup, UserGroupSummary,
+    GAME_VISIBILITIES,
+};
+use crate::utils::ApiError;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Game visibility settings and the user groups games can be shared with
+#[derive(Clone, Default)]
+pub struct VisibilityService;
+
+impl VisibilityService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn get_groups(&self, pool: &PgPool) -> Result<Vec<UserGroupSummary>, ApiError> {
+        let groups = sqlx::query_as::<_, UserGroupSummary>(
+            "SELECT ug.*,
+                    COALESCE(array_agg(m.user_id ORDER BY m.added_at) FILTER (WHERE m.user_id IS NOT NULL), '{}') AS member_ids
+             FROM user_groups ug
+             LEFT JOIN user_group_members m ON m.group_id = ug.id
+             GROUP BY ug.id
+             ORDER BY ug.name"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    pub async fn create_group(
+        &self,
+        pool: &PgPool,
+        request: &CreateUserGroupRequest,
+    ) -> Result<UserGroup, ApiError> {
+        let name = request.name.trim();
+        let exists: Option<bool> =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM user_groups WHERE lower(name) = lower($1))")
+                .bind(name)
+                .fetch_one(pool)
+                .await?;
+        if exists == Some(true) {
+            return Err(ApiError::BadRequest(format!("Group '{}' already exists", name)));
+        }
+
+        let group = sqlx::query_as::<_, UserGroup>(
+            "INSERT INTO user_groups (name, description) VALUES ($1, $2) RETURNING *"
+        )
+        .bind(name)
+        .bind(&request.description)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(group)
+    }
+
+    /// Deletes a group. Games shared only with it stay shared with nobody but their uploader.
+    pub async fn delete_group(&self, pool: &PgPool, group_id: &Uuid) -> Result<(), ApiError> {
+        let deleted = sqlx::query("DELETE FROM user_groups WHERE id = $1")
+            .bind(group_id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(ApiError::NotFound("Group not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_group_members(
+        &self,
+        pool: &PgPool,
+        group_id: &Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<(), ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let exists: Option<bool> = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM user_groups WHERE id = $1)")
+            .bind(group_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if exists != Some(true) {
+            return Err(ApiError::NotFound("Group not found".to_string()));
+        }
+        check_ids_exist(&mut tx, "users", user_ids).await?;
+
+        sqlx::query("DELETE FROM user_group_members WHERE group_id = $1 AND NOT (user_id = ANY($2))")
+            .bind(group_id)
+            .bind(user_ids)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO user_group_members (group_id, user_id)
+             SELECT $1, u FROM UNNEST($2::uuid[]) AS u
+             ON CONFLICT DO NOTHING"
+        )
+        .bind(group_id)
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn get_game_visibility(&self, pool: &PgPool, game_id: &Uuid) -> Result<GameVisibility, ApiError> {
+        let (visibility, uploaded_by): (String, Option<Uuid>) =
+            sqlx::query_as("SELECT visibility, uploaded_by FROM games WHERE id = $1")
+                .bind(game_id)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+
+        let user_ids: Vec<Uuid> =
+            sqlx::query_scalar("SELECT user_id FROM game_user_shares WHERE game_id = $1 ORDER BY user_id")
+                .bind(game_id)
+                .fetch_all(pool)
+                .await?;
+        let group_ids: Vec<Uuid> =
+            sqlx::query_scalar("SELECT group_id FROM game_group_shares WHERE game_id = $1 ORDER BY group_id")
+                .bind(game_id)
+                .fetch_all(pool)
+                .await?;
+
+        Ok(GameVisibility {
+            game_id: *game_id,
+            visibility,
+            uploaded_by,
+            user_ids,
+            group_ids,
+        })
+    }
+
+    /// Changes the visibility of several games at once. Share lists present in the request
+    /// replace the current ones, so switching a game back to `shared` restores its old shares.
+    /// Returns the number of games updated.
+    pub async fn set_visibility(
+        &self,
+        pool: &PgPool,
+        game_ids: &[Uuid],
+        request: &UpdateVisibilityRequest,
+        uploaded_by: Option<&Uuid>,
+    ) -> Result<u64, ApiError> {
+        if !GAME_VISIBILITIES.contains(&request.visibility.as_str()) {
+            return Err(ApiError::ValidationError(format!(
+                "Visibility must be one of: {}",
+                GAME_VISIBILITIES.join(", ")
+            )));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        if let Some(user) = uploaded_by {
+            check_ids_exist(&mut tx, "users", std::slice::from_ref(user)).await?;
+        }
+
+        let updated = sqlx::query(
+            "UPDATE games SET visibility = $2, uploaded_by = COALESCE($3, uploaded_by)
+             WHERE id = ANY($1)"
+        )
+        .bind(game_ids)
+        .bind(&request.visibility)
+        .bind(uploaded_by)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if let Some(user_ids) = &request.user_ids {
+            check_ids_exist(&mut tx, "users", user_ids).await?;
+            sqlx::query("DELETE FROM game_user_shares WHERE game_id = ANY($1)")
+                .bind(game_ids)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "INSERT INTO game_user_shares (game_id, user_id)
+                 SELECT g.id, u FROM games g CROSS JOIN UNNEST($2::uuid[]) AS u
+                 WHERE g.id = ANY($1)
+                 ON CONFLICT DO NOTHING"
+            )
+            .bind(game_ids)
+            .bind(user_ids)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(group_ids) = &request.group_ids {
+            check_ids_exist(&mut tx, "user_groups", group_ids).await?;
+            sqlx::query("DELETE FROM game_group_shares WHERE game_id = ANY($1)")
+                .bind(game_ids)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "INSERT INTO game_group_shares (game_id, group_id)
+                 SELECT g.id, gr FROM games g CROSS JOIN UNNEST($2::uuid[]) AS gr
+                 WHERE g.id = ANY($1)
+                 ON CONFLICT DO NOTHING"
+            )
+            .bind(game_ids)
+            .bind(group_ids)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+}
+
+/// Fails with a validation error naming the first id missing from `table`
+async fn check_ids_exist(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &str,
+    ids: &[Uuid],
+) -> Result<(), ApiError> {
+    let missing: Option<Uuid> = sqlx::query_scalar(&format!(
+        "SELECT v.id FROM UNNEST($1::uuid[]) AS v(id) WHERE NOT EXISTS (SELECT 1 FROM {} t WHERE t.id = v.id) LIMIT 1",
+        table
+    ))
+    .bind(ids)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    match missing {
+        Some(id) => Err(ApiError::ValidationError(format!("Unknown id {}", id))),
+        None => Ok(()),
+    }
+}
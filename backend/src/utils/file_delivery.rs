@@ -0,0 +1,206 @@
+use crate::utils::ApiError;
+use actix_files::HttpRange;
+use actix_web::body::SizedStream;
+use actix_web::http::header::{
+    self, AcceptEncoding, ContentEncoding, Encoding, EntityTag, IfNoneMatch, IfRange,
+};
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+use futures_util::stream::{self, Stream};
+use std::io::{self, SeekFrom};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+const CHUNK_SIZE: u64 = 256 * 1024;
+
+/// Encodings `middleware::Compress` negotiates, used to predict whether it will compress a response
+const COMPRESS_ENCODINGS: [Encoding; 5] = [
+    Encoding::identity(),
+    Encoding::brotli(),
+    Encoding::gzip(),
+    Encoding::deflate(),
+    Encoding::zstd(),
+];
+
+/// Pre-compressed sidecars looked up next to the file, e.g. `game.sfc.br`
+const SIDECARS: [(&str, Encoding, ContentEncoding); 2] = [
+    ("br", Encoding::brotli(), ContentEncoding::Brotli),
+    ("gz", Encoding::gzip(), ContentEncoding::Gzip),
+];
+
+/// A file served with checksum-based validators, single byte ranges and content encoding
+pub struct FileDelivery<'a> {
+    pub path: &'a Path,
+    /// Identifies the file contents (e.g. its CRC32); combined with the length to form the ETag
+    pub checksum: &'a str,
+    pub cache_control: &'static str,
+    /// Whether the response may be compressed on the fly by `middleware::Compress`
+    pub compressible: bool,
+}
+
+struct Variant {
+    path: PathBuf,
+    length: u64,
+    etag: EntityTag,
+    encoding: Option<ContentEncoding>,
+}
+
+impl FileDelivery<'_> {
+    /// Builds the response for `req`: 304 when the client's copy is current, 206 for a satisfiable
+    /// `Range`, otherwise the whole file, preferring a pre-compressed sidecar the client accepts.
+    pub async fn respond(&self, req: &HttpRequest) -> Result<HttpResponse, ApiError> {
+        let length = tokio::fs::metadata(self.path)
+            .await
+            .map_err(|e| ApiError::NotFound(format!("File not found: {}", e)))?
+            .len();
+        let etag = EntityTag::new_strong(format!("{}-{:x}", self.checksum, length));
+
+        // A stale If-Range means the client's partial copy is outdated, so send everything
+        let range = req
+            .headers()
+            .get(header::RANGE)
+            .filter(|_| match req.get_header::<IfRange>() {
+                Some(IfRange::EntityTag(tag)) => tag.strong_eq(&etag),
+                Some(IfRange::Date(_)) => false,
+                None => true,
+            })
+            .map(|value| value.to_str().unwrap_or_default().to_string());
+
+        let accept_encoding = req.get_header::<AcceptEncoding>();
+        let variant = match (&range, &accept_encoding) {
+            (None, Some(accept)) => self.find_sidecar(accept, length).await,
+            _ => None,
+        };
+        let variant = variant.unwrap_or_else(|| {
+            let compressed = self.compressible
+                && range.is_none()
+                && accept_encoding
+                    .as_ref()
+                    .and_then(|accept| accept.negotiate(COMPRESS_ENCODINGS.iter()))
+                    .is_some_and(|encoding| encoding != Encoding::identity());
+
+            Variant {
+                path: self.path.to_path_buf(),
+                length,
+                // A compressed body is not byte-identical to the file, so it only gets a weak validator
+                etag: if compressed {
+                    EntityTag::new_weak(etag.tag().to_string())
+                } else {
+                    etag
+                },
+                encoding: (!self.compressible).then_some(ContentEncoding::Identity),
+            }
+        });
+
+        let mut response = HttpResponse::Ok();
+        response
+            .insert_header((header::ETAG, variant.etag.to_string()))
+            .insert_header((header::CACHE_CONTROL, self.cache_control))
+            .insert_header((header::ACCEPT_RANGES, "bytes"));
+        if self.compressible || variant.encoding.is_some_and(|e| e != ContentEncoding::Identity) {
+            response.insert_header((header::VARY, "Accept-Encoding"));
+        }
+
+        let not_modified = match req.get_header::<IfNoneMatch>() {
+            Some(IfNoneMatch::Any) => true,
+            Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&variant.etag)),
+            None => false,
+        };
+        if not_modified {
+            return Ok(response.status(StatusCode::NOT_MODIFIED).finish());
+        }
+
+        response.content_type(
+            actix_files::file_extension_to_mime(
+                self.path.extension().and_then(|e| e.to_str()).unwrap_or_default(),
+            )
+            .to_string(),
+        );
+        if let Some(encoding) = variant.encoding {
+            response.insert_header((header::CONTENT_ENCODING, encoding.to_header_value()));
+        }
+
+        let (offset, count) = match range {
+            Some(range) => match HttpRange::parse(&range, variant.length).ok().and_then(|r| r.first().copied()) {
+                Some(range) => {
+                    response.status(StatusCode::PARTIAL_CONTENT).insert_header((
+                        header::CONTENT_RANGE,
+                        format!("bytes {}-{}/{}", range.start, range.start + range.length - 1, variant.length),
+                    ));
+                    (range.start, range.length)
+                }
+                None => {
+                    return Ok(response
+                        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                        .insert_header((header::CONTENT_RANGE, format!("bytes */{}", variant.length)))
+                        .finish());
+                }
+            },
+            None => (0, variant.length),
+        };
+
+        let mut file = tokio::fs::File::open(&variant.path)
+            .await
+            .map_err(|e| ApiError::NotFound(format!("File not found: {}", e)))?;
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+        Ok(response.body(SizedStream::new(count, read_chunks(file, count))))
+    }
+
+    /// Picks the sidecar the client prefers among those on disk. Sidecars older than the file
+    /// are ignored since they were compressed from a previous version.
+    async fn find_sidecar(&self, accept: &AcceptEncoding, length: u64) -> Option<Variant> {
+        let modified = tokio::fs::metadata(self.path).await.ok()?.modified().ok()?;
+        let mut available = vec![Encoding::identity()];
+        let mut sidecars = Vec::new();
+
+        for (extension, encoding, content_encoding) in SIDECARS {
+            let mut path = self.path.as_os_str().to_owned();
+            path.push(".");
+            path.push(extension);
+            let path = PathBuf::from(path);
+
+            let Ok(metadata) = tokio::fs::metadata(&path).await else { continue };
+            if metadata.modified().is_ok_and(|m| m >= modified) {
+                available.push(encoding.clone());
+                sidecars.push((encoding, content_encoding, path, metadata.len()));
+            }
+        }
+
+        let chosen = accept.negotiate(available.iter())?;
+        let (_, content_encoding, path, sidecar_length) = sidecars.into_iter().find(|(e, ..)| *e == chosen)?;
+
+        Some(Variant {
+            path,
+            length: sidecar_length,
+            etag: EntityTag::new_strong(format!(
+                "{}-{:x}-{}",
+                self.checksum,
+                length,
+                content_encoding.as_str()
+            )),
+            encoding: Some(content_encoding),
+        })
+    }
+}
+
+fn read_chunks(file: tokio::fs::File, count: u64) -> impl Stream<Item = Result<Bytes, io::Error>> {
+    stream::try_unfold((file, count), |(mut file, remaining)| async move {
+        if remaining == 0 {
+            return Ok(None);
+        }
+
+        let mut buffer = vec![0u8; remaining.min(CHUNK_SIZE) as usize];
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "file shrank while streaming"));
+        }
+        buffer.truncate(read);
+
+        Ok(Some((Bytes::from(buffer), (file, remaining - read as u64))))
+    })
+}
+
@@ -1,3 +1,5 @@
 pub mod errors;
+pub mod file_delivery;
 
 pub use errors::*;
+pub use file_delivery::*;
@@ -0,0 +1,107 @@
+//! Checks that restarting the server doesn't re-apply seed data an admin has changed.
+//!
+//! Needs a Postgres server: set `TEST_DATABASE_URL` to a URL whose user may create databases,
+//! e.g. `postgres://postgres@localhost:5432/postgres`. A throwaway database is created per run.
+//! Without it the test is skipped.
+
+use sqlx::{Connection, PgConnection};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+
+struct Server {
+    child: Child,
+    root: PathBuf,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Starts the server and waits until it listens, by which point migrations have run
+async fn start_server(database_url: &str) -> Server {
+    let root = std::env::temp_dir().join(format!("zerver-migrations-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let child = Command::new(env!("CARGO_BIN_EXE_zerver"))
+        .env("DATABASE_URL", database_url)
+        .env("JWT_SECRET", "migrations-test")
+        .env("SERVER_HOST", "127.0.0.1")
+        .env("SERVER_PORT", port.to_string())
+        .env("ROM_STORAGE_PATH", root.join("roms"))
+        .env("SAVE_STORAGE_PATH", root.join("saves"))
+        .env("MEDIA_STORAGE_PATH", root.join("media"))
+        .env("RUST_LOG", "error")
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("start zerver");
+    let server = Server { child, root };
+
+    let started = Instant::now();
+    while TcpStream::connect(("127.0.0.1", port)).await.is_err() {
+        assert!(started.elapsed() < Duration::from_secs(60), "server did not start");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    server
+}
+
+#[tokio::test]
+async fn seeded_consoles_stay_deleted_after_restart() {
+    let Ok(admin_url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL not set, skipping migrations test");
+        return;
+    };
+
+    let database = format!("zerver_test_{}", uuid::Uuid::new_v4().simple());
+    let mut admin = PgConnection::connect(&admin_url).await.expect("connect to TEST_DATABASE_URL");
+    sqlx::query(&format!("CREATE DATABASE {}", database))
+        .execute(&mut admin)
+        .await
+        .unwrap();
+    let (base, _) = admin_url.rsplit_once('/').expect("database URL with a path");
+    let database_url = format!("{}/{}", base, database);
+
+    let server = start_server(&database_url).await;
+    let mut db = PgConnection::connect(&database_url).await.unwrap();
+
+    // What deleting a console and clearing another's emulator config through the API leave behind
+    sqlx::query("DELETE FROM consoles WHERE id = 'gb'")
+        .execute(&mut db)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE consoles SET emulator_config = '{}' WHERE id = 'n64'")
+        .execute(&mut db)
+        .await
+        .unwrap();
+    drop(server);
+
+    let server = start_server(&database_url).await;
+
+    let gb: Option<String> = sqlx::query_scalar("SELECT id FROM consoles WHERE id = 'gb'")
+        .fetch_optional(&mut db)
+        .await
+        .unwrap();
+    assert_eq!(gb, None, "deleted seeded console came back after a restart");
+
+    let n64_config: serde_json::Value =
+        sqlx::query_scalar("SELECT emulator_config FROM consoles WHERE id = 'n64'")
+            .fetch_one(&mut db)
+            .await
+            .unwrap();
+    assert_eq!(n64_config, serde_json::json!({}));
+
+    drop(server);
+    db.close().await.unwrap();
+    sqlx::query(&format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", database))
+        .execute(&mut admin)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,257 @@
+//! End-to-end checks for `GET /api/v1/games/{id}/rom` against a running server.
+//!
+//! Needs a Postgres server: set `TEST_DATABASE_URL` to a URL whose user may create databases,
+//! e.g. `postgres://postgres@localhost:5432/postgres`. A throwaway database is created per run.
+//! Without it the test is skipped.
+
+use serde_json::Value;
+use sqlx::{Connection, PgConnection};
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const ROM_FILENAME: &str = "Delivery Test (USA).sfc";
+const ROM_SIZE: usize = 256 * 1024;
+
+struct Server {
+    child: Child,
+    port: u16,
+    root: PathBuf,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+struct Response {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+async fn request(port: u16, method: &str, path: &str, headers: &[(&str, &str)], body: Option<&Value>) -> Response {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.expect("connect to server");
+
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    let mut raw = format!(
+        "{} {} HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+        method,
+        path,
+        body.len()
+    );
+    for (name, value) in headers {
+        raw.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    raw.push_str("\r\n");
+    raw.push_str(&body);
+
+    stream.write_all(raw.as_bytes()).await.expect("send request");
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.expect("read response");
+
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .expect("response head");
+    let head = String::from_utf8_lossy(&response[..split]).to_string();
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse().ok())
+        .expect("status line");
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect::<HashMap<_, _>>();
+
+    let mut body = response[split + 4..].to_vec();
+    if headers.get("transfer-encoding").map(String::as_str) == Some("chunked") {
+        body = dechunk(&body);
+    }
+
+    Response { status, headers, body }
+}
+
+fn dechunk(mut data: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    while let Some(end) = data.windows(2).position(|w| w == b"\r\n") {
+        let size = usize::from_str_radix(String::from_utf8_lossy(&data[..end]).trim(), 16).unwrap_or(0);
+        if size == 0 {
+            break;
+        }
+        body.extend_from_slice(&data[end + 2..end + 2 + size]);
+        data = &data[end + 4 + size..];
+    }
+    body
+}
+
+fn rom_bytes() -> Vec<u8> {
+    // Repetitive like real cartridge dumps, so compression kicks in
+    (0..ROM_SIZE).map(|i| (i / 64 % 7) as u8).collect()
+}
+
+async fn start_server(database_url: &str) -> Server {
+    let root = std::env::temp_dir().join(format!("zerver-rom-delivery-{}", uuid::Uuid::new_v4()));
+    let roms = root.join("roms");
+    std::fs::create_dir_all(roms.join("snes")).unwrap();
+    std::fs::create_dir_all(root.join("saves")).unwrap();
+    std::fs::create_dir_all(root.join("media")).unwrap();
+    std::fs::write(roms.join("snes").join(ROM_FILENAME), rom_bytes()).unwrap();
+
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let child = Command::new(env!("CARGO_BIN_EXE_zerver"))
+        .env("DATABASE_URL", database_url)
+        .env("JWT_SECRET", "rom-delivery-test")
+        .env("SERVER_HOST", "127.0.0.1")
+        .env("SERVER_PORT", port.to_string())
+        .env("ROM_STORAGE_PATH", &roms)
+        .env("SAVE_STORAGE_PATH", root.join("saves"))
+        .env("MEDIA_STORAGE_PATH", root.join("media"))
+        .env("RUST_LOG", "error")
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("start zerver");
+    let server = Server { child, port, root };
+
+    // Migrations run before the listener binds
+    let started = Instant::now();
+    while TcpStream::connect(("127.0.0.1", port)).await.is_err() {
+        assert!(started.elapsed() < Duration::from_secs(60), "server did not start");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    server
+}
+
+#[tokio::test]
+async fn rom_supports_ranges_and_revalidation() {
+    let Ok(admin_url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL not set, skipping ROM delivery test");
+        return;
+    };
+
+    let database = format!("zerver_test_{}", uuid::Uuid::new_v4().simple());
+    let mut admin = PgConnection::connect(&admin_url).await.expect("connect to TEST_DATABASE_URL");
+    sqlx::query(&format!("CREATE DATABASE {}", database))
+        .execute(&mut admin)
+        .await
+        .unwrap();
+    let (base, _) = admin_url.rsplit_once('/').expect("database URL with a path");
+    let server = start_server(&format!("{}/{}", base, database)).await;
+    let port = server.port;
+
+    let auth = request(
+        port,
+        "POST",
+        "/api/v1/auth/register",
+        &[],
+        Some(&serde_json::json!({
+            "username": "delivery",
+            "email": "delivery@example.com",
+            "password": "correct horse battery"
+        })),
+    )
+    .await;
+    assert_eq!(auth.status, 201);
+    let token = serde_json::from_slice::<Value>(&auth.body).unwrap()["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let bearer = format!("Bearer {}", token);
+    let authorization = ("Authorization", bearer.as_str());
+
+    let scan = request(port, "POST", "/api/v1/games/scan", &[authorization], None).await;
+    assert_eq!(scan.status, 200);
+    let games = request(port, "GET", "/api/v1/games?console=snes", &[authorization], None).await;
+    let games = serde_json::from_slice::<Value>(&games.body).unwrap();
+    let game_id = games["games"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|g| g["rom_filename"] == ROM_FILENAME)
+        .expect("scanned test ROM")["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let rom_url = format!("/api/v1/games/{}/rom", game_id);
+    let rom = rom_bytes();
+
+    // Full download carries a strong checksum ETag and a revalidation policy
+    let full = request(port, "GET", &rom_url, &[authorization], None).await;
+    assert_eq!(full.status, 200);
+    assert_eq!(full.body, rom);
+    assert_eq!(full.header("accept-ranges"), Some("bytes"));
+    assert_eq!(full.header("cache-control"), Some("private, no-cache"));
+    let etag = full.header("etag").expect("ETag").to_string();
+    assert!(etag.starts_with('"'), "expected a strong ETag, got {}", etag);
+
+    // Revalidation with the current ETag is answered without a body
+    let cached = request(port, "GET", &rom_url, &[authorization, ("If-None-Match", &etag)], None).await;
+    assert_eq!(cached.status, 304);
+    assert!(cached.body.is_empty());
+    assert_eq!(cached.header("etag"), Some(etag.as_str()));
+
+    let stale = request(port, "GET", &rom_url, &[authorization, ("If-None-Match", "\"00000000-0\"")], None).await;
+    assert_eq!(stale.status, 200);
+
+    // Byte ranges are served uncompressed even when the client accepts gzip
+    let partial = request(
+        port,
+        "GET",
+        &rom_url,
+        &[authorization, ("Range", "bytes=1000-1999"), ("Accept-Encoding", "gzip")],
+        None,
+    )
+    .await;
+    assert_eq!(partial.status, 206);
+    assert_eq!(partial.header("content-range"), Some(format!("bytes 1000-1999/{}", ROM_SIZE).as_str()));
+    assert_ne!(partial.header("content-encoding"), Some("gzip"));
+    assert_eq!(partial.body, &rom[1000..2000]);
+
+    let suffix = request(port, "GET", &rom_url, &[authorization, ("Range", "bytes=-100")], None).await;
+    assert_eq!(suffix.status, 206);
+    assert_eq!(suffix.body, &rom[ROM_SIZE - 100..]);
+
+    // A partial copy from an older version gets the whole file instead
+    let outdated = request(
+        port,
+        "GET",
+        &rom_url,
+        &[authorization, ("Range", "bytes=0-99"), ("If-Range", "\"00000000-0\"")],
+        None,
+    )
+    .await;
+    assert_eq!(outdated.status, 200);
+    assert_eq!(outdated.body.len(), ROM_SIZE);
+
+    let unsatisfiable = request(port, "GET", &rom_url, &[authorization, ("Range", "bytes=999999999-")], None).await;
+    assert_eq!(unsatisfiable.status, 416);
+
+    // Cartridge ROMs are compressed on the fly and only keep a weak validator
+    let compressed = request(port, "GET", &rom_url, &[authorization, ("Accept-Encoding", "gzip")], None).await;
+    assert_eq!(compressed.status, 200);
+    assert_eq!(compressed.header("content-encoding"), Some("gzip"));
+    assert!(compressed.body.len() < ROM_SIZE / 4);
+    assert_eq!(compressed.header("etag"), Some(format!("W/{}", etag).as_str()));
+
+    drop(server);
+    sqlx::query(&format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", database))
+        .execute(&mut admin)
+        .await
+        .unwrap();
+}
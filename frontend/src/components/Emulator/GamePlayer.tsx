@@ -1,12 +1,75 @@
-import { useEffect } from 'react';
+import { useEffect, useState } from 'react';
 import { EmulatorJS } from 'react-emulatorjs';
 import { useGameStore } from '../../stores/gameStore';
+import { gameService } from '../../services/gameService';
+import { biosService } from '../../services/biosService';
+import { emulatorService } from '../../services/emulatorService';
 import './GamePlayer.css';
 
+declare global {
+  interface Window {
+    // Read by the EmulatorJS loader, as [description, code] pairs
+    EJS_cheats?: [string, string][];
+  }
+}
+
 export const GamePlayer = () => {
   const currentGame = useGameStore((state) => state.currentGame);
+  const currentPatch = useGameStore((state) => state.currentPatch);
   const exitGame = useGameStore((state) => state.exitGame);
   const isPlaying = useGameStore((state) => state.isPlaying);
+  const [romUrl, setRomUrl] = useState<string | null>(null);
+  const [biosUrl, setBiosUrl] = useState<string | undefined>(undefined);
+  const [core, setCore] = useState<string | null>(null);
+  const [dataPath, setDataPath] = useState<string | null>(null);
+
+  useEffect(() => {
+    emulatorService
+      .getDataPath()
+      .then(setDataPath)
+      .catch((error) => console.error('No EmulatorJS release installed on the server:', error));
+  }, []);
+
+  useEffect(() => {
+    // EmulatorJS fetches the ROM and BIOS itself and can't send our token, so hand it signed URLs
+    setRomUrl(null);
+    setBiosUrl(undefined);
+    setCore(null);
+    if (!currentGame) return;
+
+    let cancelled = false;
+    gameService
+      .getEmulatorConfig(currentGame.id)
+      .then((config) => {
+        if (config.missing_bios.length > 0) {
+          console.warn('Missing BIOS files:', config.missing_bios.join(', '));
+        }
+        const bios = config.bios_id
+          ? biosService.getBiosUrl(config.bios_id).catch((error) => {
+              // Most cores boot without a BIOS, so carry on without one
+              console.error('Failed to get BIOS URL:', error);
+              return undefined;
+            })
+          : undefined;
+        const cheats = gameService.getCheats(currentGame.id).catch((error) => {
+          console.error('Failed to load cheats:', error);
+          return [];
+        });
+        return Promise.all([config.core, gameService.getRomUrl(currentGame.id, currentPatch?.id), bios, cheats]);
+      })
+      .then(([core, rom, bios, cheats]) => {
+        if (cancelled) return;
+        window.EJS_cheats = cheats.map((cheat): [string, string] => [cheat.description, cheat.code]);
+        setCore(core);
+        setBiosUrl(bios);
+        setRomUrl(rom);
+      })
+      .catch((error) => console.error('Failed to prepare emulator:', error));
+
+    return () => {
+      cancelled = true;
+    };
+  }, [currentGame, currentPatch]);
 
   useEffect(() => {
     // Disable scrolling when playing
@@ -43,7 +106,8 @@ export const GamePlayer = () => {
     return null;
   }
 
-  const getCoreSystem = (consoleId: string): string => {
+  // Maps the built-in consoles' cores to EmulatorJS names, other cores pass through
+  const getCoreSystem = (emulatorCore: string): string => {
     const coreMapping: Record<string, string> = {
       nes: 'nes',
       snes: 'snes',
@@ -56,14 +120,17 @@ export const GamePlayer = () => {
       dreamcast: 'segaDC',
       ps2: 'ps2',
     };
-    return coreMapping[consoleId] || consoleId;
+    return coreMapping[emulatorCore] || emulatorCore;
   };
 
   return (
     <div className="game-player-container">
       <div className="game-player-header">
         <div className="game-info">
-          <span className="game-title">{currentGame.title}</span>
+          <span className="game-title">
+            {currentGame.title}
+            {currentPatch && ` [${currentPatch.name}]`}
+          </span>
           <span className="game-console">{currentGame.console_id.toUpperCase()}</span>
         </div>
         <div className="game-controls">
@@ -83,11 +150,14 @@ export const GamePlayer = () => {
       </div>
 
       <div className="emulator-wrapper">
-        <EmulatorJS
-          EJS_core={getCoreSystem(currentGame.console_id) as any}
-          EJS_gameUrl={`/api/v1/games/${currentGame.id}/rom`}
-          EJS_pathtodata="/data/"
-        />
+        {romUrl && core && dataPath && (
+          <EmulatorJS
+            EJS_core={getCoreSystem(core) as any}
+            EJS_gameUrl={romUrl}
+            EJS_biosUrl={biosUrl}
+            EJS_pathtodata={dataPath}
+          />
+        )}
       </div>
     </div>
   );
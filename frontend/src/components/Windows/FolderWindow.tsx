@@ -1,7 +1,7 @@
 import { useEffect, useState } from 'react';
 import { gameService } from '../../services/gameService';
 import { useGameStore } from '../../stores/gameStore';
-import type { Game } from '../../types';
+import type { Game, GamePatch } from '../../types';
 import './FolderWindow.css';
 
 interface FolderWindowProps {
@@ -13,6 +13,8 @@ export const FolderWindow = ({ consoleId }: FolderWindowProps) => {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [selectedGame, setSelectedGame] = useState<string | null>(null);
+  // Release picked in a title's variant selector, keyed by the listed game
+  const [variantChoice, setVariantChoice] = useState<Record<string, string>>({});
   const launchGame = useGameStore((state) => state.launchGame);
 
   useEffect(() => {
@@ -39,10 +41,21 @@ export const FolderWindow = ({ consoleId }: FolderWindowProps) => {
     setSelectedGame(gameId);
   };
 
-  const handleGameDoubleClick = (game: Game) => {
-    launchGame(game);
+  const handleGameDoubleClick = async (game: Game, patch?: GamePatch) => {
+    const variantId = variantChoice[game.id];
+    if (!patch && variantId && variantId !== game.id) {
+      launchGame(await gameService.getGame(variantId));
+      return;
+    }
+    launchGame(game, patch);
   };
 
+  // Patched variants are listed as their own entries right below the base game
+  const entries = games.flatMap((game) => [
+    { key: game.id, game, patch: undefined as GamePatch | undefined },
+    ...(game.patches ?? []).map((patch) => ({ key: `${game.id}:${patch.id}`, game, patch })),
+  ]);
+
   const getConsoleName = () => {
     const consoleNames: Record<string, string> = {
       nes: 'NES',
@@ -122,15 +135,34 @@ export const FolderWindow = ({ consoleId }: FolderWindowProps) => {
           </div>
         ) : (
           <div className="game-list">
-            {games.map((game) => (
+            {entries.map(({ key, game, patch }) => (
               <div
-                key={game.id}
-                className={`game-item ${selectedGame === game.id ? 'selected' : ''}`}
-                onClick={() => handleGameClick(game.id)}
-                onDoubleClick={() => handleGameDoubleClick(game)}
+                key={key}
+                className={`game-item ${selectedGame === key ? 'selected' : ''}`}
+                onClick={() => handleGameClick(key)}
+                onDoubleClick={() => handleGameDoubleClick(game, patch)}
               >
-                <div className="game-icon">🎮</div>
-                <div className="game-name">{game.title}</div>
+                <div className="game-icon">{patch ? '🩹' : '🎮'}</div>
+                <div className="game-name">
+                  {game.title}
+                  {patch && ` [${patch.name}]`}
+                  {!patch && game.variants?.length > 0 && (
+                    <select
+                      className="game-variant"
+                      value={variantChoice[game.id] ?? game.id}
+                      onClick={(e) => e.stopPropagation()}
+                      onDoubleClick={(e) => e.stopPropagation()}
+                      onChange={(e) => setVariantChoice({ ...variantChoice, [game.id]: e.target.value })}
+                    >
+                      <option value={game.id}>{game.region ?? game.title}</option>
+                      {game.variants.map((variant) => (
+                        <option key={variant.id} value={variant.id}>
+                          {variant.region ?? variant.title}
+                        </option>
+                      ))}
+                    </select>
+                  )}
+                </div>
                 <div className="game-size">{formatFileSize(game.rom_size_bytes)}</div>
                 <div className="game-date">
                   {new Date(game.created_at).toLocaleDateString()}
@@ -0,0 +1,36 @@
+import { api } from './api';
+import { downloadService } from './downloadService';
+import type { BiosFile, BiosStatusResponse, ConsoleBiosStatus } from '../types';
+
+export const biosService = {
+  async getStatus(consoleId?: string): Promise<ConsoleBiosStatus[]> {
+    const response = await api.get<BiosStatusResponse>('/bios', {
+      params: { console: consoleId },
+    });
+    return response.data.consoles;
+  },
+
+  // The server identifies the file by its MD5, so any filename works
+  async uploadBios(file: Blob, consoleId?: string): Promise<BiosFile> {
+    const formData = new FormData();
+    formData.append('file', file);
+    if (consoleId) {
+      formData.append('console_id', consoleId);
+    }
+
+    const response = await api.post<BiosFile>('/bios', formData, {
+      headers: {
+        'Content-Type': 'multipart/form-data',
+      },
+    });
+    return response.data;
+  },
+
+  async deleteBios(biosId: string): Promise<void> {
+    await api.delete(`/bios/${biosId}`);
+  },
+
+  getBiosUrl(biosId: string): Promise<string> {
+    return downloadService.getSignedUrl('bios', biosId);
+  },
+};
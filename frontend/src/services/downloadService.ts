@@ -0,0 +1,11 @@
+import { api } from './api';
+import type { DownloadKind, DownloadUrl } from '../types';
+
+export const downloadService = {
+  // Short-lived signed URL for clients that can't send the Authorization header (e.g. EmulatorJS)
+  async getSignedUrl(kind: DownloadKind, id: string, patch?: string): Promise<string> {
+    const response = await api.post<DownloadUrl>('/downloads', { kind, id, patch });
+    const base = new URL(api.defaults.baseURL ?? '', window.location.origin);
+    return new URL(response.data.url, base).toString();
+  },
+};
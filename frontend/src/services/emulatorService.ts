@@ -0,0 +1,11 @@
+import { api } from './api';
+import type { EmulatorJsInfo } from '../types';
+
+export const emulatorService = {
+  // EmulatorJS data directory served by the backend, as an absolute URL for EJS_pathtodata
+  async getDataPath(): Promise<string> {
+    const response = await api.get<EmulatorJsInfo>('/emulatorjs');
+    const base = new URL(api.defaults.baseURL ?? '', window.location.origin);
+    return new URL(response.data.path_to_data, base).toString();
+  },
+};
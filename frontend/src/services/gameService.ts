@@ -1,5 +1,17 @@
 import { api } from './api';
-import type { Game, GameListResponse, Console } from '../types';
+import { downloadService } from './downloadService';
+import type {
+  Game,
+  GameListResponse,
+  Console,
+  GameEmulatorConfig,
+  GameCheat,
+  CheatFormat,
+  GameDocument,
+  DocumentKind,
+  GameVisibility,
+  GameVisibilityLevel,
+} from '../types';
 
 export const gameService = {
   async getGames(consoleId?: string, limit = 50, offset = 0): Promise<GameListResponse> {
@@ -7,6 +19,7 @@ export const gameService = {
     if (consoleId) params.append('console', consoleId);
     params.append('limit', limit.toString());
     params.append('offset', offset.toString());
+    params.append('group_titles', 'true');
 
     const response = await api.get<GameListResponse>(`/games?${params}`);
     return response.data;
@@ -22,8 +35,116 @@ export const gameService = {
     return response.data.consoles;
   },
 
-  getRomUrl(gameId: string): string {
-    return `${api.defaults.baseURL}/games/${gameId}/rom`;
+  async getEmulatorConfig(gameId: string): Promise<GameEmulatorConfig> {
+    const response = await api.get<GameEmulatorConfig>(`/games/${gameId}/emulator`);
+    return response.data;
+  },
+
+  async getPreferredRegions(): Promise<string[]> {
+    const response = await api.get<{ preferred_regions: string[] }>('/users/me/regions');
+    return response.data.preferred_regions;
+  },
+
+  async setPreferredRegions(regions: string[]): Promise<string[]> {
+    const response = await api.put<{ preferred_regions: string[] }>('/users/me/regions', {
+      preferred_regions: regions,
+    });
+    return response.data.preferred_regions;
+  },
+
+  async getVisibility(gameId: string): Promise<GameVisibility> {
+    const response = await api.get<GameVisibility>(`/games/${gameId}/visibility`);
+    return response.data;
+  },
+
+  // Omitted share lists are left as they are
+  async updateVisibility(
+    gameId: string,
+    visibility: GameVisibilityLevel,
+    shareWith: { user_ids?: string[]; group_ids?: string[] } = {}
+  ): Promise<GameVisibility> {
+    const response = await api.put<GameVisibility>(`/games/${gameId}/visibility`, {
+      visibility,
+      ...shareWith,
+    });
+    return response.data;
+  },
+
+  async getCheats(gameId: string): Promise<GameCheat[]> {
+    const response = await api.get<{ cheats: GameCheat[] }>(`/games/${gameId}/cheats`);
+    return response.data.cheats;
+  },
+
+  // The format is detected from the code when omitted
+  async createCheat(
+    gameId: string,
+    cheat: { description: string; code: string; format?: CheatFormat; is_shared?: boolean }
+  ): Promise<GameCheat> {
+    const response = await api.post<GameCheat>(`/games/${gameId}/cheats`, cheat);
+    return response.data;
+  },
+
+  async deleteCheat(gameId: string, cheatId: string): Promise<void> {
+    await api.delete(`/games/${gameId}/cheats/${cheatId}`);
+  },
+
+  // Imports a RetroArch .cht file, entries that don't decode are reported and skipped
+  async importCheats(
+    gameId: string,
+    file: File,
+    shared = false
+  ): Promise<{ imported: number; invalid: string[] }> {
+    const response = await api.post<{ imported: number; invalid: string[] }>(
+      `/games/${gameId}/cheats/import?shared=${shared}`,
+      await file.text(),
+      { headers: { 'Content-Type': 'text/plain' } }
+    );
+    return response.data;
+  },
+
+  async getDocuments(gameId: string): Promise<GameDocument[]> {
+    const response = await api.get<{ documents: GameDocument[] }>(`/games/${gameId}/documents`);
+    return response.data.documents;
+  },
+
+  // Admin only, the title defaults to the file name
+  async uploadDocument(gameId: string, kind: DocumentKind, file: File, title?: string): Promise<GameDocument> {
+    const formData = new FormData();
+    formData.append('kind', kind);
+    if (title) {
+      formData.append('title', title);
+    }
+    formData.append('file', file);
+
+    const response = await api.post<GameDocument>(`/games/${gameId}/documents`, formData, {
+      headers: {
+        'Content-Type': 'multipart/form-data',
+      },
+    });
+    return response.data;
+  },
+
+  async deleteDocument(gameId: string, documentId: string): Promise<void> {
+    await api.delete(`/games/${gameId}/documents/${documentId}`);
+  },
+
+  // The file needs the session token, so viewers show it through an object URL
+  async getDocumentFile(document: GameDocument): Promise<Blob> {
+    const response = await api.get(`/games/${document.game_id}/documents/${document.id}/file`, {
+      responseType: 'blob',
+    });
+    return response.data;
+  },
+
+  async getDocumentThumbnail(document: GameDocument): Promise<Blob> {
+    const response = await api.get(`/games/${document.game_id}/documents/${document.id}/thumbnail`, {
+      responseType: 'blob',
+    });
+    return response.data;
+  },
+
+  getRomUrl(gameId: string, patchId?: string): Promise<string> {
+    return downloadService.getSignedUrl('rom', gameId, patchId);
   },
 
   async scanRoms(): Promise<{ message: string; count: number }> {
@@ -0,0 +1,77 @@
+import { api } from './api';
+import type {
+  ControllerLayout,
+  ControllerMapping,
+  GameInputConfig,
+  InputBindings,
+  InputProfile,
+} from '../types';
+
+// USB ids as browsers put them in Gamepad.id, e.g. "Vendor: 054c Product: 05c4" or "054c-05c4-..."
+const GAMEPAD_USB_ID = /(?:Vendor: ([0-9a-f]{4}) Product: ([0-9a-f]{4}))|^([0-9a-f]{4})-([0-9a-f]{4})-/i;
+
+export const inputService = {
+  async getLayouts(): Promise<ControllerLayout[]> {
+    const response = await api.get<{ layouts: ControllerLayout[] }>('/input/layouts');
+    return response.data.layouts;
+  },
+
+  async getProfiles(layout?: string): Promise<InputProfile[]> {
+    const response = await api.get<{ profiles: InputProfile[] }>('/input/profiles', {
+      params: { layout },
+    });
+    return response.data.profiles;
+  },
+
+  // The first profile of a layout becomes its default
+  async createProfile(profile: {
+    name: string;
+    layout: string;
+    bindings?: InputBindings;
+    is_default?: boolean;
+  }): Promise<InputProfile> {
+    const response = await api.post<InputProfile>('/input/profiles', profile);
+    return response.data;
+  },
+
+  async updateProfile(
+    profileId: string,
+    changes: { name?: string; bindings?: InputBindings; is_default?: boolean }
+  ): Promise<InputProfile> {
+    const response = await api.patch<InputProfile>(`/input/profiles/${profileId}`, changes);
+    return response.data;
+  },
+
+  async deleteProfile(profileId: string): Promise<void> {
+    await api.delete(`/input/profiles/${profileId}`);
+  },
+
+  async getGameInput(gameId: string): Promise<GameInputConfig> {
+    const response = await api.get<GameInputConfig>(`/games/${gameId}/input`);
+    return response.data;
+  },
+
+  // Bindings are merged over the profile's, per key and per pad input
+  async setGameInput(
+    gameId: string,
+    override: { profile_id?: string; bindings?: InputBindings }
+  ): Promise<GameInputConfig> {
+    const response = await api.put<GameInputConfig>(`/games/${gameId}/input`, override);
+    return response.data;
+  },
+
+  async clearGameInput(gameId: string): Promise<void> {
+    await api.delete(`/games/${gameId}/input`);
+  },
+
+  // Looks up the SDL mapping of a connected pad from its USB ids
+  async findController(gamepad: Gamepad): Promise<ControllerMapping | undefined> {
+    const match = GAMEPAD_USB_ID.exec(gamepad.id);
+    if (!match) return undefined;
+
+    const response = await api.get<{ controllers: ControllerMapping[] }>('/input/controllers', {
+      params: { vendor: match[1] ?? match[3], product: match[2] ?? match[4] },
+    });
+    return response.data.controllers[0];
+  },
+};
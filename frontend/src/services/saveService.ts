@@ -1,10 +1,11 @@
 import { api } from './api';
+import { downloadService } from './downloadService';
 import type { SaveStateListResponse } from '../types';
 
 export const saveService = {
-  async getUserSaves(gameId: string): Promise<SaveStateListResponse> {
+  async getUserSaves(gameId: string, patchId?: string): Promise<SaveStateListResponse> {
     const response = await api.get<SaveStateListResponse>('/saves', {
-      params: { game_id: gameId },
+      params: { game_id: gameId, patch_id: patchId },
     });
     return response.data;
   },
@@ -14,10 +15,14 @@ export const saveService = {
     slot: number,
     saveData: Blob,
     screenshot?: Blob,
-    description?: string
+    description?: string,
+    patchId?: string
   ): Promise<void> {
     const formData = new FormData();
     formData.append('game_id', gameId);
+    if (patchId) {
+      formData.append('patch_id', patchId);
+    }
     formData.append('slot', slot.toString());
     formData.append('save_data', saveData);
     if (screenshot) {
@@ -41,6 +46,10 @@ export const saveService = {
     return response.data;
   },
 
+  getSaveStateUrl(saveId: string): Promise<string> {
+    return downloadService.getSignedUrl('save', saveId);
+  },
+
   async deleteSaveState(saveId: string): Promise<void> {
     await api.delete(`/saves/${saveId}`);
   },
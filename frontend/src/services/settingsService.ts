@@ -0,0 +1,54 @@
+import { api } from './api';
+import type {
+  ConsoleSettingsOverride,
+  EffectiveSettings,
+  GameSettingsOverride,
+  SettingsOverride,
+  UserSettings,
+} from '../types';
+
+type SettingsChanges = Partial<
+  Pick<UserSettings, 'theme' | 'crt_effect' | 'scanline_intensity' | 'audio_volume' | 'shader' | 'core_options'>
+>;
+
+export const settingsService = {
+  async getSettings(): Promise<UserSettings> {
+    const response = await api.get<UserSettings>('/settings');
+    return response.data;
+  },
+
+  // core_options replaces all options when present
+  async updateSettings(changes: SettingsChanges): Promise<UserSettings> {
+    const response = await api.patch<UserSettings>('/settings', changes);
+    return response.data;
+  },
+
+  async getConsoleOverrides(): Promise<ConsoleSettingsOverride[]> {
+    const response = await api.get<{ overrides: ConsoleSettingsOverride[] }>('/settings/consoles');
+    return response.data.overrides;
+  },
+
+  async setConsoleOverride(consoleId: string, settings: SettingsOverride): Promise<ConsoleSettingsOverride> {
+    const response = await api.put<ConsoleSettingsOverride>(`/settings/consoles/${consoleId}`, settings);
+    return response.data;
+  },
+
+  async clearConsoleOverride(consoleId: string): Promise<void> {
+    await api.delete(`/settings/consoles/${consoleId}`);
+  },
+
+  async setGameOverride(gameId: string, settings: SettingsOverride): Promise<GameSettingsOverride> {
+    const response = await api.put<GameSettingsOverride>(`/settings/games/${gameId}`, settings);
+    return response.data;
+  },
+
+  async clearGameOverride(gameId: string): Promise<void> {
+    await api.delete(`/settings/games/${gameId}`);
+  },
+
+  // Global settings, console and game overrides resolved over the game's emulator configuration
+  async getGameSettings(gameId: string): Promise<EffectiveSettings> {
+    const response = await api.get<EffectiveSettings>(`/games/${gameId}/settings`);
+    return response.data;
+  },
+};
@@ -0,0 +1,79 @@
+import { api } from './api';
+import type { ResolvedShaderPreset, ShaderPreset } from '../types';
+
+export const shaderService = {
+  // Without a console, every preset; with one, those marked for it or for no console at all
+  async getPresets(consoleId?: string): Promise<ShaderPreset[]> {
+    const response = await api.get<{ presets: ShaderPreset[] }>('/shaders', {
+      params: { console: consoleId },
+    });
+    return response.data.presets;
+  },
+
+  async getPreset(presetId: string): Promise<ResolvedShaderPreset> {
+    const response = await api.get<ResolvedShaderPreset>(`/shaders/${presetId}`);
+    return response.data;
+  },
+
+  // Files are the preset with its passes and textures, or a ZIP of a shader folder.
+  // `preset` picks the preset by path when the files hold several.
+  async uploadPreset(
+    files: File[],
+    options: { name?: string; description?: string; consoles?: string[]; preset?: string } = {}
+  ): Promise<ShaderPreset> {
+    const formData = new FormData();
+    if (options.name) {
+      formData.append('name', options.name);
+    }
+    if (options.description) {
+      formData.append('description', options.description);
+    }
+    if (options.consoles?.length) {
+      formData.append('consoles', options.consoles.join(','));
+    }
+    if (options.preset) {
+      formData.append('preset', options.preset);
+    }
+    for (const file of files) {
+      formData.append('file', file, file.webkitRelativePath || file.name);
+    }
+
+    const response = await api.post<ShaderPreset>('/shaders', formData, {
+      headers: {
+        'Content-Type': 'multipart/form-data',
+      },
+    });
+    return response.data;
+  },
+
+  async updatePreset(
+    presetId: string,
+    changes: { name?: string; description?: string; consoles?: string[] }
+  ): Promise<ShaderPreset> {
+    const response = await api.patch<ShaderPreset>(`/shaders/${presetId}`, changes);
+    return response.data;
+  },
+
+  async deletePreset(presetId: string): Promise<void> {
+    await api.delete(`/shaders/${presetId}`);
+  },
+
+  // Replaces the user's values, each must be within the parameter's range
+  async setParameters(presetId: string, parameters: Record<string, number>): Promise<ResolvedShaderPreset> {
+    const response = await api.put<ResolvedShaderPreset>(`/shaders/${presetId}/parameters`, { parameters });
+    return response.data;
+  },
+
+  async resetParameters(presetId: string): Promise<ResolvedShaderPreset> {
+    const response = await api.delete<ResolvedShaderPreset>(`/shaders/${presetId}/parameters`);
+    return response.data;
+  },
+
+  // A preset, pass source or texture by its path in the preset's folder
+  async getFile(preset: ShaderPreset, path: string): Promise<Blob> {
+    const response = await api.get(`/shaders/${preset.id}/files/${path}`, {
+      responseType: 'blob',
+    });
+    return response.data;
+  },
+};
@@ -1,14 +1,15 @@
 import { create } from 'zustand';
-import type { Game, Console } from '../types';
+import type { Game, GamePatch, Console } from '../types';
 
 interface GameState {
   games: Game[];
   consoles: Console[];
   currentGame: Game | null;
+  currentPatch: GamePatch | null;
   isPlaying: boolean;
   setGames: (games: Game[]) => void;
   setConsoles: (consoles: Console[]) => void;
-  launchGame: (game: Game) => void;
+  launchGame: (game: Game, patch?: GamePatch) => void;
   exitGame: () => void;
 }
 
@@ -16,11 +17,12 @@ export const useGameStore = create<GameState>((set) => ({
   games: [],
   consoles: [],
   currentGame: null,
+  currentPatch: null,
   isPlaying: false,
 
   setGames: (games) => set({ games }),
   setConsoles: (consoles) => set({ consoles }),
 
-  launchGame: (game) => set({ currentGame: game, isPlaying: true }),
-  exitGame: () => set({ currentGame: null, isPlaying: false }),
+  launchGame: (game, patch) => set({ currentGame: game, currentPatch: patch ?? null, isPlaying: true }),
+  exitGame: () => set({ currentGame: null, currentPatch: null, isPlaying: false }),
 }));
@@ -0,0 +1,23 @@
+export interface BiosFile {
+  id: string;
+  console_id: string;
+  filename: string;
+  description?: string;
+  required: boolean;
+  choice_group?: string;
+  md5_hashes: string[];
+  md5?: string;
+  size_bytes?: number;
+  uploaded_by?: string;
+  uploaded_at?: string;
+}
+
+export interface ConsoleBiosStatus {
+  console_id: string;
+  ready: boolean;
+  files: BiosFile[];
+}
+
+export interface BiosStatusResponse {
+  consoles: ConsoleBiosStatus[];
+}
@@ -0,0 +1,6 @@
+export type DownloadKind = 'rom' | 'save' | 'bios';
+
+export interface DownloadUrl {
+  url: string;
+  expires_at: string;
+}
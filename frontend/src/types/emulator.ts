@@ -15,3 +15,9 @@ export interface GameControls {
   start: string;
   select: string;
 }
+
+export interface EmulatorJsInfo {
+  version: string;
+  path_to_data: string;
+  installed_versions: string[];
+}
@@ -1,3 +1,10 @@
+export interface EmulatorConfig {
+  core_options: Record<string, string>;
+  shader?: string;
+  threads?: boolean;
+  required_bios: string[];
+}
+
 export interface Console {
   id: string;
   name: string;
@@ -6,6 +13,108 @@ export interface Console {
   icon_url?: string;
   emulator_core: string;
   supported_extensions: string[];
+  emulator_config: EmulatorConfig;
+}
+
+// Per-game changes on top of the console's config
+export interface EmulatorOverride {
+  core?: string;
+  core_options: Record<string, string>;
+  shader?: string;
+  threads?: boolean;
+}
+
+export interface GameEmulatorConfig {
+  game_id: string;
+  console_id: string;
+  core: string;
+  core_options: Record<string, string>;
+  shader?: string;
+  threads: boolean;
+  required_bios: string[];
+  bios_id?: string;
+  missing_bios: string[];
+  overrides?: EmulatorOverride;
+}
+
+export interface GameTag {
+  id: string;
+  kind: 'genre' | 'theme' | 'feature' | 'user';
+  name: string;
+  slug: string;
+}
+
+export type PatchFormat = 'ips' | 'bps' | 'ups';
+
+// A soft patch (translation, romhack) playable as its own entry
+export interface GamePatch {
+  id: string;
+  name: string;
+  format: PatchFormat;
+}
+
+// Who sees a game besides admins: everyone, its uploader only, or the users and groups it is shared with
+export type GameVisibilityLevel = 'instance' | 'private' | 'shared';
+
+export interface GameVisibility {
+  game_id: string;
+  visibility: GameVisibilityLevel;
+  uploaded_by?: string;
+  user_ids: string[];
+  group_ids: string[];
+}
+
+// Another regional release or revision of the same title
+export interface GameVariant {
+  id: string;
+  title: string;
+  region?: string;
+  rom_filename: string;
+}
+
+export type CheatFormat = 'raw' | 'game_genie' | 'action_replay' | 'gameshark';
+
+// One decoded write, values are uppercase hex
+export interface CheatWrite {
+  address: string;
+  value: string;
+  compare?: string;
+}
+
+// A cheat code, private to the user who added it unless shared
+export interface GameCheat {
+  id: string;
+  game_id: string;
+  user_id: string;
+  description: string;
+  format: CheatFormat;
+  // Normalised code as passed to the emulator, several codes joined with '+'
+  code: string;
+  decoded: CheatWrite[];
+  is_shared: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export type DocumentKind = 'manual' | 'map' | 'faq' | 'other';
+
+// A scanned manual, map or FAQ: a PDF, an image or a plain text file
+export interface GameDocument {
+  id: string;
+  game_id: string;
+  kind: DocumentKind;
+  title: string;
+  original_filename: string;
+  content_type: string;
+  file_size: number;
+  sha256: string;
+  // PDFs only
+  page_count?: number;
+  uploaded_by?: string;
+  created_at: string;
+  // Authenticated endpoints, fetch them through gameService
+  url: string;
+  thumbnail_url?: string;
 }
 
 export interface Game {
@@ -18,9 +127,19 @@ export interface Game {
   description?: string;
   release_year?: number;
   developer?: string;
-  genre?: string;
+  region?: string;
+  tags: GameTag[];
+  patches: GamePatch[];
+  // Other releases of the title, the listing shows the one matching the preferred regions
+  variants: GameVariant[];
+  title_id?: string;
+  // The user's own and shared cheats, only filled when a single game is fetched
+  cheats: GameCheat[];
+  // Manuals, maps and FAQs, only filled when a single game is fetched
+  documents: GameDocument[];
   created_at: string;
   uploaded_by?: string;
+  visibility: GameVisibilityLevel;
 }
 
 export interface GameListResponse {
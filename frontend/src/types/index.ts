@@ -4,3 +4,7 @@ export * from './achievement';
 export * from './desktop';
 export * from './emulator';
 export * from './save';
+export * from './download';
+export * from './bios';
+export * from './input';
+export * from './shader';
@@ -0,0 +1,60 @@
+// What triggers one button: a KeyboardEvent.code and/or an SDL controller input
+export interface InputBinding {
+  keyboard?: string;
+  // SDL button ('a', 'dpup', 'leftshoulder') or axis half ('+leftx', '-lefty')
+  gamepad?: string;
+}
+
+export type InputBindings = Record<string, InputBinding>;
+
+export interface LayoutButton {
+  id: string;
+  label: string;
+  // RetroPad input as EmulatorJS numbers its controls
+  retropad: number;
+}
+
+export interface ControllerLayout {
+  id: string;
+  name: string;
+  buttons: LayoutButton[];
+}
+
+export interface InputProfile {
+  id: string;
+  user_id: string;
+  name: string;
+  layout: string;
+  bindings: InputBindings;
+  is_default: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface GameInputOverride {
+  user_id: string;
+  game_id: string;
+  profile_id?: string;
+  bindings: InputBindings;
+  updated_at: string;
+}
+
+// Bindings a game starts with: the chosen or default profile plus the game's overrides
+export interface GameInputConfig {
+  game_id: string;
+  layout: ControllerLayout;
+  profile_id?: string;
+  bindings: InputBindings;
+  overrides?: GameInputOverride;
+}
+
+// A pad from SDL's gamecontrollerdb.txt
+export interface ControllerMapping {
+  guid: string;
+  platform: string;
+  name: string;
+  mapping: Record<string, string>;
+  vendor_id?: string;
+  product_id?: string;
+  updated_at: string;
+}
@@ -2,6 +2,7 @@ export interface SaveState {
   id: string;
   user_id: string;
   game_id: string;
+  patch_id?: string;
   slot: number;
   save_data_filename: string;
   screenshot_filename?: string;
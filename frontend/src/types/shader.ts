@@ -0,0 +1,51 @@
+export type ShaderFormat = 'glslp' | 'slangp';
+
+export interface ShaderPass {
+  // Path of the pass's source in the preset's folder
+  shader: string;
+  // Pass settings without their index, e.g. filter_linear, scale_type
+  options?: Record<string, string>;
+}
+
+export interface ShaderTexture {
+  path: string;
+  options?: Record<string, string>;
+}
+
+// A #pragma parameter declared by one of the passes
+export interface ShaderParameter {
+  name: string;
+  description: string;
+  default: number;
+  minimum: number;
+  maximum: number;
+  step: number;
+}
+
+export interface ShaderPreset {
+  id: string;
+  // What the shader field of settings refers to the preset by
+  name: string;
+  description: string | null;
+  format: ShaderFormat;
+  preset_path: string;
+  passes: ShaderPass[];
+  textures: Record<string, ShaderTexture>;
+  parameters: ShaderParameter[];
+  preset_values: Record<string, number>;
+  files: string[];
+  total_size: number;
+  uploaded_by: string | null;
+  created_at: string;
+  updated_at: string;
+  // Empty when the preset suits every console
+  consoles: string[];
+  // Authenticated, files are relative to it
+  files_url: string;
+}
+
+export interface ResolvedShaderPreset extends ShaderPreset {
+  // The user's value, else the preset's, else the declared default
+  values: Record<string, number>;
+  user_values: Record<string, number>;
+}
@@ -1,3 +1,5 @@
+import type { ResolvedShaderPreset } from './shader';
+
 export interface User {
   id: string;
   username: string;
@@ -30,6 +32,51 @@ export interface UserSettings {
   crt_effect: boolean;
   scanline_intensity: number;
   audio_volume: number;
-  key_mappings?: Record<string, string>;
+  // null uses the emulator configuration's shader, '' means no shader
+  shader: string | null;
+  core_options: Record<string, string>;
+  preferred_regions: string[];
+  updated_at: string;
+}
+
+// Per-console or per-game changes to the global settings, absent fields are inherited
+export interface SettingsOverride {
+  crt_effect?: boolean;
+  scanline_intensity?: number;
+  shader?: string;
+  audio_volume?: number;
+  core_options?: Record<string, string>;
+}
+
+export interface ConsoleSettingsOverride {
+  user_id: string;
+  console_id: string;
+  settings: SettingsOverride;
+  updated_at: string;
+}
+
+export interface GameSettingsOverride {
+  user_id: string;
+  game_id: string;
+  settings: SettingsOverride;
   updated_at: string;
 }
+
+export type SettingsSource = 'emulator' | 'global' | 'console' | 'game';
+
+export interface EffectiveSettings {
+  game_id: string;
+  console_id: string;
+  theme: string;
+  crt_effect: boolean;
+  scanline_intensity: number;
+  shader: string | null;
+  // The uploaded preset shader names, null for EmulatorJS's built-in shaders
+  shader_preset: ResolvedShaderPreset | null;
+  audio_volume: number;
+  core_options: Record<string, string>;
+  // Core options are listed as `core_options.<name>`
+  sources: Record<string, SettingsSource>;
+  console_overrides: SettingsOverride | null;
+  game_overrides: SettingsOverride | null;
+}